@@ -192,10 +192,26 @@ impl From<csv::Error> for CliError {
 
 impl From<io::Error> for CliError {
     fn from(err: io::Error) -> CliError {
-        CliError::Io(err)
+        match err.get_ref() {
+            Some(inner) if inner.is::<NetworkIoError>() => CliError::Network(inner.to_string()),
+            _ => CliError::Io(err),
+        }
+    }
+}
+
+/// NetworkIoError is a network error returned as an io::Error, e.g. when a
+/// remote input couldn't be downloaded, so its still reported as a network error
+#[derive(Debug)]
+pub struct NetworkIoError(pub String);
+
+impl fmt::Display for NetworkIoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
+impl std::error::Error for NetworkIoError {}
+
 impl From<String> for CliError {
     fn from(err: String) -> CliError {
         CliError::Other(err)
@@ -0,0 +1,299 @@
+#![allow(clippy::cast_precision_loss)]
+static USAGE: &str = r#"
+Does streaming compression/decompression of the input using the gzip, zstd, bzip2
+or Snappy compression formats.
+
+It has four modes:
+    compress:     Compress the input (the default mode).
+    --decompress: Decompress the input.
+    --check:      Quickly check if the input is compressed by inspecting its first bytes.
+                  Returns exitcode 0 if the input starts with valid compressed data,
+                  exitcode 1 otherwise.
+    --validate:   Validate if the ENTIRE input is a valid compressed file.
+                  Returns exitcode 0 if valid, exitcode 1 otherwise.
+
+When compressing, the codec is set with --codec. If not set, it's inferred from the
+--output file extension (".gz", ".zst", ".bz2" or ".sz"), defaulting to gzip.
+When decompressing, checking or validating, the codec is inferred from the first bytes
+of the input, unless --codec is set.
+
+Note that most qsv commands already automatically decompress files with a ".gz", ".zst",
+".bz2" or ".sz" extension, and compressed data on stdin. They will also automatically
+compress the output if the --output file has one of these extensions.
+
+Also, this command is not specific to CSV data, it can compress/decompress ANY file.
+
+For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_compress.rs.
+
+Usage:
+    qsv compress [options] [<input>]
+    qsv compress --help
+
+compress arguments:
+    <input>                The input file to compress/decompress. This can be a local file,
+                           stdin, or a URL (http and https schemes supported).
+
+compress options:
+    -d, --decompress       Decompress the input.
+    --check                Check if the input is compressed.
+    --validate             Validate if the entire input is a valid compressed file.
+    --codec <codec>        The compression codec to use. One of gzip, zstd, bzip2 or snappy.
+    --level <arg>          The compression level. gzip: 0-9, zstd: 1-22, bzip2: 1-9.
+                           When not set, the codec's default level is used.
+                           Ignored for snappy, which has no compression levels.
+    -j, --jobs <arg>       The number of jobs to run in parallel when compressing with
+                           snappy. When not set, its set to the number of CPUs - 1.
+                           The other codecs are single-threaded.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <output> instead of stdout.
+    -Q, --quiet            Suppress status messages to stderr.
+"#;
+
+use std::{
+    fs,
+    io::{self, BufRead, Read, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    cmd::snappy,
+    config::{self, Codec, Config},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_decompress: bool,
+    flag_check:      bool,
+    flag_validate:   bool,
+    flag_codec:      Option<String>,
+    flag_level:      Option<u32>,
+    flag_jobs:       Option<usize>,
+    flag_output:     Option<String>,
+    flag_quiet:      bool,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if [args.flag_decompress, args.flag_check, args.flag_validate]
+        .iter()
+        .filter(|&&mode| mode)
+        .count()
+        > 1
+    {
+        return fail_incorrectusage_clierror!(
+            "Only one of --decompress, --check or --validate can be used."
+        );
+    }
+
+    let codec_arg = match args.flag_codec {
+        Some(ref codec_name) => {
+            let Ok(codec) = Codec::from_str(codec_name) else {
+                return fail_incorrectusage_clierror!(
+                    "Invalid codec `{codec_name}`. Supported codecs are: gzip, zstd, bzip2, \
+                     snappy."
+                );
+            };
+            Some(codec)
+        },
+        None => None,
+    };
+
+    // Config downloads remote inputs to a temp file. We open the file ourselves, as we
+    // don't want Config to transparently decompress it
+    // the input size isn't known when reading from stdin
+    let rconfig = Config::new(&args.arg_input);
+    let input_bytes;
+    let mut input_reader: Box<dyn BufRead + Send> = match rconfig.path {
+        Some(ref path) => {
+            let file = fs::File::open(path)?;
+            input_bytes = Some(file.metadata()?.len());
+            Box::new(io::BufReader::with_capacity(
+                config::DEFAULT_RDR_BUFFER_CAPACITY,
+                file,
+            ))
+        },
+        None => {
+            input_bytes = None;
+            Box::new(io::BufReader::with_capacity(
+                config::DEFAULT_RDR_BUFFER_CAPACITY,
+                io::stdin(),
+            ))
+        },
+    };
+
+    if args.flag_check {
+        let magic_codec = Codec::from_magic(input_reader.fill_buf()?);
+        let check_ok = magic_codec.is_compressed()
+            && codec_arg.map_or(true, |c| c == magic_codec)
+            && check(magic_codec, input_reader);
+        if !check_ok {
+            return fail!(match codec_arg {
+                Some(codec) => format!("Not a {codec:?} file."),
+                None => "Not a compressed file.".to_string(),
+            });
+        }
+        if !args.flag_quiet {
+            winfo!("{magic_codec:?} file.");
+        }
+        return Ok(());
+    }
+
+    let output_writer: Box<dyn Write + Send + 'static> = match &args.flag_output {
+        Some(output_path) => Box::new(io::BufWriter::with_capacity(
+            config::DEFAULT_WTR_BUFFER_CAPACITY,
+            fs::File::create(output_path)?,
+        )),
+        None => Box::new(io::BufWriter::with_capacity(
+            config::DEFAULT_WTR_BUFFER_CAPACITY,
+            io::stdout(),
+        )),
+    };
+
+    if args.flag_decompress || args.flag_validate {
+        if args.flag_validate && args.arg_input.is_none() {
+            return fail_incorrectusage_clierror!("stdin is not supported by compress --validate.");
+        }
+        let codec = match codec_arg {
+            Some(codec) => codec,
+            None => Codec::from_magic(input_reader.fill_buf()?),
+        };
+        if !codec.is_compressed() {
+            return fail_clierror!("Not a compressed file.");
+        }
+
+        let decompressed_bytes = if args.flag_validate {
+            let Ok(decompressed_bytes) = decompress(codec, input_reader, io::sink()) else {
+                return fail_clierror!("Not a valid {codec:?} file.");
+            };
+            decompressed_bytes
+        } else {
+            decompress(codec, input_reader, output_writer)?
+        };
+
+        if !args.flag_quiet {
+            let mode = if args.flag_validate {
+                "Valid"
+            } else {
+                "Decompressed"
+            };
+            match input_bytes {
+                Some(input_bytes) if input_bytes > 0 => winfo!(
+                    "{mode} {codec:?} file. Compressed bytes: {}, Decompressed bytes: {}, \
+                     Compression ratio: {:.3}:1",
+                    indicatif::HumanBytes(input_bytes),
+                    indicatif::HumanBytes(decompressed_bytes),
+                    decompressed_bytes as f64 / input_bytes as f64,
+                ),
+                _ => winfo!(
+                    "{mode} {codec:?} file. Decompressed bytes: {}",
+                    indicatif::HumanBytes(decompressed_bytes),
+                ),
+            }
+        }
+        return Ok(());
+    }
+
+    // compress mode
+    let codec = codec_arg.unwrap_or_else(|| {
+        args.flag_output
+            .as_ref()
+            .map(|output| Codec::from_path(Path::new(output)))
+            .filter(|codec| codec.is_compressed())
+            .unwrap_or(Codec::Gzip)
+    });
+
+    if let Some(level) = args.flag_level {
+        check_level(codec, level)?;
+    }
+
+    // the number of bytes actually read, as the input size isn't known for stdin
+    let input_bytes = if codec == Codec::Snappy {
+        // use the multithreaded snappy compressor of the snappy command
+        let mut jobs = util::njobs(args.flag_jobs);
+        if jobs > 1 {
+            jobs -= 1; // save one thread for other tasks
+        }
+        snappy::compress(input_reader, output_writer, jobs, gzp::BUFSIZE * 2)?
+    } else {
+        let mut encoder = codec.encoder(output_writer, args.flag_level)?;
+        let input_bytes = io::copy(&mut input_reader, &mut encoder)?;
+        // finish the compressed stream before we check the size of the output file
+        encoder.finish()?;
+        input_bytes
+    };
+
+    let compressed_bytes = if let Some(path) = &args.flag_output {
+        fs::metadata(path)?.len()
+    } else {
+        0
+    };
+    if !args.flag_quiet && compressed_bytes > 0 && input_bytes > 0 {
+        winfo!(
+            "{codec:?} compression successful. Compressed bytes: {}, Decompressed bytes: {}, \
+             Compression ratio: {:.3}:1, Space savings: {} - {:.2}%",
+            indicatif::HumanBytes(compressed_bytes),
+            indicatif::HumanBytes(input_bytes),
+            input_bytes as f64 / compressed_bytes as f64,
+            indicatif::HumanBytes(
+                input_bytes
+                    .checked_sub(compressed_bytes)
+                    .unwrap_or_default()
+            ),
+            (1.0 - (compressed_bytes as f64 / input_bytes as f64)) * 100.0
+        );
+    }
+
+    Ok(())
+}
+
+// check the compression level is in the range of the codec, as the bzip2 encoder
+// panics with an invalid level
+fn check_level(codec: Codec, level: u32) -> CliResult<()> {
+    let (min_level, max_level) = match codec {
+        Codec::Gzip => (0, 9),
+        Codec::Bzip2 => (1, 9),
+        Codec::Zstd => (1, *zstd::compression_level_range().end() as u32),
+        // snappy has no compression levels
+        Codec::Snappy | Codec::None => return Ok(()),
+    };
+    if level < min_level || level > max_level {
+        return fail_incorrectusage_clierror!(
+            "Invalid {codec:?} compression level {level}. It must be from {min_level} to \
+             {max_level}."
+        );
+    }
+    Ok(())
+}
+
+// streaming decompression. Used by both --decompress and --validate,
+// the latter decompressing to sink (i.e. /dev/null)
+fn decompress<R: Read + Send + 'static, W: Write>(
+    codec: Codec,
+    src: R,
+    mut dst: W,
+) -> CliResult<u64> {
+    let mut decoder = codec.decoder(src)?;
+    match io::copy(&mut decoder, &mut dst) {
+        Ok(decompressed_bytes) => Ok(decompressed_bytes),
+        Err(err) => fail_clierror!("Error decompressing {codec:?} data: {err:?}"),
+    }
+}
+
+// quickly check if the input is valid compressed data
+// note that the fn only decompresses the first 50 bytes of the input
+// and does not check the entire input for validity
+fn check<R: Read + Send + 'static>(codec: Codec, src: R) -> bool {
+    let Ok(decoder) = codec.decoder(src) else {
+        return false;
+    };
+    let mut buffer = Vec::with_capacity(50);
+    decoder.take(50).read_to_end(&mut buffer).is_ok()
+}
@@ -87,10 +87,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 (idx.count(), 0)
             },
             None => {
-                // if --no-polars or its a snappy compressed file, use the
-                // regular CSV reader
+                // if --no-polars or its a compressed or Parquet/Arrow file,
+                // use the regular CSV reader
                 #[cfg(feature = "polars")]
-                if args.flag_no_polars || conf.is_snappy() {
+                if args.flag_no_polars || conf.is_compressed() || conf.is_columnar() {
                     count_input(&conf, args.flag_width)?
                 } else {
                     polars_count_input(&conf, args.flag_low_memory)?
@@ -172,10 +172,10 @@ pub fn polars_count_input(
 
     let filepath = if is_stdin {
         let mut temp_file = tempfile::Builder::new().suffix(".csv").tempfile()?;
-        let stdin = std::io::stdin();
-        let mut stdin_handle = stdin.lock();
-        std::io::copy(&mut stdin_handle, &mut temp_file)?;
-        drop(stdin_handle);
+        // io_reader transparently decompresses compressed stdin
+        let mut stdin_rdr = conf.io_reader()?;
+        std::io::copy(&mut stdin_rdr, &mut temp_file)?;
+        drop(stdin_rdr);
 
         let (_, tempfile_pb) =
             temp_file.keep().or(Err(
@@ -10,6 +10,12 @@ than memory CSV files. This will make dedup run in streaming mode with constant
 
 Either way, the output will not only be deduplicated, it will also be sorted.
 
+For CSV files that are too large to sort in memory, the --external option can be used
+instead. It streams the CSV and keeps track of the --select key columns of the rows it
+has seen in an on-disk hash table (the same one used by the extdedup command), so it
+runs in constant memory, keeps the first occurrence of each key, and retains the input
+row order (i.e. the output is NOT sorted).
+
 A duplicate count will also be sent to <stderr>.
 
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_dedup.rs.
@@ -28,6 +34,12 @@ dedup options:
     --sorted                   The input is already sorted. Do not load the CSV into
                                memory to sort it first. Meant to be used in tandem and
                                after an extsort.
+    --external                 Dedup without loading the CSV into memory, keeping the
+                               first occurrence of each key in the input order.
+                               Cannot be used with --sorted.
+    --memory-limit <arg>       The maximum amount of memory to buffer the on-disk hash
+                               table with --external. This is a percentage of total
+                               memory. [default: 10]
     -D, --dupes-output <file>  Write duplicates to <file>.
     -H, --human-readable       Comma separate duplicate count.
     -j, --jobs <arg>           The number of jobs to run in parallel when sorting
@@ -57,13 +69,17 @@ use csv::ByteRecord;
 use rayon::slice::ParallelSliceMut;
 use serde::Deserialize;
 use simdutf8::basic::from_utf8;
+use sysinfo::System;
 
 use crate::{
     cmd::sort::{iter_cmp, iter_cmp_num},
     config::{Config, Delimiter},
-    select::SelectColumns,
+    odhtcache::ExtDedupCache,
+    select::{SelectColumns, Selection},
     util, CliResult,
 };
+
+const MEMORY_LIMITED_BUFFER: u64 = 100 * 1_000_000; // 100 MB
 #[derive(Deserialize)]
 struct Args {
     arg_input:           Option<String>,
@@ -71,6 +87,8 @@ struct Args {
     flag_numeric:        bool,
     flag_ignore_case:    bool,
     flag_sorted:         bool,
+    flag_external:       bool,
+    flag_memory_limit:   Option<u8>,
     flag_dupes_output:   Option<String>,
     flag_output:         Option<String>,
     flag_no_headers:     bool,
@@ -81,6 +99,7 @@ struct Args {
     flag_memcheck:       bool,
 }
 
+#[derive(Clone, Copy)]
 enum ComparisonMode {
     Numeric,
     IgnoreCase,
@@ -98,6 +117,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         ComparisonMode::Normal
     };
 
+    if args.flag_external && args.flag_sorted {
+        return fail_incorrectusage_clierror!("--external cannot be used with --sorted.");
+    }
+
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers)
@@ -117,7 +140,33 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     rconfig.write_headers(&mut rdr, &mut wtr)?;
     let mut dupe_count = 0_usize;
 
-    if args.flag_sorted {
+    if args.flag_external {
+        // memory buffer to use for the on-disk hash table,
+        // as a percentage of the total memory
+        let mem_limited_buffer = if sysinfo::IS_SUPPORTED_SYSTEM {
+            let mut sys = System::new();
+            sys.refresh_memory();
+            sys.total_memory() * u8::min(args.flag_memory_limit.unwrap_or(10), 50) as u64 / 100
+        } else {
+            MEMORY_LIMITED_BUFFER
+        };
+        log::info!("{mem_limited_buffer} bytes used for memory buffer for on-disk hash table...");
+
+        let mut dedup_cache = ExtDedupCache::new(mem_limited_buffer);
+        let mut record = ByteRecord::new();
+        let mut key = String::new();
+        while rdr.read_byte_record(&mut record)? {
+            dedup_key(&record, &sel, compare_mode, &mut key);
+            if dedup_cache.insert(&key) {
+                wtr.write_byte_record(&record)?;
+            } else {
+                dupe_count += 1;
+                if dupes_output {
+                    dupewtr.write_byte_record(&record)?;
+                }
+            }
+        }
+    } else if args.flag_sorted {
         let mut record = ByteRecord::new();
         let mut next_record = ByteRecord::new();
 
@@ -247,6 +296,37 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+/// Builds the --external dedup key of a record from its selected columns, so that
+/// records that compare as equal with the comparison mode have the same key.
+fn dedup_key(record: &ByteRecord, sel: &Selection, compare_mode: ComparisonMode, key: &mut String) {
+    key.clear();
+    for (i, field) in sel.select(record).enumerate() {
+        // separate the fields with the ASCII unit separator
+        if i > 0 {
+            key.push('\x1f');
+        }
+        let field = String::from_utf8_lossy(field);
+        match compare_mode {
+            ComparisonMode::Normal => key.push_str(&field),
+            ComparisonMode::IgnoreCase => key.push_str(&field.to_lowercase()),
+            ComparisonMode::Numeric => {
+                // normalize numbers, so 1, 1.0 & 01 have the same key
+                if let Ok(int) = atoi_simd::parse::<i64>(field.as_bytes()) {
+                    key.push_str(&int.to_string());
+                } else if let Ok(float) = field.parse::<f64>() {
+                    if float.fract() == 0.0 && float.abs() < 9e15 {
+                        key.push_str(&(float as i64).to_string());
+                    } else {
+                        key.push_str(&float.to_string());
+                    }
+                } else {
+                    key.push_str(&field);
+                }
+            },
+        }
+    }
+}
+
 /// Try comparing `a` and `b` ignoring the case
 #[inline]
 pub fn iter_cmp_ignore_case<'a, L, R>(mut a: L, mut b: R) -> cmp::Ordering
@@ -32,6 +32,17 @@ sort the result by the first and second column:
 Find the difference between two CSVs, but do not output headers in the result:
     qsv diff --no-headers-output left.csv right.csv
 
+List the changed columns of the modified records with their old and new values,
+along with the added and removed records:
+    qsv diff --format changes left.csv right.csv
+
+Output the difference as JSON Lines, with one patch-style object per added,
+removed or modified record:
+    qsv diff --format jsonl left.csv right.csv
+
+Only output the number of added, removed and modified records:
+    qsv diff --format summary left.csv right.csv
+
 Find the difference between two CSVs. Both CSVs have no headers, but the result should have
 headers, so generic headers will be used in the form of: _col_1, _col_2, etc.:
     qsv diff --no-headers-left --no-headers-right left.csv right.csv
@@ -60,6 +71,7 @@ diff options:
                                 Must be a single character. (default: ,)
     --delimiter-output <arg>    The field delimiter for writing the CSV diff result.
                                 Must be a single character. (default: ,)
+                                Only used by the csv, changes and summary formats.
     -k, --key <arg...>          The column indices that uniquely identify a record
                                 as a comma separated list of indices, e.g. 0,1,2.
                                 (default: 0)
@@ -70,6 +82,24 @@ diff options:
                                 but have different content) will always be kept together
                                 in the sorted diff result and so won't be sorted
                                 independently from each other.
+    --format <arg>              The format of the diff result. Valid values are:
+                                  csv - the records of both CSVs that differ, with a
+                                        "diffresult" column of "+" (added) or "-"
+                                        (removed). A modified record is a "-" record
+                                        followed by a "+" record with the same key.
+                                  changes - a CSV with the key columns, a "change" column
+                                        (added, removed or modified) and, for modified
+                                        records, a row for each changed column with
+                                        its "field", "old_value" and "new_value".
+                                  json - a JSON array of patch-style objects, e.g.
+                                        {"op":"modify","key":{"id":"1"},
+                                         "changes":{"name":{"old":"a","new":"b"}}}.
+                                        Added and removed records have an "add" or
+                                        "remove" op, and the whole "record".
+                                  jsonl - the same objects as json, one per line.
+                                  summary - a CSV with the number of "added", "removed"
+                                        and "modified" records.
+                                [default: csv]
     -j, --jobs <arg>            The number of jobs to run in parallel.
                                 When not set, the number of jobs is set to the number
                                 of CPUs detected.
@@ -79,13 +109,17 @@ Common options:
     -o, --output <file>         Write output to <file> instead of stdout.
 "#;
 
-use std::io::{self, Write};
+use std::{
+    io::{self, Write},
+    str::FromStr,
+};
 
 use csv_diff::{
     csv_diff::CsvByteDiffBuilder, csv_headers::Headers, diff_result::DiffByteRecords,
     diff_row::DiffByteRecord,
 };
 use serde::Deserialize;
+use serde_json::{json, Map, Value};
 
 use super::rename::rename_headers_all_generic;
 use crate::{
@@ -108,6 +142,33 @@ struct Args {
     flag_delimiter_output:  Option<Delimiter>,
     flag_key:               Option<String>,
     flag_sort_columns:      Option<String>,
+    flag_format:            String,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum DiffFormat {
+    Csv,
+    Changes,
+    Json,
+    Jsonl,
+    Summary,
+}
+
+impl FromStr for DiffFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(DiffFormat::Csv),
+            "changes" => Ok(DiffFormat::Changes),
+            "json" => Ok(DiffFormat::Json),
+            "jsonl" => Ok(DiffFormat::Jsonl),
+            "summary" => Ok(DiffFormat::Summary),
+            _ => Err(format!(
+                "Invalid --format: {s}. Valid values are: csv, changes, json, jsonl, summary."
+            )),
+        }
+    }
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -127,6 +188,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         );
     }
 
+    let diff_format: DiffFormat = match args.flag_format.parse() {
+        Ok(diff_format) => diff_format,
+        Err(e) => return fail_incorrectusage_clierror!("{e}"),
+    };
+
     let primary_key_cols = match args.flag_key {
         None => vec![0],
         Some(s) => s
@@ -146,9 +212,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         })
         .transpose()?;
 
-    let wtr = Config::new(&args.flag_output)
-        .delimiter(args.flag_delimiter_output)
-        .writer()?;
+    let wconfig = Config::new(&args.flag_output).delimiter(args.flag_delimiter_output);
     let csv_rdr_left = rconfig_left.reader()?;
     let csv_rdr_right = rconfig_right.reader()?;
 
@@ -156,7 +220,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     util::njobs(args.flag_jobs);
 
     let Ok(csv_diff) = CsvByteDiffBuilder::new()
-        .primary_key_columns(primary_key_cols)
+        .primary_key_columns(primary_key_cols.clone())
         .build()
     else {
         return fail_clierror!("Cannot instantiate diff");
@@ -177,8 +241,217 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         },
     }
 
-    let mut csv_diff_writer = CsvDiffWriter::new(wtr, args.flag_no_headers_output);
-    Ok(csv_diff_writer.write_diff_byte_records(diff_byte_records)?)
+    match diff_format {
+        DiffFormat::Csv => {
+            let mut csv_diff_writer =
+                CsvDiffWriter::new(wconfig.writer()?, args.flag_no_headers_output);
+            Ok(csv_diff_writer.write_diff_byte_records(diff_byte_records)?)
+        },
+        DiffFormat::Changes => {
+            let mut wtr = wconfig.writer()?;
+            write_changes(
+                &mut wtr,
+                diff_byte_records,
+                &primary_key_cols,
+                args.flag_no_headers_output,
+            )?;
+            Ok(wtr.flush()?)
+        },
+        DiffFormat::Json | DiffFormat::Jsonl => {
+            let mut wtr = io::BufWriter::new(wconfig.io_writer()?);
+            write_patches(
+                &mut wtr,
+                diff_byte_records,
+                &primary_key_cols,
+                diff_format == DiffFormat::Jsonl,
+            )?;
+            Ok(wtr.flush()?)
+        },
+        DiffFormat::Summary => {
+            let (mut added, mut removed, mut modified) = (0_u64, 0_u64, 0_u64);
+            for dbr in diff_byte_records {
+                match dbr {
+                    DiffByteRecord::Add(_) => added += 1,
+                    DiffByteRecord::Delete(_) => removed += 1,
+                    DiffByteRecord::Modify { .. } => modified += 1,
+                }
+            }
+            let mut wtr = wconfig.writer()?;
+            if !args.flag_no_headers_output {
+                wtr.write_record(["added", "removed", "modified"])?;
+            }
+            wtr.write_record([added.to_string(), removed.to_string(), modified.to_string()])?;
+            Ok(wtr.flush()?)
+        },
+    }
+}
+
+/// the column names of the diff result - the headers of the left or right CSV,
+/// or generic names (_col_1, _col_2, etc.) if both CSVs have no headers
+fn column_names(headers: &Headers, num_columns: Option<usize>) -> Vec<String> {
+    match headers.headers_left().or_else(|| headers.headers_right()) {
+        Some(bh) => bh
+            .iter()
+            .map(|h| String::from_utf8_lossy(h).to_string())
+            .collect(),
+        None => (1..=num_columns.unwrap_or_default())
+            .map(|i| format!("_col_{i}"))
+            .collect(),
+    }
+}
+
+fn column_name(names: &[String], i: usize) -> String {
+    names
+        .get(i)
+        .cloned()
+        .unwrap_or_else(|| format!("_col_{}", i + 1))
+}
+
+/// the key columns' values of the record, as a JSON object
+fn key_object(record: &csv::ByteRecord, key_cols: &[usize], names: &[String]) -> Value {
+    let mut key = Map::with_capacity(key_cols.len());
+    for &i in key_cols {
+        key.insert(
+            column_name(names, i),
+            json!(String::from_utf8_lossy(record.get(i).unwrap_or_default())),
+        );
+    }
+    Value::Object(key)
+}
+
+fn record_object(record: &csv::ByteRecord, names: &[String]) -> Value {
+    Value::Object(
+        names
+            .iter()
+            .zip(record)
+            .map(|(name, field)| (name.clone(), json!(String::from_utf8_lossy(field))))
+            .collect(),
+    )
+}
+
+/// writes a row for each added & removed record, and a row for each changed
+/// column of the modified records
+fn write_changes<W: Write>(
+    wtr: &mut csv::Writer<W>,
+    diff_byte_records: DiffByteRecords,
+    key_cols: &[usize],
+    no_headers: bool,
+) -> csv::Result<()> {
+    let names = column_names(diff_byte_records.headers(), diff_byte_records.num_columns());
+    if !no_headers {
+        let mut header: Vec<String> = key_cols.iter().map(|&i| column_name(&names, i)).collect();
+        header.extend(["change", "field", "old_value", "new_value"].map(String::from));
+        wtr.write_record(header)?;
+    }
+
+    let key_fields = |record: &csv::ByteRecord| -> Vec<Vec<u8>> {
+        key_cols
+            .iter()
+            .map(|&i| record.get(i).unwrap_or_default().to_vec())
+            .collect()
+    };
+
+    for dbr in diff_byte_records {
+        match dbr {
+            DiffByteRecord::Add(add) => {
+                let mut row = key_fields(add.byte_record());
+                row.extend([b"added".to_vec(), vec![], vec![], vec![]]);
+                wtr.write_record(row)?;
+            },
+            DiffByteRecord::Delete(del) => {
+                let mut row = key_fields(del.byte_record());
+                row.extend([b"removed".to_vec(), vec![], vec![], vec![]]);
+                wtr.write_record(row)?;
+            },
+            DiffByteRecord::Modify {
+                delete,
+                add,
+                field_indices,
+            } => {
+                let key = key_fields(add.byte_record());
+                for &i in field_indices.iter() {
+                    let mut row = key.clone();
+                    row.extend([
+                        b"modified".to_vec(),
+                        column_name(&names, i).into_bytes(),
+                        delete.byte_record().get(i).unwrap_or_default().to_vec(),
+                        add.byte_record().get(i).unwrap_or_default().to_vec(),
+                    ]);
+                    wtr.write_record(row)?;
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+/// writes a patch-style JSON object for each added, removed & modified record,
+/// either as a JSON array or as JSON Lines
+fn write_patches<W: Write>(
+    wtr: &mut W,
+    diff_byte_records: DiffByteRecords,
+    key_cols: &[usize],
+    jsonl: bool,
+) -> CliResult<()> {
+    let names = column_names(diff_byte_records.headers(), diff_byte_records.num_columns());
+
+    let mut first = true;
+    if !jsonl {
+        wtr.write_all(b"[")?;
+    }
+    for dbr in diff_byte_records {
+        let patch = match dbr {
+            DiffByteRecord::Add(add) => json!({
+                "op": "add",
+                "key": key_object(add.byte_record(), key_cols, &names),
+                "record": record_object(add.byte_record(), &names),
+            }),
+            DiffByteRecord::Delete(del) => json!({
+                "op": "remove",
+                "key": key_object(del.byte_record(), key_cols, &names),
+                "record": record_object(del.byte_record(), &names),
+            }),
+            DiffByteRecord::Modify {
+                delete,
+                add,
+                field_indices,
+            } => {
+                let mut changes = Map::with_capacity(field_indices.len());
+                for &i in field_indices.iter() {
+                    let value = |record: &csv::ByteRecord| {
+                        json!(String::from_utf8_lossy(record.get(i).unwrap_or_default()))
+                    };
+                    changes.insert(
+                        column_name(&names, i),
+                        json!({
+                            "old": value(delete.byte_record()),
+                            "new": value(add.byte_record()),
+                        }),
+                    );
+                }
+                json!({
+                    "op": "modify",
+                    "key": key_object(add.byte_record(), key_cols, &names),
+                    "changes": changes,
+                })
+            },
+        };
+
+        if jsonl {
+            serde_json::to_writer(&mut *wtr, &patch)?;
+            wtr.write_all(b"\n")?;
+        } else {
+            if !first {
+                wtr.write_all(b",")?;
+            }
+            serde_json::to_writer(&mut *wtr, &patch)?;
+        }
+        first = false;
+    }
+    if !jsonl {
+        wtr.write_all(b"]\n")?;
+    }
+    Ok(())
 }
 
 struct CsvDiffWriter<W: Write> {
@@ -1,6 +1,6 @@
 static USAGE: &str = r#"
 Exports a specified Excel/ODS sheet to a CSV file.
-The first row of a sheet is assumed to be the header row.
+The first row of a sheet is assumed to be the header row, unless --header-row is set.
 
 Examples:
 
@@ -37,6 +37,19 @@ Export a range of cells in the first sheet:
 Export a range of cells in the second sheet:
     qsv excel --range C3:T25 -s 1 input.xlsx
 
+Export a sheet whose header is below a title (the rows above the header are skipped):
+    qsv excel --header-row auto input.xlsx
+    # the header is on the 4th row of the sheet
+    qsv excel --header-row 4 input.xlsx
+
+Export an Excel Table by name (case-insensitive):
+    qsv excel --table SalesTable input.xlsx
+
+Export all the sheets to CSV files in the outdir directory (e.g. outdir/Sheet1.csv):
+    qsv excel --all-sheets outdir input.xlsx
+    # with a filename template (e.g. outdir/sales_Sheet1.csv)
+    qsv excel --all-sheets outdir --filename sales_{}.csv input.xlsx
+
 Export metadata for all sheets in CSV format:
     qsv excel --metadata c input.xlsx
 
@@ -103,6 +116,23 @@ Excel options:
      --keep-zero-time          Keep the time part of a date-time field if it is 00:00:00.
                                By default, qsv will remove the time part if it is 00:00:00.
      --range <range>           An Excel format range, like C:T or C3:T25, to extract to the CSV.
+     --header-row <row>        The row with the column names. Rows above it, like a report title,
+                               are skipped. Either the row number, as numbered in Excel, or "auto"
+                               to use the first row with the most non-empty cells among the first
+                               25 rows of the sheet (or --range).
+                               When not set, the first non-empty row (or the first row of --range)
+                               is the header row.
+     --table <name>            Name (case-insensitive) of an Excel Table to export, instead of a
+                               sheet. The header row of the table and its data rows, without
+                               the totals row, are exported.
+                               Only supported for xlsx and xlsm files.
+     --all-sheets <outdir>     Export all the sheets to CSV files in <outdir>, creating it if
+                               it doesn't exist. Chart sheets, macro sheets and empty sheets
+                               are skipped. --header-row applies to each sheet.
+     --filename <template>     With --all-sheets, the filename template of the CSV files.
+                               The string '{}' is replaced by the sheet name. Characters that
+                               aren't allowed in filenames are replaced by '_'.
+                               [default: {}.csv]
      -j, --jobs <arg>          The number of jobs to run in parallel.
                                When not set, the number of jobs is set to the number of CPUs detected.
 
@@ -114,7 +144,13 @@ Common options:
     -Q, --quiet                Do not display export summary message.
 "#;
 
-use std::{cmp, fmt::Write, path::PathBuf};
+use std::{
+    cmp,
+    fmt::Write,
+    fs::{self, File},
+    io::{self, BufReader},
+    path::PathBuf,
+};
 
 use calamine::{open_workbook, Data, Error, Range, Reader, SheetType, Sheets};
 use indicatif::HumanCount;
@@ -124,9 +160,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     config::{Config, Delimiter},
-    util, CliError, CliResult,
+    util::{self, FilenameTemplate},
+    CliError, CliResult,
 };
 
+// the number of rows at the start of a sheet where --header-row auto looks for the header row
+const HEADER_ROW_SAMPLE_SIZE: usize = 25;
+
 #[derive(Deserialize)]
 struct Args {
     arg_input:           String,
@@ -141,6 +181,10 @@ struct Args {
     flag_date_format:    Option<String>,
     flag_keep_zero_time: bool,
     flag_range:          String,
+    flag_table:          Option<String>,
+    flag_all_sheets:     Option<String>,
+    flag_filename:       FilenameTemplate,
+    flag_header_row:     Option<String>,
     flag_jobs:           Option<usize>,
 }
 
@@ -161,6 +205,14 @@ enum ErrorFormat {
     Both,
 }
 
+struct ExportOptions {
+    error_format:   ErrorFormat,
+    trim:           bool,
+    date_format:    Option<String>,
+    keep_zero_time: bool,
+    njobs:          usize,
+}
+
 #[derive(Serialize, Deserialize)]
 struct SheetMetadata {
     index:                   usize,
@@ -262,6 +314,21 @@ impl RequestedRange {
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    if args.flag_table.is_some()
+        && (args.flag_all_sheets.is_some()
+            || !args.flag_range.is_empty()
+            || args.flag_header_row.is_some())
+    {
+        return fail_incorrectusage_clierror!(
+            "--table cannot be used with --all-sheets, --range or --header-row."
+        );
+    }
+    if args.flag_all_sheets.is_some() && (args.flag_output.is_some() || !args.flag_range.is_empty())
+    {
+        return fail_incorrectusage_clierror!(
+            "--all-sheets cannot be used with --output or --range."
+        );
+    }
     let path = &args.arg_input;
 
     let sce = PathBuf::from(path);
@@ -529,6 +596,128 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     // --------------------------------------------------------------------
     // we're not exporting metadata, we're exporting the spreadsheet to CSV
 
+    let options = ExportOptions {
+        error_format:   match args.flag_error_format.to_lowercase().as_str() {
+            "formula" => ErrorFormat::Formula,
+            "both" => ErrorFormat::Both,
+            _ => ErrorFormat::Code,
+        },
+        trim:           args.flag_trim,
+        date_format:    args.flag_date_format,
+        keep_zero_time: args.flag_keep_zero_time,
+        njobs:          util::njobs(args.flag_jobs),
+    };
+    let header_row = args.flag_header_row.as_deref();
+
+    // export an Excel Table, instead of a sheet
+    if let Some(table_name) = args.flag_table {
+        let Sheets::Xlsx(ref mut xlsx) = workbook else {
+            return fail_incorrectusage_clierror!(
+                "--table is only supported for xlsx and xlsm files."
+            );
+        };
+        xlsx.load_tables().map_err(Error::Xlsx)?;
+        let table_names = xlsx.table_names();
+        let Some(name) = table_names
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(&table_name))
+            .map(|name| (*name).to_string())
+        else {
+            if table_names.is_empty() {
+                return fail_clierror!(
+                    "Table \"{table_name}\" not found. The workbook has no tables."
+                );
+            }
+            return fail_clierror!(
+                "Table \"{table_name}\" not found. Available tables: {}",
+                table_names
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        };
+        let table = xlsx.table_by_name(&name).map_err(Error::Xlsx)?;
+        let formulas = sheet_formulas(&mut workbook, table.sheet_name(), &options.error_format)?;
+        info!(
+            "exporting table ({name}) of sheet ({})...",
+            table.sheet_name()
+        );
+        let row_count = export_range(
+            &mut wtr,
+            table.columns().to_vec(),
+            table.data(),
+            &formulas,
+            &options,
+        )?;
+        if !args.flag_quiet {
+            winfo!(
+                "{}",
+                format!(
+                    "{} {}-column rows exported from \"{name}\" table",
+                    HumanCount(row_count as u64),
+                    HumanCount(table.columns().len() as u64),
+                )
+            );
+        }
+        return Ok(());
+    }
+
+    // export every worksheet to its own CSV file
+    if let Some(outdir) = args.flag_all_sheets {
+        let outdir = PathBuf::from(outdir);
+        fs::create_dir_all(&outdir)?;
+        for (i, sheet_name) in sheet_names.iter().enumerate() {
+            let sheet_type = workbook.sheets_metadata()[i].typ;
+            if sheet_type != SheetType::WorkSheet {
+                if !args.flag_quiet {
+                    winfo!("\"{sheet_name}\" sheet skipped: it's a {sheet_type:?}");
+                }
+                continue;
+            }
+            let range = if let Some(result) = workbook.worksheet_range_at(i) {
+                result?
+            } else {
+                Range::empty()
+            };
+            if range.is_empty() {
+                if !args.flag_quiet {
+                    winfo!("\"{sheet_name}\" sheet skipped: it's empty");
+                }
+                continue;
+            }
+
+            let (headers, data) = split_header(&range, header_row)?;
+            let col_count = headers.len();
+            let formulas = sheet_formulas(&mut workbook, sheet_name, &options.error_format)?;
+            let csv_path = outdir.join(args.flag_filename.filename(&safe_filename(sheet_name)));
+            if let Some(parent) = csv_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut sheet_wtr = Config::new(&Some(csv_path.display().to_string()))
+                .flexible(args.flag_flexible)
+                .delimiter(args.flag_delimiter)
+                .writer()?;
+            info!(
+                "exporting sheet ({sheet_name}) to {}...",
+                csv_path.display()
+            );
+            let row_count = export_range(&mut sheet_wtr, headers, &data, &formulas, &options)?;
+            if !args.flag_quiet {
+                winfo!(
+                    "{}",
+                    format!(
+                        "{} {}-column rows exported from \"{sheet_name}\" sheet to {}",
+                        HumanCount(row_count as u64),
+                        HumanCount(col_count as u64),
+                        csv_path.display()
+                    )
+                );
+            }
+        }
+        return Ok(());
+    }
+
     // convert sheet_names to lowercase so we can do a case-insensitive compare
     let lower_sheet_names: Vec<String> = sheet_names.iter().map(|s| s.to_lowercase()).collect();
 
@@ -613,46 +802,141 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         range = range.range(parsed_range.start, parsed_range.end);
     }
 
-    let (row_count, col_count) = range.get_size();
-
-    let error_format = match args.flag_error_format.to_lowercase().as_str() {
-        "formula" => ErrorFormat::Formula,
-        "both" => ErrorFormat::Both,
-        _ => ErrorFormat::Code,
-    };
-
-    if row_count == 0 {
+    if range.is_empty() {
         if !requested_range.is_empty() {
             return fail_clierror!("\"{requested_range}\" range in sheet \"{sheet}\" is empty.");
         }
         return fail_clierror!("\"{sheet}\" sheet is empty.");
     }
-    // there are rows to export
-    let mut rows_iter = range.rows();
 
-    let range_start = range.start().unwrap_or((0, 0));
+    let (headers, data) = split_header(&range, header_row)?;
+    let col_count = headers.len();
+    let formulas = sheet_formulas(&mut workbook, &sheet, &options.error_format)?;
+    info!("exporting sheet ({sheet})...");
+    let row_count = export_range(&mut wtr, headers, &data, &formulas, &options)?;
 
-    // get the sheet formulas only if we need them
-    // as this is an expensive operation
-    let sheet_formulas = if error_format == ErrorFormat::Code {
-        Range::empty()
+    if !args.flag_quiet {
+        winfo!(
+            "{}",
+            format!(
+                "{} {}-column rows exported from \"{sheet}\" sheet",
+                HumanCount(row_count as u64),
+                HumanCount(col_count as u64),
+            )
+        );
+    }
+
+    Ok(())
+}
+
+/// Splits a range into its header row and the data rows below it.
+/// Rows above the header row are skipped.
+fn split_header(
+    range: &Range<Data>,
+    header_row: Option<&str>,
+) -> CliResult<(Vec<String>, Range<Data>)> {
+    let (Some(start), Some(end)) = (range.start(), range.end()) else {
+        return Ok((vec![], Range::empty()));
+    };
+    let header_idx = match header_row {
+        None => start.0,
+        Some(row) if row.eq_ignore_ascii_case("auto") => detect_header_row(range),
+        Some(row) => match atoi_simd::parse::<u32>(row.as_bytes()) {
+            Ok(row_number) if row_number > start.0 && row_number <= end.0 + 1 => row_number - 1,
+            Ok(row_number) if row_number > 0 => {
+                return fail_incorrectusage_clierror!(
+                    "Header row {row_number} is not one of the exported rows ({}-{}).",
+                    start.0 + 1,
+                    end.0 + 1
+                );
+            },
+            _ => {
+                return fail_incorrectusage_clierror!(
+                    "Invalid header row \"{row}\". Must be a row number or \"auto\"."
+                );
+            },
+        },
+    };
+    if header_idx != start.0 {
+        info!("using row {} as header row", header_idx + 1);
+    }
+
+    let headers = range
+        .rows()
+        .nth((header_idx - start.0) as usize)
+        .map(|row| row.iter().map(ToString::to_string).collect())
+        .unwrap_or_default();
+    let data = if header_idx < end.0 {
+        range.range((header_idx + 1, start.1), end)
     } else {
-        workbook.worksheet_formula(&sheet)?
+        Range::empty()
     };
+    Ok((headers, data))
+}
+
+/// Detects the header row of a range, skipping title and blank rows above the data.
+/// The header row is the first of the first HEADER_ROW_SAMPLE_SIZE rows with the most
+/// non-empty cells.
+fn detect_header_row(range: &Range<Data>) -> u32 {
+    let start_row = range.start().map_or(0, |(row, _)| row);
+    let mut header_idx = start_row;
+    let mut max_cells = 0;
+    for (i, row) in range.rows().take(HEADER_ROW_SAMPLE_SIZE).enumerate() {
+        let cells = row.iter().filter(|cell| **cell != Data::Empty).count();
+        if cells > max_cells {
+            max_cells = cells;
+            header_idx = start_row + i as u32;
+        }
+    }
+    header_idx
+}
+
+/// Gets the formulas of a sheet, only if they're needed to format error cells,
+/// as this is an expensive operation.
+fn sheet_formulas(
+    workbook: &mut Sheets<BufReader<File>>,
+    sheet: &str,
+    error_format: &ErrorFormat,
+) -> CliResult<Range<String>> {
+    if *error_format == ErrorFormat::Code {
+        Ok(Range::empty())
+    } else {
+        Ok(workbook.worksheet_formula(sheet)?)
+    }
+}
+
+/// Replaces the characters of a sheet name that aren't allowed in filenames.
+fn safe_filename(sheet_name: &str) -> String {
+    sheet_name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Writes the headers and the data rows of a range as CSV, returning the number of data rows.
+fn export_range(
+    wtr: &mut csv::Writer<Box<dyn io::Write>>,
+    headers: Vec<String>,
+    range: &Range<Data>,
+    sheet_formulas: &Range<String>,
+    options: &ExportOptions,
+) -> CliResult<usize> {
+    let col_count = headers.len();
+    let error_format = &options.error_format;
+    let trim = options.trim;
+    let keep_zero_time = options.keep_zero_time;
 
     // amortize allocations
     let mut record = csv::StringRecord::with_capacity(500, col_count);
     let mut trimmed_record = csv::StringRecord::with_capacity(500, col_count);
 
-    // get headers
-    info!("exporting sheet ({sheet})... processing first row as header...");
-    let headers = range.headers().unwrap_or_default();
     for header in headers {
         record.push_field(&header);
     }
-    rows_iter.next(); // we processed the header row
-
-    let trim = args.flag_trim;
 
     if trim {
         record.trim();
@@ -669,31 +953,25 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     wtr.write_record(&record)?;
 
     let no_date_format: bool;
-    let date_format = if let Some(df) = args.flag_date_format {
+    let date_format = if let Some(df) = &options.date_format {
         no_date_format = false;
-        df
+        df.as_str()
     } else {
         no_date_format = true;
-        String::new()
+        ""
     };
 
+    let (row_count, _) = range.get_size();
+    let (range_start_row, range_start_col) = range.start().unwrap_or((0, 0));
     let mut rows: Vec<(u32, &[Data])> = Vec::with_capacity(row_count);
 
-    // we add 1 as we already processed the header row
-    let mut row_idx = range_start.0 + 1;
-    // queue rest of the rows for processing as data rows
-    for row in rows_iter {
+    // queue the rows for processing, with their row index in the sheet
+    for (row_idx, row) in (range_start_row..).zip(range.rows()) {
         rows.push((row_idx, row));
-        row_idx += 1;
     }
 
-    // set RAYON_NUM_THREADS
-    let ncpus = util::njobs(args.flag_jobs);
-
     // set chunk_size to number of rows per core/thread
-    let chunk_size = row_count.div_ceil(ncpus);
-
-    let keep_zero_time = args.flag_keep_zero_time;
+    let chunk_size = row_count.div_ceil(options.njobs).max(1);
 
     let processed_rows: Vec<Vec<csv::StringRecord>> = rows
         .par_chunks(chunk_size)
@@ -707,13 +985,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             let mut formatted_date = String::new();
 
             let mut processed_chunk: Vec<csv::StringRecord> = Vec::with_capacity(chunk_size);
-            let mut col_idx = 0_u32;
 
             let formula_get_value_error = "cannot get formula".to_string();
             let mut cell_value: &String;
 
             for (row_idx, row) in chunk {
-                for cell in *row {
+                for (col_idx, cell) in (range_start_col..).zip(*row) {
                     match *cell {
                         Data::Empty => record.push_field(""),
                         Data::String(ref s) => record.push_field(s),
@@ -746,7 +1023,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                                     } else {
                                         // a date format was specified, so we'll use it
                                         formatted_date.clear();
-                                        if write!(formatted_date, "{}", dt.format(&date_format))
+                                        if write!(formatted_date, "{}", dt.format(date_format))
                                             .is_ok()
                                         {
                                             // the format string was ok, so use to_string()
@@ -792,7 +1069,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                                     cell_value = sheet_formulas
                                         .get_value((*row_idx, col_idx))
                                         .unwrap_or(&formula_get_value_error);
-                                    if error_format == ErrorFormat::Formula {
+                                    if *error_format == ErrorFormat::Formula {
                                         record.push_field(&format!("#={cell_value}"));
                                     } else {
                                         record.push_field(&format!("{e}: ={cell_value}"));
@@ -801,7 +1078,6 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                             };
                         },
                     };
-                    col_idx += 1;
                 }
 
                 if trim {
@@ -837,16 +1113,5 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     wtr.flush()?;
 
-    if !args.flag_quiet {
-        winfo!(
-            "{}",
-            format!(
-                "{} {}-column rows exported from \"{sheet}\" sheet",
-                HumanCount(row_count.saturating_sub(1) as u64),
-                HumanCount(col_count as u64),
-            )
-        );
-    }
-
-    Ok(())
+    Ok(row_count)
 }
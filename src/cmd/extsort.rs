@@ -1,15 +1,38 @@
 static USAGE: &str = r#"
 Sort an arbitrarily large CSV/text file using a multithreaded external sort algorithm.
 
-This command is not specific to CSV data, it sorts any text file on a 
+By default, this command is not specific to CSV data, it sorts any text file on a 
 line-by-line basis. If sorting a non-CSV file, be sure to set --no-headers, 
 otherwise, the first line will not be included in the external sort.
 
+If any of the --select, --by, --numeric, --reverse, --ignore-case or --delimiter
+options are used, the input is parsed as CSV instead, and its records are sorted
+with the same semantics as the sort command - so fields with quoted newlines are
+handled correctly and the header row is preserved.
+To sort CSV records on all of their columns, use `--select 1-`.
+
+Examples:
+
+  # sort a 100GB CSV by its "country" column, using the /scratch directory for the
+  # sorted segments that are merged
+  qsv extsort --select country --tmp-dir /scratch data.csv sorted.csv
+
+  # sort by country ascending, then by revenue descending numerically
+  qsv extsort --by country,revenue:desc:n data.csv sorted.csv
+
 Usage:
     qsv extsort [options] [<input>] [<output>]
     qsv extsort --help
 
 External sort option:
+    -s, --select <arg>     Select a subset of columns to sort on.
+                           See 'qsv select --help' for the format details.
+    --by <spec>            Sort using the given sort-spec instead of --select.
+                           See 'qsv sort --help' for the sort-spec format details.
+                           Cannot be used with --numeric, --reverse or --ignore-case.
+    -N, --numeric          Compare according to string numerical value
+    -R, --reverse          Reverse order
+    -i, --ignore-case      Compare strings disregarding case
     --memory-limit <arg>   The maximum amount of memory to buffer the on-disk hash table.
                            This is a percentage of total memory.
                            [default: 10]
@@ -25,6 +48,8 @@ Common options:
                            as headers and will be sorted with the rest
                            of the rows. Otherwise, the first row will always
                            appear as the header row in the output.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
 "#;
 
 use std::{
@@ -37,16 +62,27 @@ use ext_sort::{buffer::mem::MemoryLimitedBufferBuilder, ExternalSorter, External
 use serde::Deserialize;
 use sysinfo::System;
 
-use crate::{config, util, CliResult};
+use crate::{
+    cmd::sort::{DateKeys, SortKeys, SortSpec},
+    config::{self, Config, Delimiter},
+    select::SelectColumns,
+    util, CliResult,
+};
 
 #[derive(Deserialize)]
 struct Args {
     arg_input:         Option<String>,
     arg_output:        Option<String>,
+    flag_select:       Option<SelectColumns>,
+    flag_by:           Option<SortSpec>,
+    flag_numeric:      bool,
+    flag_reverse:      bool,
+    flag_ignore_case:  bool,
     flag_jobs:         Option<usize>,
     flag_memory_limit: Option<u8>,
     flag_tmp_dir:      Option<String>,
     flag_no_headers:   bool,
+    flag_delimiter:    Option<Delimiter>,
 }
 
 const MEMORY_LIMITED_BUFFER: u64 = 100 * 1_000_000; // 100 MB
@@ -79,6 +115,16 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     };
     log::info!("{mem_limited_buffer} bytes used for in memory mergesort buffer...");
 
+    if args.flag_select.is_some()
+        || args.flag_by.is_some()
+        || args.flag_numeric
+        || args.flag_reverse
+        || args.flag_ignore_case
+        || args.flag_delimiter.is_some()
+    {
+        return sort_csv(&args, &tmp_dir, mem_limited_buffer);
+    }
+
     let mut input_reader: Box<dyn BufRead> = match &args.arg_input {
         Some(input_path) => {
             if input_path.to_lowercase().ends_with(".sz") {
@@ -148,6 +194,79 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+/// externally sorts the CSV records by the selected columns or sort-spec
+fn sort_csv(args: &Args, tmp_dir: &str, mem_limited_buffer: u64) -> CliResult<()> {
+    if args.flag_by.is_some() && (args.flag_numeric || args.flag_reverse || args.flag_ignore_case) {
+        return fail_incorrectusage_clierror!(
+            "--by cannot be used with --numeric, --reverse or --ignore-case."
+        );
+    }
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(
+            args.flag_select
+                .clone()
+                .unwrap_or(SelectColumns::parse("")?),
+        );
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let sort_keys = match args.flag_by {
+        Some(ref spec) => spec.resolve(&headers, !rconfig.no_headers)?,
+        None => SortKeys::from_selection(
+            &rconfig.selection(&headers)?,
+            args.flag_numeric,
+            args.flag_reverse,
+            args.flag_ignore_case,
+        ),
+    };
+
+    let sorter: ExternalSorter<(DateKeys, Vec<Vec<u8>>), csv::Error, MemoryLimitedBufferBuilder> =
+        match ExternalSorterBuilder::new()
+            .with_tmp_dir(path::Path::new(tmp_dir))
+            .with_buffer(MemoryLimitedBufferBuilder::new(mem_limited_buffer))
+            .with_rw_buf_size(RW_BUFFER_CAPACITY)
+            .with_threads_number(util::njobs(args.flag_jobs))
+            .build()
+        {
+            Ok(sorter) => sorter,
+            Err(e) => {
+                return fail_clierror!("cannot create external sorter: {e}");
+            },
+        };
+
+    // the records are sorted as a Vec of their fields, as ByteRecords
+    // can't be serialized to the on-disk sorted segments. The dates of the
+    // date sort keys are parsed once per record, and sorted along with it
+    let sort_keys = &sort_keys;
+    let records = rdr.into_byte_records().map(|record| {
+        record.map(|r| {
+            let fields = r.iter().map(<[u8]>::to_vec).collect::<Vec<_>>();
+            (sort_keys.date_keys_of_fields(&fields), fields)
+        })
+    });
+    let sorted = match sorter.sort_by(records, move |(a_dates, a), (b_dates, b)| {
+        sort_keys.compare_fields(a, a_dates, b, b_dates)
+    }) {
+        Ok(sorted) => sorted,
+        Err(e) => return fail_clierror!("cannot do external sort: {e}"),
+    };
+
+    let mut wtr = Config::new(&args.arg_output).writer()?;
+    if !rconfig.no_headers && !headers.is_empty() {
+        wtr.write_byte_record(&headers)?;
+    }
+    for record in sorted {
+        match record {
+            Ok((_, fields)) => wtr.write_record(&fields)?,
+            Err(e) => return fail_clierror!("cannot read sorted segment: {e}"),
+        }
+    }
+    Ok(wtr.flush()?)
+}
+
 #[test]
 fn test_mem_check() {
     // check to see if sysinfo return meminfo without segfaulting
@@ -24,6 +24,12 @@ items and not to columns with a small number of unique items.
 Since this computes an exact frequency table, memory proportional to the
 cardinality of each column is required.
 
+If the input has a current stats cache with cardinalities (i.e. `qsv stats` was run with
+the --cardinality option and its results were cached), columns with all unique values
+(e.g. ID columns) are not counted. Approximate cardinalities (i.e. computed with the
+stats --approx option) are not used. Their frequency table is a single "<ALL_UNIQUE>" row
+instead. See --stats-mode.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_frequency.rs.
 
 Usage:
@@ -69,6 +75,14 @@ frequency options:
                             count. The default is descending order.
     --no-nulls              Don't include NULLs in the frequency table.
     -i, --ignore-case       Ignore case when computing frequencies.
+    --stats-mode <arg>      How to use the stats cache of the input. Valid values are:
+                              auto - use the cached cardinalities if the stats cache
+                                     exists & is current, to skip counting columns
+                                     with all unique values.
+                              none - don't use the stats cache.
+                            The stats cache is not used with --ignore-case or
+                            --no-headers, or when both --limit and --unq-limit are 0.
+                            [default: auto]
     -j, --jobs <arg>        The number of jobs to run in parallel.
                             This works much faster when the given CSV data has
                             an index already created. Note that a file handle
@@ -101,11 +115,14 @@ use crate::{
     config::{Config, Delimiter},
     index::Indexed,
     select::{SelectColumns, Selection},
+    statscache::StatsCache,
     util,
     util::ByteString,
     CliResult,
 };
 
+const ALL_UNIQUE: &[u8] = b"<ALL_UNIQUE>";
+
 #[allow(clippy::unsafe_derive_deserialize)]
 #[derive(Clone, Deserialize)]
 pub struct Args {
@@ -120,6 +137,7 @@ pub struct Args {
     pub flag_asc:            bool,
     pub flag_no_nulls:       bool,
     pub flag_ignore_case:    bool,
+    pub flag_stats_mode:     String,
     pub flag_jobs:           Option<usize>,
     pub flag_output:         Option<String>,
     pub flag_no_headers:     bool,
@@ -128,19 +146,57 @@ pub struct Args {
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
     let rconfig = args.rconfig();
 
+    if !["auto", "none"].contains(&args.flag_stats_mode.as_str()) {
+        return fail_incorrectusage_clierror!(
+            "Invalid --stats-mode: {}. Valid values are: auto, none.",
+            args.flag_stats_mode
+        );
+    }
+
+    // the selected columns with all unique values according to the stats cache
+    // are not counted, so we remove them from the selection
+    let cached_columns = args.all_unique_columns()?;
+    let counted_columns: Vec<String> = cached_columns
+        .iter()
+        .filter(|(_, _, unique_count)| unique_count.is_none())
+        .map(|(i, _, _)| (i + 1).to_string())
+        .collect();
+    if !counted_columns.is_empty() && counted_columns.len() < cached_columns.len() {
+        args.flag_select = SelectColumns::parse(&counted_columns.join(","))?;
+    }
+
     // we're loading the entire file into memory, we need to check avail mem
     if let Some(path) = rconfig.path.clone() {
         util::mem_file_check(&path, false, args.flag_memcheck)?;
     }
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
-    let (headers, tables) = match args.rconfig().indexed()? {
-        Some(ref mut idx) if util::njobs(args.flag_jobs) > 1 => args.parallel_ftables(idx),
-        _ => args.sequential_ftables(),
-    }?;
+    let (headers, tables) = if !cached_columns.is_empty() && counted_columns.is_empty() {
+        // all the columns have unique values, there's nothing to count
+        (csv::ByteRecord::new(), vec![])
+    } else {
+        match args.rconfig().indexed()? {
+            Some(ref mut idx) if util::njobs(args.flag_jobs) > 1 => args.parallel_ftables(idx),
+            _ => args.sequential_ftables(),
+        }?
+    };
+
+    // the headers of all the selected columns, with their record count if
+    // they have all unique values
+    let columns: Vec<(Vec<u8>, Option<u64>)> = if cached_columns.is_empty() {
+        headers
+            .iter()
+            .map(|header| (header.to_vec(), None))
+            .collect()
+    } else {
+        cached_columns
+            .into_iter()
+            .map(|(_, header, unique_count)| (header, unique_count))
+            .collect()
+    };
 
     #[allow(unused_assignments)]
     let mut header_vec: Vec<u8> = Vec::with_capacity(tables.len());
@@ -154,14 +210,26 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut row;
 
     wtr.write_record(vec!["field", "value", "count", "percentage"])?;
-    let head_ftables = headers.iter().zip(tables);
-    for (i, (header, ftab)) in head_ftables.enumerate() {
+    let mut ftables = tables.into_iter();
+    for (i, (header, unique_count)) in columns.into_iter().enumerate() {
         header_vec = if rconfig.no_headers {
             (i + 1).to_string().into_bytes()
         } else {
-            header.to_vec()
+            header
         };
 
+        if let Some(record_count) = unique_count {
+            wtr.write_record(vec![
+                &*header_vec,
+                ALL_UNIQUE,
+                buffer.format(record_count).as_bytes(),
+                b"100".as_slice(),
+            ])?;
+            continue;
+        }
+
+        // safety: there's a frequency table for each column we counted
+        let ftab = ftables.next().unwrap();
         let mut sorted_counts: Vec<(Vec<u8>, u64, f64)> = args.counts(&ftab);
 
         // if not --other_sorted and the first value is "Other (", rotate it to the end
@@ -223,6 +291,48 @@ impl Args {
             .select(self.flag_select.clone())
     }
 
+    /// the index & header of the selected columns, with the record count of the columns
+    /// with all unique values according to the stats cache (i.e. their cardinality is
+    /// equal to the record count). Empty if the stats cache is not available or used.
+    fn all_unique_columns(&self) -> CliResult<Vec<(usize, Vec<u8>, Option<u64>)>> {
+        let conf = self.rconfig();
+        if self.flag_stats_mode == "none"
+            || self.flag_ignore_case
+            || self.flag_no_headers
+            || (self.flag_limit == 0 && self.flag_unq_limit == 0)
+        {
+            return Ok(vec![]);
+        }
+        let Some(stats_cache) = conf.path.as_deref().and_then(StatsCache::load) else {
+            return Ok(vec![]);
+        };
+        let record_count = match stats_cache.record_count() {
+            Some(record_count) => record_count,
+            None => util::count_rows(&conf)?,
+        };
+        if record_count == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut rdr = conf.reader()?;
+        let headers = rdr.byte_headers()?.clone();
+        let sel = conf.selection(&headers)?;
+        Ok(sel
+            .iter()
+            .map(|&i| {
+                let header = &headers[i];
+                // approximate cardinalities (stats --approx) can't tell if a column is
+                // all unique, so those columns are computed normally
+                let all_unique = stats_cache
+                    .get(&String::from_utf8_lossy(header))
+                    .filter(|column| column.cardinality_exact)
+                    .and_then(|column| column.cardinality)
+                    == Some(record_count);
+                (i, header.to_vec(), all_unique.then_some(record_count))
+            })
+            .collect())
+    }
+
     #[inline]
     fn counts(&self, ftab: &FTable) -> Vec<(ByteString, u64, f64)> {
         const NULL_VAL: &[u8] = b"(NULL)";
@@ -0,0 +1,367 @@
+static USAGE: &str = r#"
+Converts fixed-width files to CSV, and CSV to fixed-width files.
+
+The columns of the fixed-width file are given with one of:
+
+  * --widths: the width of each column, in order from the start of the line,
+    e.g. "10,5,8".
+  * --positions: the 1-based start and the length of each column, e.g.
+    "1:10,16:8". Columns can be given in any order, skip characters (e.g. filler)
+    or overlap.
+  * --layout: a CSV file with a row for each column. It can have a "name" column,
+    and needs a "start" column with a "length" or an "end" (inclusive) column, or a
+    "width" column. When a row has a width but no start, the column starts where
+    the previous one ends. Other columns, e.g. a "type" or "description", are
+    ignored.
+
+Positions and widths are counted in characters, not bytes. The columns are named
+with --names, or the names in the layout file. Otherwise, they're named field_1,
+field_2, etc.
+
+By default, the fixed-width input is converted to CSV, with a record for each
+line. Fields are trimmed of surrounding whitespace. Lines shorter than the spec
+have empty fields for the missing columns, and characters after the last column
+are ignored. Blank lines are skipped. The input must be UTF-8 encoded; convert
+EBCDIC or Latin-1 extracts first, e.g. with iconv.
+
+With --export, CSV is converted to a fixed-width file instead. The CSV columns
+are mapped to the columns of the spec in order, and each field is left-aligned
+and padded with spaces to the width of its column. The header row isn't written.
+
+Fields longer than their column are truncated. Unless --truncate is set, they're
+also reported as errors, like the `validate` command does: a
+"<input>.validation-errors.tsv" report is created with the following columns:
+
+  * row_number: the row number of the record
+  * field: the name of the CSV column
+  * error: the length of the field and the width of its column
+
+and the command returns an exit code of 1. If piped from stdin, the report is
+named "stdin.csv.validation-errors.tsv".
+
+Examples:
+    # convert a mainframe extract with three columns of 10, 5 and 8 characters
+    qsv fwf --widths 10,5,8 --names id,branch,amount extract.txt -o extract.csv
+
+    # skip the two lines of the report header, and a filler from 11 to 15
+    qsv fwf --positions 1:10,16:8 --skip-lines 2 report.txt
+
+    # use a layout file, e.g. generated from a copybook
+    qsv fwf --layout layout.csv extract.txt
+
+    # convert back to fixed-width, failing on fields that don't fit
+    qsv fwf --export --layout layout.csv extract.csv -o extract.txt
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_fwf.rs.
+
+Usage:
+    qsv fwf [options] [<input>]
+    qsv fwf --help
+
+fwf options:
+    -w, --widths <list>      The comma-separated widths of the columns.
+    -p, --positions <list>   The comma-separated <start>:<length> of the columns,
+                             with 1-based starts.
+    -l, --layout <file>      A CSV file with the layout of the columns.
+    -n, --names <list>       The comma-separated names of the columns.
+    --skip-lines <n>         Skip this many lines at the start of the fixed-width
+                             input, e.g. a report header. [default: 0]
+    --no-trim                Don't trim whitespace from the fields.
+    -x, --export             Convert CSV to a fixed-width file instead.
+    --truncate               With --export, silently truncate fields longer than
+                             their column, instead of reporting them as errors.
+
+Common options:
+    -h, --help               Display this message
+    -o, --output <file>      Write output to <file> instead of stdout.
+    -d, --delimiter <arg>    The field delimiter for writing CSV data, or with
+                             --export, for reading CSV data.
+                             Must be a single character. (default: ,)
+"#;
+
+use std::io::{self, BufRead, Write};
+
+use serde::Deserialize;
+
+use crate::{
+    cmd::validate::write_error_report,
+    config::{Config, Delimiter, DEFAULT_RDR_BUFFER_CAPACITY, DEFAULT_WTR_BUFFER_CAPACITY},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:       Option<String>,
+    flag_widths:     Option<String>,
+    flag_positions:  Option<String>,
+    flag_layout:     Option<String>,
+    flag_names:      Option<String>,
+    flag_skip_lines: usize,
+    flag_no_trim:    bool,
+    flag_export:     bool,
+    flag_truncate:   bool,
+    flag_output:     Option<String>,
+    flag_delimiter:  Option<Delimiter>,
+}
+
+/// A column of a fixed-width file, with its 0-based start and its width, in characters.
+struct Column {
+    name:  String,
+    start: usize,
+    width: usize,
+}
+
+impl Column {
+    const fn end(&self) -> usize {
+        self.start + self.width
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let mut columns = match (&args.flag_widths, &args.flag_positions, &args.flag_layout) {
+        (Some(widths), None, None) => parse_widths(widths)?,
+        (None, Some(positions), None) => parse_positions(positions)?,
+        (None, None, Some(layout)) => read_layout(layout)?,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Specify the columns with one of --widths, --positions or --layout."
+            )
+        },
+    };
+    if let Some(names) = &args.flag_names {
+        let names: Vec<&str> = names.split(',').map(str::trim).collect();
+        if names.len() != columns.len() {
+            return fail_incorrectusage_clierror!(
+                "{} names given for {} columns.",
+                names.len(),
+                columns.len()
+            );
+        }
+        for (column, name) in columns.iter_mut().zip(names) {
+            column.name = name.to_string();
+        }
+    }
+
+    if args.flag_export {
+        export(&args, &columns)
+    } else {
+        import(&args, &columns)
+    }
+}
+
+fn import(args: &Args, columns: &[Column]) -> CliResult<()> {
+    let mut rdr = io::BufReader::with_capacity(
+        DEFAULT_RDR_BUFFER_CAPACITY,
+        Config::new(&args.arg_input).io_reader()?,
+    );
+    let mut wtr = Config::new(&args.flag_output)
+        .delimiter(args.flag_delimiter)
+        .writer()?;
+    wtr.write_record(columns.iter().map(|column| &column.name))?;
+
+    let mut line = Vec::new();
+    // the byte offset of each character of the line, and of its end
+    let mut offsets: Vec<usize> = Vec::new();
+    let mut record = csv::StringRecord::new();
+    let mut line_number = 0_usize;
+    loop {
+        line.clear();
+        if rdr.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+        if line_number <= args.flag_skip_lines {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim_end_matches(&['\r', '\n'][..]);
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        offsets.clear();
+        offsets.extend(text.char_indices().map(|(i, _)| i));
+        offsets.push(text.len());
+        let num_chars = offsets.len() - 1;
+
+        record.clear();
+        for column in columns {
+            let field =
+                &text[offsets[column.start.min(num_chars)]..offsets[column.end().min(num_chars)]];
+            record.push_field(if args.flag_no_trim {
+                field
+            } else {
+                field.trim()
+            });
+        }
+        wtr.write_record(&record)?;
+    }
+    Ok(wtr.flush()?)
+}
+
+fn export(args: &Args, columns: &[Column]) -> CliResult<()> {
+    let mut rdr = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .reader()?;
+    let headers = rdr.headers()?.clone();
+    if headers.len() != columns.len() {
+        return fail_incorrectusage_clierror!(
+            "The CSV has {} columns, but {} columns are given.",
+            headers.len(),
+            columns.len()
+        );
+    }
+    let mut wtr = io::BufWriter::with_capacity(
+        DEFAULT_WTR_BUFFER_CAPACITY,
+        Config::new(&args.flag_output).io_writer()?,
+    );
+
+    let line_width = columns.iter().map(Column::end).max().unwrap_or(0);
+    let mut line: Vec<char> = Vec::with_capacity(line_width);
+    let mut record = csv::StringRecord::new();
+    let mut row_number = 0_u64;
+    let mut invalid_count = 0_u64;
+    let mut error_messages: Vec<String> = Vec::new();
+    while rdr.read_record(&mut record)? {
+        row_number += 1;
+        line.clear();
+        line.resize(line_width, ' ');
+        let mut invalid = false;
+        for ((column, field), header) in columns.iter().zip(&record).zip(&headers) {
+            let len = field.chars().count();
+            if len > column.width && !args.flag_truncate {
+                invalid = true;
+                error_messages.push(format!(
+                    "{row_number}\t{header}\tvalue is {len} characters long, but the column is {} \
+                     characters wide",
+                    column.width
+                ));
+            }
+            for (i, c) in field.chars().take(column.width).enumerate() {
+                line[column.start + i] = c;
+            }
+        }
+        if invalid {
+            invalid_count += 1;
+        }
+        wtr.write_all(line.iter().collect::<String>().as_bytes())?;
+        wtr.write_all(b"\n")?;
+    }
+    wtr.flush()?;
+
+    if invalid_count > 0 {
+        let input_path = args.arg_input.as_deref().unwrap_or("stdin.csv");
+        write_error_report(input_path, error_messages)?;
+        return fail_clierror!(
+            "{invalid_count} out of {row_number} records have fields longer than their column. \
+             See {input_path}.validation-errors.tsv."
+        );
+    }
+    Ok(())
+}
+
+fn default_name(index: usize) -> String {
+    format!("field_{}", index + 1)
+}
+
+fn parse_number(value: &str, what: &str) -> CliResult<usize> {
+    match value.trim().parse::<usize>() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => {
+            fail_incorrectusage_clierror!("Invalid {what} \"{value}\". Must be a positive integer.")
+        },
+    }
+}
+
+fn parse_widths(widths: &str) -> CliResult<Vec<Column>> {
+    let mut columns = Vec::new();
+    let mut start = 0;
+    for width in widths.split(',') {
+        let width = parse_number(width, "width")?;
+        columns.push(Column {
+            name: default_name(columns.len()),
+            start,
+            width,
+        });
+        start += width;
+    }
+    Ok(columns)
+}
+
+fn parse_positions(positions: &str) -> CliResult<Vec<Column>> {
+    let mut columns = Vec::new();
+    for position in positions.split(',') {
+        let Some((start, length)) = position.split_once(':') else {
+            return fail_incorrectusage_clierror!(
+                "Invalid position \"{position}\". Must be <start>:<length>, e.g. 1:10."
+            );
+        };
+        columns.push(Column {
+            name:  default_name(columns.len()),
+            start: parse_number(start, "start")? - 1,
+            width: parse_number(length, "length")?,
+        });
+    }
+    Ok(columns)
+}
+
+fn read_layout(layout: &str) -> CliResult<Vec<Column>> {
+    let mut rdr = Config::new(&Some(layout.to_string())).reader()?;
+    let headers: Vec<String> = rdr
+        .headers()?
+        .iter()
+        .map(|header| header.trim().to_lowercase())
+        .collect();
+    let find = |name: &str| headers.iter().position(|header| header == name);
+    let (name_idx, start_idx, length_idx, end_idx, width_idx) = (
+        find("name"),
+        find("start"),
+        find("length"),
+        find("end"),
+        find("width"),
+    );
+
+    let mut columns = Vec::new();
+    let mut next_start = 0;
+    for (row, record) in rdr.records().enumerate() {
+        let record = record?;
+        let field = |idx: Option<usize>| {
+            idx.and_then(|idx| record.get(idx))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let start = match field(start_idx) {
+            Some(start) => parse_number(start, "start")? - 1,
+            None => next_start,
+        };
+        let width = match (field(length_idx), field(end_idx), field(width_idx)) {
+            (Some(length), ..) => parse_number(length, "length")?,
+            (None, Some(end), _) => {
+                let end = parse_number(end, "end")?;
+                if end <= start {
+                    return fail_incorrectusage_clierror!(
+                        "Invalid end {end} in row {} of the layout. It's before the start.",
+                        row + 1
+                    );
+                }
+                end - start
+            },
+            (None, None, Some(width)) => parse_number(width, "width")?,
+            (None, None, None) => {
+                return fail_incorrectusage_clierror!(
+                    "Row {} of the layout has no length, end or width.",
+                    row + 1
+                )
+            },
+        };
+        let name = field(name_idx).map_or_else(|| default_name(columns.len()), String::from);
+        columns.push(Column { name, start, width });
+        next_start = start + width;
+    }
+    if columns.is_empty() {
+        return fail_incorrectusage_clierror!("The layout has no columns.");
+    }
+    Ok(columns)
+}
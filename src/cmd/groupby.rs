@@ -0,0 +1,532 @@
+static USAGE: &str = r#"
+Compute summary statistics for each group of a CSV, where a group is the set of rows
+with the same values in the <group> column/s.
+
+For each group and each selected column, the same summary statistics as the `stats`
+command are reported (sum, min/max/range, min/max length, mean, stddev, variance,
+nullcount, max_precision & sparsity by default), along with the number of rows in
+the group. Sparsity is computed relative to the number of rows in the group.
+Like `stats`, the "non-streaming" statistics (cardinality, median, MAD, quartiles &
+modes) need to be enabled explicitly, as they require keeping the values of each
+group in memory.
+
+The output has one row per group and selected column, sorted by the group values.
+
+Grouping a large file can be made MUCH faster if you create an index for it first
+with 'qsv index' to enable multithreading, just like `stats`.
+
+By default, all the groups are kept in memory. For high-cardinality group columns,
+use --external to spill the partial statistics of the groups to disk when the
+--memory-limit is reached. The spilled groups are merged back at the end.
+
+Examples:
+
+  # compute the default statistics of the fare column for each borough
+   qsv groupby borough trips.csv --select fare
+
+  # group by borough & payment type, also computing the cardinality & median
+   qsv groupby borough,payment_type trips.csv --cardinality --median
+
+  # group by a high-cardinality column, spilling to disk when 20% of memory is used
+   qsv groupby customer_id transactions.csv --external --memory-limit 20
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_groupby.rs.
+
+Usage:
+    qsv groupby [options] <group> [<input>]
+    qsv groupby --help
+
+groupby arguments:
+    <group>                   The column/s to group by.
+                              See 'qsv select --help' for the format details.
+    <input>                   The CSV file to read. If not given, reads from stdin.
+
+groupby options:
+    -s, --select <arg>        Select the columns to compute statistics for.
+                              See 'qsv select --help' for the format details.
+                              If not set, all the columns not in <group> are selected.
+    -E, --everything          Compute all the statistics available.
+    --cardinality             Compute the cardinality of each group.
+    --median                  Compute the median of each group.
+    --mad                     Compute the median absolute deviation (MAD) of each group.
+    --quartiles               Compute the quartiles, the IQR, the lower/upper inner/outer
+                              fences and skewness of each group.
+    --mode                    Compute the mode/s & antimode/s of each group.
+    --round <decimal_places>  Round statistics to <decimal_places>. Rounding is done following
+                              Midpoint Nearest Even (aka "Bankers Rounding") rule.
+                              [default: 4]
+    --nulls                   Include NULLs in the population size for computing
+                              mean and standard deviation.
+    -j, --jobs <arg>          The number of jobs to run in parallel.
+                              This works only when the given CSV has an index and
+                              --external is not set.
+                              When not set, the number of jobs is set to the
+                              number of CPUs detected.
+    --external                Spill the partial statistics of the groups to disk when
+                              the memory limit is reached.
+    --memory-limit <arg>      The percentage of total memory the groups can use before
+                              they're spilled to disk (capped at 90). Only used with --external.
+                              Set to 0 to keep as few groups in memory as possible (very slow).
+                              [default: 10]
+
+Common options:
+    -h, --help                Display this message
+    -o, --output <file>       Write output to <file> instead of stdout.
+    -n, --no-headers          When set, the first row will NOT be interpreted
+                              as column names. i.e., They will be included
+                              in statistics.
+    -d, --delimiter <arg>     The field delimiter for reading CSV data.
+                              Must be a single character. (default: ,)
+"#;
+
+use std::{collections::hash_map, mem};
+
+use ahash::AHashMap;
+use deepsize::DeepSizeOf;
+use ext_sort::{buffer::mem::MemoryLimitedBufferBuilder, ExternalSorter, ExternalSorterBuilder};
+use serde::{Deserialize, Serialize};
+use stats::Commute;
+use sysinfo::System;
+use threadpool::ThreadPool;
+
+use crate::{
+    cmd::stats::{Args as StatsArgs, Stats},
+    config::{Config, Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
+    select::{SelectColumns, Selection},
+    util, CliResult,
+};
+
+#[derive(Clone, Deserialize)]
+struct Args {
+    arg_group:         String,
+    arg_input:         Option<String>,
+    flag_select:       Option<SelectColumns>,
+    flag_everything:   bool,
+    flag_cardinality:  bool,
+    flag_median:       bool,
+    flag_mad:          bool,
+    flag_quartiles:    bool,
+    flag_mode:         bool,
+    flag_round:        u32,
+    flag_nulls:        bool,
+    flag_jobs:         Option<usize>,
+    flag_external:     bool,
+    flag_memory_limit: u8,
+    flag_output:       Option<String>,
+    flag_no_headers:   bool,
+    flag_delimiter:    Option<Delimiter>,
+}
+
+// used when we cannot detect the total memory
+const DEFAULT_MEMORY_LIMIT: u64 = 100 * 1_000_000; // 100 MB
+
+// the estimated memory used by the stats of a field in a group,
+// not counting the values stored by the non-streaming statistics
+const STATS_OVERHEAD_BYTES: u64 = 256;
+
+// the values of the group columns of a row
+type GroupKey = Vec<Vec<u8>>;
+
+type Groups = AHashMap<GroupKey, Group>;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Group {
+    count:    u64,
+    stats:    Vec<Stats>,
+    // the estimated memory used by the stats. It's only needed until the
+    // group is spilled to disk, so it's not serialized
+    #[serde(skip)]
+    mem_size: u64,
+}
+
+// the stats don't implement DeepSizeOf, so the ext-sort buffer uses the
+// memory estimated as the rows are added to the group
+impl DeepSizeOf for Group {
+    fn deep_size_of_children(&self, _context: &mut deepsize::Context) -> usize {
+        self.mem_size as usize
+    }
+}
+
+impl Commute for Group {
+    #[inline]
+    fn merge(&mut self, other: Group) {
+        self.count += other.count;
+        for (stat, other_stat) in self.stats.iter_mut().zip(other.stats) {
+            stat.merge(other_stat);
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let group_cols = SelectColumns::parse(&args.arg_group)?;
+    // by default, compute the stats of all the columns not in the group
+    let value_cols = match args.flag_select {
+        Some(ref sel) => sel.clone(),
+        None => SelectColumns::parse(&format!("!{}", args.arg_group))?,
+    };
+
+    let rconfig = args.rconfig();
+    let mut rdr = rconfig.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let group_sel = group_cols.selection(&headers, !args.flag_no_headers)?;
+    let value_sel = value_cols.selection(&headers, !args.flag_no_headers)?;
+    if value_sel.is_empty() {
+        return fail_incorrectusage_clierror!("No columns selected to compute statistics for.");
+    }
+
+    let stats_args = args.stats_args();
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    // header: the group columns, the group row count & the stats columns
+    let mut header_row = csv::ByteRecord::new();
+    for (i, field) in group_sel.select(&headers).enumerate() {
+        if args.flag_no_headers {
+            header_row.push_field(i.to_string().as_bytes());
+        } else {
+            header_row.push_field(field);
+        }
+    }
+    header_row.push_field(b"count");
+    for field in &stats_args.stat_headers() {
+        header_row.push_field(field.as_bytes());
+    }
+    wtr.write_byte_record(&header_row)?;
+
+    let value_headers: Vec<Vec<u8>> = value_sel
+        .select(&headers)
+        .enumerate()
+        .map(|(i, field)| {
+            if args.flag_no_headers {
+                i.to_string().into_bytes()
+            } else {
+                field.to_vec()
+            }
+        })
+        .collect();
+    let mut write_group = |key: &GroupKey, mut group: Group| -> CliResult<()> {
+        let mut buffer = itoa::Buffer::new();
+        let count = buffer.format(group.count).as_bytes().to_vec();
+        for (field_name, stat) in value_headers.iter().zip(group.stats.iter_mut()) {
+            let stat_record = stat.to_record_with_count(args.flag_round, false, group.count);
+            let mut record = csv::ByteRecord::from(key.clone());
+            record.push_field(&count);
+            record.push_field(field_name);
+            for stat_field in &stat_record {
+                record.push_field(stat_field.as_bytes());
+            }
+            wtr.write_byte_record(&record)?;
+        }
+        Ok(())
+    };
+
+    if args.flag_external {
+        let memory_limit = args.memory_limit();
+        log::info!("{memory_limit} bytes of memory used for groups before spilling to disk...");
+        let mut spilled_groups = SpilledGroups {
+            rows: rdr.into_byte_records(),
+            group_sel: &group_sel,
+            value_sel: &value_sel,
+            stats_args: &stats_args,
+            memory_limit,
+            memory_used: 0,
+            groups: Groups::new(),
+            spilled: Groups::new().into_iter(),
+            done: false,
+        };
+        spilled_groups.fill()?;
+        if spilled_groups.done {
+            // everything fit in memory
+            for (key, group) in sorted_groups(spilled_groups.groups) {
+                write_group(&key, group)?;
+            }
+        } else {
+            let sorter: ExternalSorter<(GroupKey, Group), csv::Error, MemoryLimitedBufferBuilder> =
+                match ExternalSorterBuilder::new()
+                    .with_buffer(MemoryLimitedBufferBuilder::new(memory_limit))
+                    .with_rw_buf_size(DEFAULT_WTR_BUFFER_CAPACITY)
+                    .with_threads_number(util::njobs(args.flag_jobs))
+                    .build()
+                {
+                    Ok(sorter) => sorter,
+                    Err(e) => {
+                        return fail_clierror!("cannot create external sorter: {e}");
+                    },
+                };
+            let sorted = match sorter.sort_by(spilled_groups, |a, b| a.0.cmp(&b.0)) {
+                Ok(sorted) => sorted,
+                Err(e) => return fail_clierror!("cannot do external sort: {e}"),
+            };
+
+            // the partial stats of a group spilled several times are adjacent
+            // once sorted by key, so they're merged before being written
+            let mut current: Option<(GroupKey, Group)> = None;
+            for entry in sorted {
+                let (key, group) = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => return fail_clierror!("cannot read spilled groups: {e}"),
+                };
+                if let Some((current_key, current_group)) = current.as_mut() {
+                    if *current_key == key {
+                        current_group.merge(group);
+                        continue;
+                    }
+                }
+                if let Some((prev_key, prev_group)) = current.replace((key, group)) {
+                    write_group(&prev_key, prev_group)?;
+                }
+            }
+            if let Some((key, group)) = current {
+                write_group(&key, group)?;
+            }
+        }
+    } else {
+        let groups = match rconfig.indexed()? {
+            Some(idx) if args.flag_jobs != Some(1) && idx.count() > 0 => {
+                args.parallel_groups(&group_sel, &value_sel, idx.count())?
+            },
+            _ => {
+                let mut groups = Groups::new();
+                for row in rdr.byte_records() {
+                    add_row(&mut groups, &row?, &group_sel, &value_sel, &stats_args);
+                }
+                groups
+            },
+        };
+        for (key, group) in sorted_groups(groups) {
+            write_group(&key, group)?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
+
+impl Args {
+    fn rconfig(&self) -> Config {
+        Config::new(&self.arg_input)
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers)
+    }
+
+    /// the stats args used to create the Stats of each group
+    fn stats_args(&self) -> StatsArgs {
+        StatsArgs {
+            arg_input:            self.arg_input.clone(),
+            flag_select:          SelectColumns::parse("").unwrap(),
+            flag_everything:      self.flag_everything,
+            flag_typesonly:       false,
+            flag_infer_boolean:   false,
+            flag_mode:            self.flag_mode,
+            flag_cardinality:     self.flag_cardinality,
+            flag_median:          self.flag_median,
+            flag_mad:             self.flag_mad,
+            flag_quartiles:       self.flag_quartiles,
+            flag_approx:          false,
+            flag_quality:         false,
+            flag_percentiles:     None,
+            flag_histogram:       None,
+            flag_round:           self.flag_round,
+            flag_nulls:           self.flag_nulls,
+            flag_infer_dates:     false,
+            flag_dates_whitelist: String::new(),
+            flag_prefer_dmy:      false,
+            flag_force:           false,
+            flag_jobs:            self.flag_jobs,
+            flag_stats_binout:    false,
+            flag_cache_threshold: 0,
+            flag_output:          None,
+            flag_no_headers:      self.flag_no_headers,
+            flag_delimiter:       self.flag_delimiter,
+            flag_memcheck:        false,
+        }
+    }
+
+    /// the number of bytes the groups can use before they're spilled to disk
+    fn memory_limit(&self) -> u64 {
+        if self.flag_memory_limit == 0 {
+            0
+        } else if sysinfo::IS_SUPPORTED_SYSTEM {
+            let mut sys = System::new();
+            sys.refresh_memory();
+            sys.total_memory() / 100 * u64::from(self.flag_memory_limit.min(90))
+        } else {
+            DEFAULT_MEMORY_LIMIT
+        }
+    }
+
+    fn parallel_groups(
+        &self,
+        group_sel: &Selection,
+        value_sel: &Selection,
+        idx_count: u64,
+    ) -> CliResult<Groups> {
+        let njobs = util::njobs(self.flag_jobs);
+        let chunk_size = util::chunk_size(idx_count as usize, njobs);
+        let nchunks = util::num_of_chunks(idx_count as usize, chunk_size);
+
+        let pool = ThreadPool::new(njobs);
+        let (send, recv) = channel::bounded(0);
+        for i in 0..nchunks {
+            let (send, args, group_sel, value_sel) = (
+                send.clone(),
+                self.clone(),
+                group_sel.clone(),
+                value_sel.clone(),
+            );
+            pool.execute(move || {
+                // safety: we know the index exists, as we're only called when it does
+                let mut idx = args.rconfig().indexed().unwrap().unwrap();
+                idx.seek((i * chunk_size) as u64)
+                    .expect("File seek failed.");
+                let stats_args = args.stats_args();
+                // a CSV error is sent back as is, so it's returned from the main thread
+                let groups = idx.byte_records().take(chunk_size).try_fold(
+                    Groups::new(),
+                    |mut groups, row| {
+                        add_row(&mut groups, &row?, &group_sel, &value_sel, &stats_args);
+                        Ok::<_, csv::Error>(groups)
+                    },
+                );
+                // safety: this will only return an Error if the channel has been disconnected
+                send.send(groups).unwrap();
+            });
+        }
+        drop(send);
+
+        let mut groups = Groups::new();
+        for chunk_groups in &recv {
+            for (key, group) in chunk_groups? {
+                match groups.get_mut(&key) {
+                    Some(existing) => existing.merge(group),
+                    None => {
+                        groups.insert(key, group);
+                    },
+                }
+            }
+        }
+        Ok(groups)
+    }
+}
+
+/// returns true if the stats keep the values of the group in memory
+const fn stores_values(stats_args: &StatsArgs) -> bool {
+    stats_args.flag_everything
+        || stats_args.flag_cardinality
+        || stats_args.flag_median
+        || stats_args.flag_mad
+        || stats_args.flag_quartiles
+        || stats_args.flag_mode
+}
+
+/// adds the row to its group, returning the estimated memory it used
+#[inline]
+fn add_row(
+    groups: &mut Groups,
+    row: &csv::ByteRecord,
+    group_sel: &Selection,
+    value_sel: &Selection,
+    stats_args: &StatsArgs,
+) -> u64 {
+    let mut mem_added = 0;
+    let key: GroupKey = group_sel.select(row).map(<[u8]>::to_vec).collect();
+    let group = groups.entry(key).or_insert_with_key(|key| {
+        let mem_size = STATS_OVERHEAD_BYTES * value_sel.len() as u64;
+        mem_added = key.deep_size_of() as u64 + mem_size;
+        Group {
+            count: 0,
+            stats: stats_args.new_stats(value_sel.len()),
+            mem_size,
+        }
+    });
+    group.count += 1;
+    let stores_values = stores_values(stats_args);
+    for (stat, field) in group.stats.iter_mut().zip(value_sel.select(row)) {
+        stat.add(field, false, false, false);
+        if stores_values {
+            let value_size = field.len() as u64 + 8;
+            group.mem_size += value_size;
+            mem_added += value_size;
+        }
+    }
+    mem_added
+}
+
+fn sorted_groups(groups: Groups) -> Vec<(GroupKey, Group)> {
+    let mut groups: Vec<(GroupKey, Group)> = groups.into_iter().collect();
+    groups.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    groups
+}
+
+/// groups the rows until the memory limit is reached, then yields the
+/// partial groups to the ext-sort external sorter, which sorts them by key &
+/// spills them to disk, before grouping the next rows.
+struct SpilledGroups<'a, I> {
+    rows:         I,
+    group_sel:    &'a Selection,
+    value_sel:    &'a Selection,
+    stats_args:   &'a StatsArgs,
+    memory_limit: u64,
+    memory_used:  u64,
+    groups:       Groups,
+    spilled:      hash_map::IntoIter<GroupKey, Group>,
+    done:         bool,
+}
+
+impl<I> SpilledGroups<'_, I>
+where
+    I: Iterator<Item = csv::Result<csv::ByteRecord>>,
+{
+    /// group the rows until the memory limit is reached, or all the rows are read.
+    /// At least one row is grouped, so a memory limit of 0 still makes progress.
+    fn fill(&mut self) -> csv::Result<()> {
+        loop {
+            let Some(row) = self.rows.next() else {
+                self.done = true;
+                return Ok(());
+            };
+            self.memory_used += add_row(
+                &mut self.groups,
+                &row?,
+                self.group_sel,
+                self.value_sel,
+                self.stats_args,
+            );
+            if self.is_full() {
+                return Ok(());
+            }
+        }
+    }
+
+    #[inline]
+    const fn is_full(&self) -> bool {
+        self.memory_used >= self.memory_limit
+    }
+}
+
+impl<I> Iterator for SpilledGroups<'_, I>
+where
+    I: Iterator<Item = csv::Result<csv::ByteRecord>>,
+{
+    type Item = csv::Result<(GroupKey, Group)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.spilled.next() {
+                return Some(Ok(entry));
+            }
+            if self.groups.is_empty() {
+                if self.done {
+                    return None;
+                }
+                if let Err(e) = self.fill() {
+                    return Some(Err(e));
+                }
+                continue;
+            }
+            self.spilled = mem::take(&mut self.groups).into_iter();
+            self.memory_used = 0;
+        }
+    }
+}
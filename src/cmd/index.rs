@@ -35,7 +35,7 @@ use csv_index::RandomAccessSimple;
 use serde::Deserialize;
 
 use crate::{
-    config::{Config, DEFAULT_WTR_BUFFER_CAPACITY},
+    config::{Codec, Config, DEFAULT_WTR_BUFFER_CAPACITY},
     util, CliResult,
 };
 
@@ -48,8 +48,8 @@ struct Args {
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
-    if args.arg_input.to_lowercase().ends_with(".sz") {
-        return fail_incorrectusage_clierror!("Cannot index a snappy file.");
+    if Codec::from_path(Path::new(&args.arg_input)).is_compressed() {
+        return fail_incorrectusage_clierror!("Cannot index a compressed file.");
     }
 
     let pidx = match args.flag_output {
@@ -1,5 +1,5 @@
 static USAGE: &str = r#"
-Joins two sets of CSV data on the specified columns.
+Joins two or more sets of CSV data on the specified columns.
 
 The default join operation is an 'inner' join. This corresponds to the
 intersection of rows on the keys specified.
@@ -11,13 +11,20 @@ flag.
 The columns arguments specify the columns to join for each input. Columns can
 be referenced by name or index, starting at 1. Specify multiple columns by
 separating them with a comma. Specify a range of columns with `-`. Both
-columns1 and columns2 must specify exactly the same number of columns.
+columns1 and columns2 must specify exactly the same number of columns,
+except for interval joins (see --interval below).
 (See 'qsv select --help' for the full syntax.)
 
+More than two inputs can be joined in one command by adding more column/input
+pairs. They are joined in order: <input1> is joined with <input2>, that result
+is joined with the next input and so on, always on the <columns1> key columns
+of <input1>. Use --coalesce-keys so rows only found in a later input of a
+--right or --full join still have a key to join on.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_join.rs.
 
 Usage:
-    qsv join [options] <columns1> <input1> <columns2> <input2>
+    qsv join [options] <columns1> <input1> <columns2> <input2> [<more-columns> <more-input>]...
     qsv join --help
 
 input parameters:
@@ -57,6 +64,60 @@ join options:
                            (In fact, any row that has an empty field in the
                            key specified is ignored.)
 
+column naming options:
+    --left-prefix <arg>    Prefix the column names of <input1> with <arg>.
+    --right-prefix <arg>   Prefix the column names of <input2> and any further
+                           inputs with <arg>. "{n}" in <arg> is replaced with
+                           the position of the input, e.g. "in{n}_" gives "in2_"
+                           for <input2>, "in3_" for the next input and so on.
+    --right-suffix <arg>   Append <arg> to the column names of <input2> and any
+                           further inputs that would otherwise duplicate a
+                           column name already in the output, e.g. "_right".
+                           "{n}" is replaced as with --right-prefix.
+    --coalesce-keys        Output a single set of key columns. The key columns
+                           of <input1> are kept and filled in from the other
+                           input when empty, e.g. for rows only found in the
+                           other input of a --right or --full join. The key
+                           columns of the other input are dropped.
+
+fuzzy join options:
+    --fuzzy <algo:thres>   Join rows whose keys are similar instead of equal.
+                           <algo> is one of "jaro-winkler", "sorensen-dice" or
+                           "damerau-levenshtein" (normalized), and rows are
+                           joined when their similarity score (0.0 to 1.0) is
+                           at least <thres>, e.g. "jaro-winkler:0.9".
+                           The score is appended as a "fuzzy_score" column.
+                           Only inner (default) and --left joins are supported.
+                           Note that <input2> is loaded into memory.
+    --top-k <n>            The maximum number of matches above the threshold to
+                           return for each row in <input1>, best first.
+                           [default: 1]
+    --block <arg>          Only compare rows that share a blocking key, to keep
+                           fuzzy joins tractable on large inputs. One of:
+                             none       - compare every pair of rows. A warning
+                                          is shown when both inputs are large.
+                             prefix:<n> - keys share the first <n> characters.
+                             eudex      - keys start with the same sound, per
+                                          the eudex phonetic hash.
+                           [default: none]
+
+interval join options:
+    --interval <type>      Join rows on ranges of values instead of equal values,
+                           comparing values as <type> "number" or "date".
+                           <columns2> must select the start and end columns of
+                           the intervals in <input2>, with both bounds inclusive.
+                           If <columns1> selects a single column, rows are joined
+                           when its value is between the start and end of an
+                           <input2> interval. If it selects two columns (a start
+                           and an end), rows are joined when the intervals overlap.
+                           Rows with empty, unparseable or reversed bounds never
+                           match. Intervals are matched with a sorted sweep, so
+                           this scales to large inputs. Only inner (default)
+                           and --left joins are supported.
+                           Note that the <input2> rows with valid bounds are
+                           loaded into memory, along with the intervals of both
+                           inputs and the <input2> matches of each <input1> row.
+
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
@@ -69,8 +130,9 @@ Common options:
 
 use std::{collections::hash_map::Entry, fmt, io, iter::repeat, str};
 
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
 use byteorder::{BigEndian, WriteBytesExt};
+use qsv_dateparser::parse_with_preference;
 use serde::Deserialize;
 
 use crate::{
@@ -82,93 +144,103 @@ use crate::{
     CliResult,
 };
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 struct Args {
-    arg_columns1:     SelectColumns,
-    arg_input1:       String,
-    arg_columns2:     SelectColumns,
-    arg_input2:       String,
-    flag_left:        bool,
-    flag_left_anti:   bool,
-    flag_left_semi:   bool,
-    flag_right:       bool,
-    flag_full:        bool,
-    flag_cross:       bool,
-    flag_output:      Option<String>,
-    flag_no_headers:  bool,
-    flag_ignore_case: bool,
-    flag_nulls:       bool,
-    flag_delimiter:   Option<Delimiter>,
+    arg_columns1:       SelectColumns,
+    arg_input1:         String,
+    arg_columns2:       SelectColumns,
+    arg_input2:         String,
+    flag_left:          bool,
+    flag_left_anti:     bool,
+    flag_left_semi:     bool,
+    flag_right:         bool,
+    flag_full:          bool,
+    flag_cross:         bool,
+    flag_output:        Option<String>,
+    flag_no_headers:    bool,
+    flag_ignore_case:   bool,
+    flag_nulls:         bool,
+    flag_delimiter:     Option<Delimiter>,
+    flag_fuzzy:         Option<String>,
+    flag_top_k:         usize,
+    flag_block:         String,
+    flag_interval:      Option<String>,
+    flag_left_prefix:   Option<String>,
+    flag_right_prefix:  Option<String>,
+    flag_right_suffix:  Option<String>,
+    flag_coalesce_keys: bool,
+    arg_more_columns:   Vec<SelectColumns>,
+    arg_more_input:     Vec<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
-    let mut state = args.new_io_state()?;
-    match (
-        args.flag_left,
-        args.flag_left_anti,
-        args.flag_left_semi,
-        args.flag_right,
-        args.flag_full,
-        args.flag_cross,
-    ) {
-        (true, false, false, false, false, false) => {
-            state.write_headers()?;
-            state.outer_join(false)
-        },
-        (false, true, false, false, false, false) => {
-            state.write_headers1()?;
-            state.left_join(true)
-        },
-        (false, false, true, false, false, false) => {
-            state.write_headers1()?;
-            state.left_join(false)
-        },
-        (false, false, false, true, false, false) => {
-            state.write_headers()?;
-            state.outer_join(true)
-        },
-        (false, false, false, false, true, false) => {
-            state.write_headers()?;
-            state.full_outer_join()
-        },
-        (false, false, false, false, false, true) => {
-            state.write_headers()?;
-            state.cross_join()
-        },
-        (false, false, false, false, false, false) => {
-            state.write_headers()?;
-            state.inner_join()
-        },
-        _ => fail_incorrectusage_clierror!("Please pick exactly one join operation."),
+    if args.flag_fuzzy.is_some() && args.flag_interval.is_some() {
+        return fail_incorrectusage_clierror!("--fuzzy and --interval cannot be used together.");
     }
+    if args.flag_coalesce_keys
+        && (args.flag_cross || args.flag_fuzzy.is_some() || args.flag_interval.is_some())
+    {
+        return fail_incorrectusage_clierror!(
+            "--coalesce-keys cannot be used with --cross, --fuzzy or --interval."
+        );
+    }
+    if !args.arg_more_input.is_empty()
+        && (args.flag_fuzzy.is_some() || args.flag_interval.is_some())
+    {
+        return fail_incorrectusage_clierror!("--fuzzy and --interval only support two inputs.");
+    }
+    args.join_all()
 }
 
 struct IoState<R, W: io::Write> {
-    wtr:        csv::Writer<W>,
-    rdr1:       csv::Reader<R>,
-    sel1:       Selection,
-    rdr2:       csv::Reader<R>,
-    sel2:       Selection,
-    no_headers: bool,
-    casei:      bool,
-    nulls:      bool,
+    wtr:          JoinWriter<W>,
+    rdr1:         csv::Reader<R>,
+    sel1:         Selection,
+    rdr2:         csv::Reader<R>,
+    sel2:         Selection,
+    no_headers:   bool,
+    casei:        bool,
+    nulls:        bool,
+    left_prefix:  String,
+    right_prefix: String,
+    right_suffix: String,
 }
 
 impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
+    /// Returns the headers of both inputs, renamed with the column naming options.
+    fn headers(&mut self) -> CliResult<(csv::ByteRecord, csv::ByteRecord)> {
+        let mut headers1 = csv::ByteRecord::new();
+        let mut names = AHashSet::new();
+        for name in self.rdr1.byte_headers()? {
+            let name = [self.left_prefix.as_bytes(), name].concat();
+            headers1.push_field(&name);
+            names.insert(name);
+        }
+        let mut headers2 = csv::ByteRecord::new();
+        for name in self.rdr2.byte_headers()? {
+            let mut name = [self.right_prefix.as_bytes(), name].concat();
+            if !self.right_suffix.is_empty() && names.contains(&name) {
+                name.extend_from_slice(self.right_suffix.as_bytes());
+            }
+            headers2.push_field(&name);
+            names.insert(name);
+        }
+        Ok((headers1, headers2))
+    }
+
     fn write_headers(&mut self) -> CliResult<()> {
         if !self.no_headers {
-            let mut headers = self.rdr1.byte_headers()?.clone();
-            headers.extend(self.rdr2.byte_headers()?.iter());
-            self.wtr.write_record(&headers)?;
+            let (headers1, headers2) = self.headers()?;
+            self.wtr.write_joined(&headers1, &headers2)?;
         }
         Ok(())
     }
 
     fn write_headers1(&mut self) -> CliResult<()> {
         if !self.no_headers {
-            let headers = self.rdr1.byte_headers()?;
-            self.wtr.write_record(headers)?;
+            let (headers1, _) = self.headers()?;
+            self.wtr.write_record(&headers1)?;
         }
         Ok(())
     }
@@ -185,8 +257,7 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
                     validx.idx.seek(rowi as u64)?;
 
                     validx.idx.read_byte_record(&mut scratch)?;
-                    let combined = row.iter().chain(scratch.iter());
-                    self.wtr.write_record(combined)?;
+                    self.wtr.write_joined(&row, &scratch)?;
                 }
             }
         }
@@ -209,18 +280,17 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
             if let Some(rows) = validx.values.get(&key) {
                 for &rowi in rows {
                     validx.idx.seek(rowi as u64)?;
-                    let row1 = row.iter();
                     validx.idx.read_byte_record(&mut scratch)?;
                     if right {
-                        self.wtr.write_record(scratch.iter().chain(row1))?;
+                        self.wtr.write_joined(&scratch, &row)?;
                     } else {
-                        self.wtr.write_record(row1.chain(&scratch))?;
+                        self.wtr.write_joined(&row, &scratch)?;
                     }
                 }
             } else if right {
-                self.wtr.write_record(pad2.iter().chain(&row))?;
+                self.wtr.write_joined(&pad2, &row)?;
             } else {
-                self.wtr.write_record(row.iter().chain(&pad2))?;
+                self.wtr.write_joined(&row, &pad2)?;
             }
         }
         Ok(self.wtr.flush()?)
@@ -260,10 +330,10 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
 
                     validx.idx.seek(rowi as u64)?;
                     validx.idx.read_byte_record(&mut scratch)?;
-                    self.wtr.write_record(row1.iter().chain(&scratch))?;
+                    self.wtr.write_joined(&row1, &scratch)?;
                 }
             } else {
-                self.wtr.write_record(row1.iter().chain(&pad2))?;
+                self.wtr.write_joined(&row1, &pad2)?;
             }
         }
 
@@ -273,7 +343,7 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
             if !written {
                 validx.idx.seek(i as u64)?;
                 validx.idx.read_byte_record(&mut scratch)?;
-                self.wtr.write_record(pad1.iter().chain(&scratch))?;
+                self.wtr.write_joined(&pad1, &scratch)?;
             }
         }
         Ok(self.wtr.flush()?)
@@ -293,7 +363,86 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
                 self.rdr2.read_byte_record(&mut row2)?;
             }
             while self.rdr2.read_byte_record(&mut row2)? {
-                self.wtr.write_record(row1.iter().chain(&row2))?;
+                self.wtr.write_joined(&row1, &row2)?;
+            }
+        }
+        Ok(self.wtr.flush()?)
+    }
+
+    fn interval_join(mut self, kind: IntervalKind, left: bool) -> CliResult<()> {
+        let prefer_dmy = util::get_envvar_flag("QSV_PREFER_DMY");
+        let (_, pad2) = self.get_padding()?;
+
+        let mut intervals = Vec::new();
+        let mut rows2 = Vec::new();
+        let mut row = csv::ByteRecord::new();
+        while self.rdr2.read_byte_record(&mut row)? {
+            if let Some((start, end)) = get_interval(&self.sel2, &row, kind, prefer_dmy) {
+                intervals.push(Interval {
+                    start,
+                    end,
+                    left: false,
+                    idx: rows2.len(),
+                });
+                rows2.push(row.clone());
+            }
+        }
+        let mut num_rows1 = 0_usize;
+        while self.rdr1.read_byte_record(&mut row)? {
+            if let Some((start, end)) = get_interval(&self.sel1, &row, kind, prefer_dmy) {
+                intervals.push(Interval {
+                    start,
+                    end,
+                    left: true,
+                    idx: num_rows1,
+                });
+            }
+            num_rows1 += 1;
+        }
+
+        // Sweep the intervals in order of their start. Each interval overlaps
+        // the intervals of the other input that are still open at its start,
+        // i.e. those that started before it and haven't ended yet.
+        intervals.sort_by(|a, b| a.start.total_cmp(&b.start));
+        let mut matches: Vec<Vec<usize>> = vec![Vec::new(); num_rows1];
+        let mut open1: Vec<&Interval> = Vec::new();
+        let mut open2: Vec<&Interval> = Vec::new();
+        for interval in &intervals {
+            if interval.left {
+                open2.retain(|open| open.end >= interval.start);
+                matches[interval.idx].extend(open2.iter().map(|open| open.idx));
+                open1.push(interval);
+            } else {
+                open1.retain(|open| open.end >= interval.start);
+                for open in &open1 {
+                    matches[open.idx].push(interval.idx);
+                }
+                open2.push(interval);
+            }
+        }
+
+        // Now go over rdr1 again, writing its rows in their original order.
+        let mut pos = csv::Position::new();
+        pos.set_byte(0);
+        self.rdr1.seek(pos)?;
+        if self.rdr1.has_headers() {
+            // Read and skip the header row, since CSV readers disable
+            // the header skipping logic after being seeked.
+            self.rdr1.read_byte_record(&mut row)?;
+        }
+        for rows in &mut matches {
+            if !self.rdr1.read_byte_record(&mut row)? {
+                break;
+            }
+            if rows.is_empty() {
+                if left {
+                    self.wtr.write_joined(&row, &pad2)?;
+                }
+                continue;
+            }
+            rows.sort_unstable();
+            for &rowi in rows.iter() {
+                self.wtr.write_joined(&row, &rows2[rowi])?;
             }
         }
         Ok(self.wtr.flush()?)
@@ -309,9 +458,227 @@ impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
     }
 }
 
+#[cfg(feature = "apply")]
+impl<R: io::Read + io::Seek, W: io::Write> IoState<R, W> {
+    fn fuzzy_join(mut self, spec: &FuzzySpec, left: bool) -> CliResult<()> {
+        if !self.no_headers {
+            let (mut headers, headers2) = self.headers()?;
+            headers.extend(headers2.iter());
+            headers.push_field(b"fuzzy_score");
+            self.wtr.write_record(&headers)?;
+        }
+        let (_, pad2) = self.get_padding()?;
+
+        // load <input2> into memory, grouping its rows by blocking key
+        let mut rows2 = Vec::new();
+        let mut keys2 = Vec::new();
+        let mut blocks: AHashMap<String, Vec<usize>> = AHashMap::new();
+        let mut row = csv::ByteRecord::new();
+        while self.rdr2.read_byte_record(&mut row)? {
+            let key = get_fuzzy_key(&self.sel2, &row, self.casei);
+            if key.is_empty() && !self.nulls {
+                continue;
+            }
+            blocks
+                .entry(spec.block.key(&key))
+                .or_default()
+                .push(rows2.len());
+            keys2.push(key);
+            rows2.push(row.clone());
+        }
+
+        // without blocking, every pair of rows is compared, so warn once the
+        // inputs are large enough for the join to be very slow
+        let mut warn_at_row1 = match spec.block {
+            FuzzyBlock::None if !rows2.is_empty() => Some(FUZZY_UNBLOCKED_WARN_PAIRS / rows2.len()),
+            _ => None,
+        };
+        let mut num_rows1 = 0_usize;
+        let mut matches: Vec<(f64, usize)> = Vec::new();
+        while self.rdr1.read_byte_record(&mut row)? {
+            if warn_at_row1 == Some(num_rows1) {
+                wwarn!(
+                    "--block none compares every pair of rows, and over \
+                     {FUZZY_UNBLOCKED_WARN_PAIRS} pairs are being compared. Consider using \
+                     --block prefix:<n> or eudex."
+                );
+                warn_at_row1 = None;
+            }
+            num_rows1 += 1;
+            let key = get_fuzzy_key(&self.sel1, &row, self.casei);
+            matches.clear();
+            if !key.is_empty() || self.nulls {
+                if let Some(candidates) = blocks.get(&spec.block.key(&key)) {
+                    for &i in candidates {
+                        let score = spec.algo.score(&key, &keys2[i]);
+                        if score >= spec.threshold {
+                            matches.push((score, i));
+                        }
+                    }
+                }
+            }
+            // best matches first, ties are kept in <input2> order
+            matches.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+            matches.truncate(spec.top_k);
+
+            if matches.is_empty() {
+                if left {
+                    self.wtr
+                        .write_record(row.iter().chain(&pad2).chain(std::iter::once(&b""[..])))?;
+                }
+                continue;
+            }
+            for &(score, i) in &matches {
+                let score = util::round_num(score, 4);
+                self.wtr.write_record(
+                    row.iter()
+                        .chain(&rows2[i])
+                        .chain(std::iter::once(score.as_bytes())),
+                )?;
+            }
+        }
+        Ok(self.wtr.flush()?)
+    }
+}
+
 impl Args {
+    fn join(
+        &self,
+        mut state: IoState<Box<dyn SeekRead + 'static>, Box<dyn io::Write + 'static>>,
+    ) -> CliResult<()> {
+        if let Some(spec) = &self.flag_fuzzy {
+            if self.flag_left_anti
+                || self.flag_left_semi
+                || self.flag_right
+                || self.flag_full
+                || self.flag_cross
+            {
+                return fail_incorrectusage_clierror!(
+                    "--fuzzy only supports inner and --left joins."
+                );
+            }
+            return fuzzy_join(self, spec, state);
+        }
+        if let Some(kind) = &self.flag_interval {
+            if self.flag_left_anti
+                || self.flag_left_semi
+                || self.flag_right
+                || self.flag_full
+                || self.flag_cross
+            {
+                return fail_incorrectusage_clierror!(
+                    "--interval only supports inner and --left joins."
+                );
+            }
+            let kind = match kind.to_ascii_lowercase().as_str() {
+                "number" => IntervalKind::Number,
+                "date" => IntervalKind::Date,
+                _ => {
+                    return fail_incorrectusage_clierror!(
+                        "--interval must be either number or date, got \"{kind}\"."
+                    )
+                },
+            };
+            state.write_headers()?;
+            return state.interval_join(kind, self.flag_left);
+        }
+        match (
+            self.flag_left,
+            self.flag_left_anti,
+            self.flag_left_semi,
+            self.flag_right,
+            self.flag_full,
+            self.flag_cross,
+        ) {
+            (true, false, false, false, false, false) => {
+                state.write_headers()?;
+                state.outer_join(false)
+            },
+            (false, true, false, false, false, false) => {
+                state.write_headers1()?;
+                state.left_join(true)
+            },
+            (false, false, true, false, false, false) => {
+                state.write_headers1()?;
+                state.left_join(false)
+            },
+            (false, false, false, true, false, false) => {
+                state.write_headers()?;
+                state.outer_join(true)
+            },
+            (false, false, false, false, true, false) => {
+                state.write_headers()?;
+                state.full_outer_join()
+            },
+            (false, false, false, false, false, true) => {
+                state.write_headers()?;
+                state.cross_join()
+            },
+            (false, false, false, false, false, false) => {
+                state.write_headers()?;
+                state.inner_join()
+            },
+            _ => fail_incorrectusage_clierror!("Please pick exactly one join operation."),
+        }
+    }
+
+    fn join_all(&self) -> CliResult<()> {
+        let tmpdir = tempfile::tempdir()?;
+        let num_joins = self.arg_more_input.len() + 1;
+        let inputs = std::iter::once((&self.arg_columns2, &self.arg_input2))
+            .chain(self.arg_more_columns.iter().zip(&self.arg_more_input));
+
+        let mut step = self.clone();
+        let mut prev_output = String::new();
+        let mut keys1 = String::new();
+        for (i, (columns, input)) in inputs.enumerate() {
+            let n = (i + 2).to_string();
+            if i > 0 {
+                // join the result so far, where the key columns of <input1> still come first
+                step.arg_input1 = prev_output;
+                step.arg_columns1 = SelectColumns::parse(&keys1)?;
+                step.flag_left_prefix = None;
+            }
+            step.arg_columns2 = columns.clone();
+            step.arg_input2 = input.clone();
+            step.flag_right_prefix = self
+                .flag_right_prefix
+                .as_ref()
+                .map(|p| p.replace("{n}", &n));
+            step.flag_right_suffix = self
+                .flag_right_suffix
+                .as_ref()
+                .map(|s| s.replace("{n}", &n));
+            prev_output = tmpdir
+                .path()
+                .join(format!("join{n}.csv"))
+                .to_string_lossy()
+                .into_owned();
+            // intermediate results are written with the input delimiter, as that's
+            // what they'll be read with when joining the next input
+            let state = if i + 1 == num_joins {
+                step.flag_output.clone_from(&self.flag_output);
+                step.new_io_state(None)?
+            } else {
+                step.flag_output = Some(prev_output.clone());
+                step.new_io_state(self.flag_delimiter)?
+            };
+            if i == 0 {
+                keys1 = state
+                    .sel1
+                    .iter()
+                    .map(|k| (k + 1).to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+            step.join(state)?;
+        }
+        Ok(())
+    }
+
     fn new_io_state(
         &self,
+        out_delimiter: Option<Delimiter>,
     ) -> CliResult<IoState<Box<dyn SeekRead + 'static>, Box<dyn io::Write + 'static>>> {
         let rconf1 = Config::new(&Some(self.arg_input1.clone()))
             .delimiter(self.flag_delimiter)
@@ -325,8 +692,17 @@ impl Args {
         let mut rdr1 = rconf1.reader_file_stdin()?;
         let mut rdr2 = rconf2.reader_file_stdin()?;
         let (sel1, sel2) = self.get_selections(&rconf1, &mut rdr1, &rconf2, &mut rdr2)?;
+        let coalesce = self
+            .flag_coalesce_keys
+            .then(|| (sel1.to_vec(), sel2.to_vec()));
         Ok(IoState {
-            wtr: Config::new(&self.flag_output).writer()?,
+            wtr: JoinWriter {
+                wtr: Config::new(&self.flag_output)
+                    .delimiter(out_delimiter)
+                    .writer()?,
+                coalesce,
+                record: csv::ByteRecord::new(),
+            },
             rdr1,
             sel1,
             rdr2,
@@ -334,10 +710,12 @@ impl Args {
             no_headers: rconf1.no_headers,
             casei: self.flag_ignore_case,
             nulls: self.flag_nulls,
+            left_prefix: self.flag_left_prefix.clone().unwrap_or_default(),
+            right_prefix: self.flag_right_prefix.clone().unwrap_or_default(),
+            right_suffix: self.flag_right_suffix.clone().unwrap_or_default(),
         })
     }
 
-    #[allow(clippy::unused_self)]
     fn get_selections<R: io::Read>(
         &self,
         rconf1: &Config,
@@ -349,7 +727,17 @@ impl Args {
         let headers2 = rdr2.byte_headers()?;
         let select1 = rconf1.selection(headers1)?;
         let select2 = rconf2.selection(headers2)?;
-        if select1.len() != select2.len() {
+        if self.flag_interval.is_some() {
+            if select2.len() != 2 || !(1..=2).contains(&select1.len()) {
+                return fail_incorrectusage_clierror!(
+                    "--interval requires <columns2> to select a start and an end column, and \
+                     <columns1> to select either one column or a start and an end column, but \
+                     found column selections with {} and {} columns.",
+                    select1.len(),
+                    select2.len()
+                );
+            }
+        } else if select1.len() != select2.len() {
             return fail_incorrectusage_clierror!(
                 "Column selections must have the same number of columns, but found column \
                  selections with {} and {} columns.",
@@ -361,6 +749,52 @@ impl Args {
     }
 }
 
+/// Writes joined rows, coalescing the key columns of both inputs when asked to.
+struct JoinWriter<W: io::Write> {
+    wtr:      csv::Writer<W>,
+    // the key column indices of both inputs, when coalescing keys
+    coalesce: Option<(Vec<usize>, Vec<usize>)>,
+    record:   csv::ByteRecord,
+}
+
+impl<W: io::Write> JoinWriter<W> {
+    #[inline]
+    fn write_record<I, T>(&mut self, record: I) -> csv::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.wtr.write_record(record)
+    }
+
+    fn write_joined(&mut self, row1: &csv::ByteRecord, row2: &csv::ByteRecord) -> csv::Result<()> {
+        let Some((keys1, keys2)) = &self.coalesce else {
+            return self.wtr.write_record(row1.iter().chain(row2));
+        };
+        self.record.clear();
+        for (i, field) in row1.iter().enumerate() {
+            match keys1.iter().position(|&k| k == i) {
+                Some(keyi) if field.is_empty() => {
+                    self.record
+                        .push_field(row2.get(keys2[keyi]).unwrap_or_default());
+                },
+                _ => self.record.push_field(field),
+            }
+        }
+        for (i, field) in row2.iter().enumerate() {
+            if !keys2.contains(&i) {
+                self.record.push_field(field);
+            }
+        }
+        self.wtr.write_byte_record(&self.record)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.wtr.flush()
+    }
+}
+
 struct ValueIndex<R> {
     // This maps tuples of values to corresponding rows.
     values:   AHashMap<Vec<ByteString>, Vec<usize>>,
@@ -433,6 +867,154 @@ impl<R: io::Read + io::Seek> ValueIndex<R> {
     }
 }
 
+#[derive(Clone, Copy)]
+enum IntervalKind {
+    Number,
+    Date,
+}
+
+impl IntervalKind {
+    #[allow(clippy::cast_precision_loss)]
+    fn parse_bound(self, bytes: &[u8], prefer_dmy: bool) -> Option<f64> {
+        let value = str::from_utf8(bytes).ok()?.trim();
+        match self {
+            IntervalKind::Number => value.parse::<f64>().ok().filter(|n| !n.is_nan()),
+            IntervalKind::Date => parse_with_preference(value, prefer_dmy)
+                .ok()
+                .map(|dt| dt.timestamp_millis() as f64),
+        }
+    }
+}
+
+struct Interval {
+    start: f64,
+    end:   f64,
+    left:  bool,
+    idx:   usize,
+}
+
+#[cfg(feature = "apply")]
+#[derive(Clone, Copy)]
+enum FuzzyAlgo {
+    JaroWinkler,
+    SorensenDice,
+    DamerauLevenshtein,
+}
+
+#[cfg(feature = "apply")]
+impl FuzzyAlgo {
+    fn score(self, a: &str, b: &str) -> f64 {
+        match self {
+            FuzzyAlgo::JaroWinkler => strsim::jaro_winkler(a, b),
+            FuzzyAlgo::SorensenDice => strsim::sorensen_dice(a, b),
+            FuzzyAlgo::DamerauLevenshtein => strsim::normalized_damerau_levenshtein(a, b),
+        }
+    }
+}
+
+// the number of row pairs compared by an unblocked fuzzy join before warning
+// that the join may be very slow
+#[cfg(feature = "apply")]
+const FUZZY_UNBLOCKED_WARN_PAIRS: usize = 100_000_000;
+
+#[cfg(feature = "apply")]
+enum FuzzyBlock {
+    None,
+    Prefix(usize),
+    Eudex,
+}
+
+#[cfg(feature = "apply")]
+impl FuzzyBlock {
+    fn key(&self, key: &str) -> String {
+        match self {
+            FuzzyBlock::None => String::new(),
+            FuzzyBlock::Prefix(n) => key.chars().take(*n).collect(),
+            // the most significant byte of a eudex hash encodes the leading sound
+            FuzzyBlock::Eudex => (u64::from(eudex::Hash::new(key)) >> 56).to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "apply")]
+struct FuzzySpec {
+    algo:      FuzzyAlgo,
+    threshold: f64,
+    top_k:     usize,
+    block:     FuzzyBlock,
+}
+
+#[cfg(feature = "apply")]
+impl FuzzySpec {
+    fn new(spec: &str, top_k: usize, block: &str) -> CliResult<FuzzySpec> {
+        let Some((algo, threshold)) = spec.split_once(':') else {
+            return fail_incorrectusage_clierror!(
+                "--fuzzy must be of the form <algo>:<threshold>, e.g. jaro-winkler:0.9"
+            );
+        };
+        let algo = match algo.to_ascii_lowercase().as_str() {
+            "jaro-winkler" => FuzzyAlgo::JaroWinkler,
+            "sorensen-dice" => FuzzyAlgo::SorensenDice,
+            "damerau-levenshtein" => FuzzyAlgo::DamerauLevenshtein,
+            _ => {
+                return fail_incorrectusage_clierror!(
+                    "Unknown --fuzzy algorithm \"{algo}\". Use jaro-winkler, sorensen-dice or \
+                     damerau-levenshtein."
+                )
+            },
+        };
+        let threshold = match threshold.parse::<f64>() {
+            Ok(t) if (0.0..=1.0).contains(&t) => t,
+            _ => {
+                return fail_incorrectusage_clierror!(
+                    "--fuzzy threshold must be a number between 0.0 and 1.0, got \"{threshold}\"."
+                )
+            },
+        };
+        if top_k == 0 {
+            return fail_incorrectusage_clierror!("--top-k must be greater than 0.");
+        }
+        let block = match block.to_ascii_lowercase().as_str() {
+            "none" => FuzzyBlock::None,
+            "eudex" => FuzzyBlock::Eudex,
+            other => match other.strip_prefix("prefix:").map(str::parse::<usize>) {
+                Some(Ok(n)) if n > 0 => FuzzyBlock::Prefix(n),
+                _ => {
+                    return fail_incorrectusage_clierror!(
+                        "--block must be one of none, prefix:<n> (n > 0) or eudex, got \
+                         \"{block}\"."
+                    )
+                },
+            },
+        };
+        Ok(FuzzySpec {
+            algo,
+            threshold,
+            top_k,
+            block,
+        })
+    }
+}
+
+#[cfg(feature = "apply")]
+fn fuzzy_join<R: io::Read + io::Seek, W: io::Write>(
+    args: &Args,
+    spec: &str,
+    state: IoState<R, W>,
+) -> CliResult<()> {
+    let spec = FuzzySpec::new(spec, args.flag_top_k, &args.flag_block)?;
+    state.fuzzy_join(&spec, args.flag_left)
+}
+
+#[cfg(not(feature = "apply"))]
+fn fuzzy_join<R: io::Read + io::Seek, W: io::Write>(
+    _args: &Args,
+    _spec: &str,
+    _state: IoState<R, W>,
+) -> CliResult<()> {
+    fail_incorrectusage_clierror!("--fuzzy requires qsv to be built with the apply feature.")
+}
+
 impl<R> fmt::Debug for ValueIndex<R> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Sort the values by order of first appearance.
@@ -454,3 +1036,25 @@ impl<R> fmt::Debug for ValueIndex<R> {
 fn get_row_key(sel: &Selection, row: &csv::ByteRecord, casei: bool) -> Vec<ByteString> {
     sel.select(row).map(|v| util::transform(v, casei)).collect()
 }
+
+fn get_interval(
+    sel: &Selection,
+    row: &csv::ByteRecord,
+    kind: IntervalKind,
+    prefer_dmy: bool,
+) -> Option<(f64, f64)> {
+    let mut bounds = sel.select(row).map(|v| kind.parse_bound(v, prefer_dmy));
+    let start = bounds.next()??;
+    // a single column is a point, i.e. an interval that starts and ends at the same value
+    let end = bounds.next().unwrap_or(Some(start))?;
+    (start <= end).then_some((start, end))
+}
+
+#[cfg(feature = "apply")]
+#[inline]
+fn get_fuzzy_key(sel: &Selection, row: &csv::ByteRecord, casei: bool) -> String {
+    sel.select(row)
+        .map(|v| String::from_utf8_lossy(&util::transform(v, casei)).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
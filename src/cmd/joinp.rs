@@ -5,10 +5,11 @@ The default join operation is an 'inner' join. This corresponds to the
 intersection of rows on the keys specified.
 
 Unlike the join command, joinp can process files larger than RAM, is multithreaded,
-has join key validation, pre-join filtering, supports asof joins & its output doesn't
-have duplicate columns.
+has join key validation, pre-join filtering, supports asof & interval joins & its output
+doesn't have duplicate columns.
 
-However, joinp doesn't have an --ignore-case option & it doesn't support right outer joins.
+However, joinp doesn't have an --ignore-case option & it doesn't support right outer joins,
+nor the fuzzy joins (--fuzzy) of the join command.
 
 Returns the shape of the join result (number of rows, number of columns) to stderr.
 
@@ -81,6 +82,14 @@ joinp options:
     --infer-len <arg>      The number of rows to scan when inferring the schema of the CSV.
                            Set to 0 to do a full table scan (warning: very slow).
                            (default: 250)
+    --stats-mode <arg>     How to use the stats cache of the input files. Valid values are:
+                             auto - if an input file has a current stats cache (i.e.
+                                    `qsv stats` was run & its results were cached),
+                                    use its inferred types for the Integer, Float &
+                                    String columns instead of inferring them from
+                                    the first --infer-len rows.
+                             none - don't use the stats cache.
+                           [default: auto]
     --low-memory           Use low memory mode when parsing CSVs. This will use less memory
                            but will be slower. It will also process the join in streaming mode.
                            Only use this when you get out of memory errors.
@@ -143,6 +152,22 @@ joinp options:
                              large for their month should saturate at the largest date
                              (e.g. 2022-02-29 -> 2022-02-28) instead of erroring.
 
+                           INTERVAL JOIN OPTIONS:
+    --interval <type>      Join rows on ranges of values instead of equal values,
+                           comparing values as <type> "number" or "date".
+                           <columns2> must select the start and end columns of
+                           the intervals in <input2>, with both bounds inclusive.
+                           If <columns1> selects a single column, rows are joined
+                           when its value is between the start and end of an
+                           <input2> interval. If it selects two columns (a start
+                           and an end), rows are joined when the intervals overlap.
+                           Rows with empty, unparseable or reversed bounds never
+                           match. With "date", --try-parsedates is enabled.
+                           Only inner (default) and --left joins are supported.
+                           Note that every row of <input1> is compared with every
+                           row of <input2>, i.e. a cross join of the interval
+                           columns filtered by the interval predicate.
+
                              OUTPUT FORMAT OPTIONS:
    --sql-filter <SQL>        The SQL expression to apply against the join result.
                              Ordinarily used to select columns and filter rows from 
@@ -173,13 +198,15 @@ use std::{
     io::{self, Write},
     path::Path,
     str,
+    sync::Arc,
 };
 
 use polars::{
     datatypes::AnyValue,
     prelude::{
-        AsOfOptions, AsofStrategy, CsvWriter, IntoLazy, JoinType, JoinValidation, LazyCsvReader,
-        LazyFileListReader, LazyFrame, SerWriter, SortMultipleOptions,
+        col, AsOfOptions, AsofStrategy, CsvWriter, DataFrame, DataType, IntoLazy, JoinArgs,
+        JoinType, JoinValidation, LazyCsvReader, LazyFileListReader, LazyFrame, SerWriter,
+        SortMultipleOptions,
     },
     sql::SQLContext,
 };
@@ -187,7 +214,12 @@ use serde::Deserialize;
 use smartstring::SmartString;
 use tempfile::tempdir;
 
-use crate::{cmd::sqlp::compress_output_if_needed, config::Delimiter, util, CliResult};
+use crate::{
+    cmd::sqlp::compress_output_if_needed,
+    config::{Codec, Delimiter},
+    statscache::StatsCache,
+    util, CliResult,
+};
 
 #[derive(Deserialize)]
 struct Args {
@@ -207,6 +239,7 @@ struct Args {
     flag_streaming:        bool,
     flag_try_parsedates:   bool,
     flag_infer_len:        usize,
+    flag_stats_mode:       String,
     flag_low_memory:       bool,
     flag_no_optimizations: bool,
     flag_ignore_errors:    bool,
@@ -215,6 +248,7 @@ struct Args {
     flag_right_by:         Option<String>,
     flag_strategy:         Option<String>,
     flag_tolerance:        Option<String>,
+    flag_interval:         Option<String>,
     flag_sql_filter:       Option<String>,
     flag_datetime_format:  Option<String>,
     flag_date_format:      Option<String>,
@@ -236,6 +270,27 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         args.flag_try_parsedates = true;
     }
 
+    let interval_kind = match args.flag_interval.as_deref().map(str::to_ascii_lowercase) {
+        None => None,
+        Some(kind) if kind == "number" => Some(IntervalKind::Number),
+        Some(kind) if kind == "date" => {
+            args.flag_try_parsedates = true;
+            Some(IntervalKind::Date)
+        },
+        Some(kind) => {
+            return fail_incorrectusage_clierror!(
+                "--interval must be either number or date, got \"{kind}\"."
+            )
+        },
+    };
+
+    if args.flag_stats_mode != "auto" && args.flag_stats_mode != "none" {
+        return fail_incorrectusage_clierror!(
+            "Invalid --stats-mode: {}. Valid values are: auto, none.",
+            args.flag_stats_mode
+        );
+    }
+
     let tmpdir = tempdir()?;
     let join = args.new_join(
         args.flag_try_parsedates,
@@ -255,71 +310,96 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Some(s) => return fail_incorrectusage_clierror!("Invalid join validation: {s}"),
     };
 
-    let join_shape: (usize, usize) = match (
-        args.flag_left,
-        args.flag_left_anti,
-        args.flag_left_semi,
-        args.flag_full,
-        args.flag_cross,
-        args.flag_asof,
-    ) {
-        (false, false, false, false, false, false) => join.run(JoinType::Inner, validation, false),
-        (true, false, false, false, false, false) => join.run(JoinType::Left, validation, false),
-        (false, true, false, false, false, false) => join.run(JoinType::Anti, validation, false),
-        (false, false, true, false, false, false) => join.run(JoinType::Semi, validation, false),
-        (false, false, false, true, false, false) => {
-            join.run(JoinType::Outer { coalesce: true }, validation, false)
-        },
-        (false, false, false, false, true, false) => join.run(JoinType::Cross, validation, false),
-        (false, false, false, false, false, true) => {
-            // safety: flag_strategy is always is_some() as it has a default value
-            args.flag_strategy = Some(args.flag_strategy.unwrap().to_lowercase());
-            let strategy = match args.flag_strategy.as_deref() {
-                Some("backward") | None => AsofStrategy::Backward,
-                Some("forward") => AsofStrategy::Forward,
-                Some("nearest") => AsofStrategy::Nearest,
-                Some(s) => return fail_clierror!("Invalid asof strategy: {}", s),
-            };
-
-            let mut asof_options = AsOfOptions {
-                strategy,
-                ..Default::default()
-            };
-
-            if strategy == AsofStrategy::Nearest {
-                if let Some(ref tolerance) = args.flag_tolerance {
-                    // If the tolerance is a positive integer, it is tolerance number of rows.
-                    // Otherwise, it is a tolerance date language spec.
-                    if let Ok(numeric_tolerance) = atoi_simd::parse_pos::<u64>(tolerance.as_bytes())
-                    {
-                        asof_options.tolerance = Some(AnyValue::UInt64(numeric_tolerance));
-                    } else {
-                        asof_options.tolerance_str = Some(tolerance.into());
+    let join_shape: (usize, usize) = if let Some(kind) = interval_kind {
+        if args.flag_left_anti
+            || args.flag_left_semi
+            || args.flag_full
+            || args.flag_cross
+            || args.flag_asof
+        {
+            return fail_incorrectusage_clierror!(
+                "--interval only supports inner and --left joins."
+            );
+        }
+        join.run_interval(kind, args.flag_left)?
+    } else {
+        match (
+            args.flag_left,
+            args.flag_left_anti,
+            args.flag_left_semi,
+            args.flag_full,
+            args.flag_cross,
+            args.flag_asof,
+        ) {
+            (false, false, false, false, false, false) => {
+                join.run(JoinType::Inner, validation, false)
+            },
+            (true, false, false, false, false, false) => {
+                join.run(JoinType::Left, validation, false)
+            },
+            (false, true, false, false, false, false) => {
+                join.run(JoinType::Anti, validation, false)
+            },
+            (false, false, true, false, false, false) => {
+                join.run(JoinType::Semi, validation, false)
+            },
+            (false, false, false, true, false, false) => {
+                join.run(JoinType::Outer { coalesce: true }, validation, false)
+            },
+            (false, false, false, false, true, false) => {
+                join.run(JoinType::Cross, validation, false)
+            },
+            (false, false, false, false, false, true) => {
+                // safety: flag_strategy is always is_some() as it has a default value
+                args.flag_strategy = Some(args.flag_strategy.unwrap().to_lowercase());
+                let strategy = match args.flag_strategy.as_deref() {
+                    Some("backward") | None => AsofStrategy::Backward,
+                    Some("forward") => AsofStrategy::Forward,
+                    Some("nearest") => AsofStrategy::Nearest,
+                    Some(s) => return fail_clierror!("Invalid asof strategy: {}", s),
+                };
+
+                let mut asof_options = AsOfOptions {
+                    strategy,
+                    ..Default::default()
+                };
+
+                if strategy == AsofStrategy::Nearest {
+                    if let Some(ref tolerance) = args.flag_tolerance {
+                        // If the tolerance is a positive integer, it is tolerance number of rows.
+                        // Otherwise, it is a tolerance date language spec.
+                        if let Ok(numeric_tolerance) =
+                            atoi_simd::parse_pos::<u64>(tolerance.as_bytes())
+                        {
+                            asof_options.tolerance = Some(AnyValue::UInt64(numeric_tolerance));
+                        } else {
+                            asof_options.tolerance_str = Some(tolerance.into());
+                        }
                     }
                 }
-            }
-            if args.flag_left_by.is_some() {
-                asof_options.left_by = Some(
-                    args.flag_left_by
-                        .unwrap()
-                        .split(',')
-                        .map(smartstring::SmartString::from)
-                        .collect(),
-                );
-            }
-            if args.flag_right_by.is_some() {
-                asof_options.right_by = Some(
-                    args.flag_right_by
-                        .unwrap()
-                        .split(',')
-                        .map(smartstring::SmartString::from)
-                        .collect(),
-                );
-            }
-            join.run(JoinType::AsOf(asof_options), validation, true)
-        },
-        _ => fail_incorrectusage_clierror!("Please pick exactly one join operation."),
-    }?;
+                if args.flag_left_by.is_some() {
+                    asof_options.left_by = Some(
+                        args.flag_left_by
+                            .unwrap()
+                            .split(',')
+                            .map(smartstring::SmartString::from)
+                            .collect(),
+                    );
+                }
+                if args.flag_right_by.is_some() {
+                    asof_options.right_by = Some(
+                        args.flag_right_by
+                            .unwrap()
+                            .split(',')
+                            .map(smartstring::SmartString::from)
+                            .collect(),
+                    );
+                }
+                join.run(JoinType::AsOf(asof_options), validation, true)
+            },
+            _ => fail_incorrectusage_clierror!("Please pick exactly one join operation."),
+        }?
+    };
 
     if !args.flag_quiet {
         eprintln!("{join_shape:?}");
@@ -328,6 +408,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+#[derive(Clone, Copy)]
+enum IntervalKind {
+    Number,
+    Date,
+}
+
 struct JoinStruct {
     left_lf:          LazyFrame,
     left_sel:         String,
@@ -373,29 +459,7 @@ impl JoinStruct {
             );
         }
 
-        let optimization_state = if self.no_optimizations {
-            // use default optimization state
-            polars::lazy::frame::OptState {
-                streaming: self.streaming,
-                ..Default::default()
-            }
-        } else {
-            polars::lazy::frame::OptState {
-                projection_pushdown: true,
-                predicate_pushdown:  true,
-                type_coercion:       true,
-                simplify_expr:       true,
-                file_caching:        true,
-                slice_pushdown:      true,
-                comm_subplan_elim:   true,
-                comm_subexpr_elim:   true,
-                streaming:           self.streaming,
-                fast_projection:     true,
-                eager:               false,
-                row_estimate:        true,
-            }
-        };
-        log::debug!("Optimization state: {optimization_state:?}");
+        let optimization_state = self.optimization_state();
 
         let join_results = if jointype == JoinType::Cross {
             self.left_lf
@@ -438,6 +502,144 @@ impl JoinStruct {
                 .collect()?
         };
 
+        self.write_join_results(join_results)
+    }
+
+    /// Joins the rows of the left and right tables whose intervals match.
+    /// The left table selects either a point column or start & end columns,
+    /// and the right table always selects start & end columns, like the
+    /// --interval option of the join command. As polars doesn't have range
+    /// joins, the interval columns of both tables are cross joined & filtered
+    /// by the interval predicate, and the matching row indices are then used
+    /// to join back the full rows.
+    fn run_interval(self, kind: IntervalKind, left_join: bool) -> CliResult<(usize, usize)> {
+        const LEFT_IDX: &str = "__qsv_left_idx";
+        const RIGHT_IDX: &str = "__qsv_right_idx";
+
+        let left_cols: Vec<&str> = self.left_sel.split(',').collect();
+        let right_cols: Vec<&str> = self.right_sel.split(',').collect();
+        if right_cols.len() != 2 {
+            return fail_incorrectusage_clierror!(
+                "--interval requires columns2 to select exactly 2 columns (the start and end of \
+                 the intervals), got {}.",
+                right_cols.len()
+            );
+        }
+        let (left_start, left_end) = match left_cols.as_slice() {
+            [point] => (*point, *point),
+            [start, end] => (*start, *end),
+            _ => {
+                return fail_incorrectusage_clierror!(
+                    "--interval requires columns1 to select 1 column (a point) or 2 columns (the \
+                     start and end of the intervals), got {}.",
+                    left_cols.len()
+                )
+            },
+        };
+
+        let bound = |name: &str, alias: &str| match kind {
+            IntervalKind::Number => col(name).cast(DataType::Float64).alias(alias),
+            IntervalKind::Date => col(name).alias(alias),
+        };
+
+        let optimization_state = self.optimization_state();
+        let left_lf = self
+            .left_lf
+            .clone()
+            .with_optimizations(optimization_state)
+            .with_row_index(LEFT_IDX, None);
+        let right_lf = self
+            .right_lf
+            .clone()
+            .with_optimizations(optimization_state)
+            .with_row_index(RIGHT_IDX, None);
+
+        let left_keys = left_lf.clone().select([
+            col(LEFT_IDX),
+            bound(left_start, "__qsv_left_start"),
+            bound(left_end, "__qsv_left_end"),
+        ]);
+        let right_keys = right_lf.clone().select([
+            col(RIGHT_IDX),
+            bound(right_cols[0], "__qsv_right_start"),
+            bound(right_cols[1], "__qsv_right_end"),
+        ]);
+
+        // nulls never satisfy the comparisons, so rows with empty or
+        // unparseable bounds don't match, and neither do reversed intervals
+        let matches = left_keys
+            .join_builder()
+            .with(right_keys)
+            .how(JoinType::Cross)
+            .allow_parallel(true)
+            .finish()
+            .filter(
+                col("__qsv_left_start")
+                    .lt_eq(col("__qsv_right_end"))
+                    .and(col("__qsv_left_end").gt_eq(col("__qsv_right_start")))
+                    .and(col("__qsv_left_start").lt_eq(col("__qsv_left_end")))
+                    .and(col("__qsv_right_start").lt_eq(col("__qsv_right_end"))),
+            )
+            .select([col(LEFT_IDX), col(RIGHT_IDX)]);
+
+        let idx_cols: Vec<SmartString<smartstring::LazyCompact>> =
+            vec![LEFT_IDX.into(), RIGHT_IDX.into()];
+        let join_results = left_lf
+            .join(
+                matches,
+                [col(LEFT_IDX)],
+                [col(LEFT_IDX)],
+                JoinArgs::new(if left_join {
+                    JoinType::Left
+                } else {
+                    JoinType::Inner
+                }),
+            )
+            .join(
+                right_lf,
+                [col(RIGHT_IDX)],
+                [col(RIGHT_IDX)],
+                JoinArgs::new(JoinType::Left),
+            )
+            .sort(
+                &idx_cols,
+                SortMultipleOptions::default().with_nulls_last(true),
+            )
+            .select([col("*").exclude([LEFT_IDX, RIGHT_IDX])])
+            .collect()?;
+
+        self.write_join_results(join_results)
+    }
+
+    fn optimization_state(&self) -> polars::lazy::frame::OptState {
+        let optimization_state = if self.no_optimizations {
+            // use default optimization state
+            polars::lazy::frame::OptState {
+                streaming: self.streaming,
+                ..Default::default()
+            }
+        } else {
+            polars::lazy::frame::OptState {
+                projection_pushdown: true,
+                predicate_pushdown:  true,
+                type_coercion:       true,
+                simplify_expr:       true,
+                file_caching:        true,
+                slice_pushdown:      true,
+                comm_subplan_elim:   true,
+                comm_subexpr_elim:   true,
+                streaming:           self.streaming,
+                fast_projection:     true,
+                eager:               false,
+                row_estimate:        true,
+            }
+        };
+        log::debug!("Optimization state: {optimization_state:?}");
+        optimization_state
+    }
+
+    /// applies the --sql-filter, if any, to the join results and writes them out
+    fn write_join_results(self, join_results: DataFrame) -> CliResult<(usize, usize)> {
         let mut results_df = if let Some(sql_filter) = &self.sql_filter {
             let mut ctx = SQLContext::new();
             ctx.register("join_result", join_results.lazy());
@@ -516,12 +718,24 @@ impl Args {
             return fail_clierror!("Input file {} does not exist.", self.arg_input2);
         }
 
+        // use the column types of the stats caches, if available.
+        // The caches are looked up before decompressing the input files.
+        let stats_dtypes = |input_path: &Path| {
+            if self.flag_stats_mode == "auto" {
+                StatsCache::load(input_path)
+                    .map(|stats_cache| Arc::new(stats_cache.polars_dtypes(try_parsedates)))
+            } else {
+                None
+            }
+        };
+        let left_dtypes = stats_dtypes(input1_path);
+        let right_dtypes = stats_dtypes(input2_path);
+
         let mut left_lf = {
-            // check if the left input file is snappy compressed
+            // check if the left input file is compressed
             // if so, we need to decompress it first
-            if input1_path.extension().and_then(std::ffi::OsStr::to_str) == Some("sz") {
-                let decompressed_path =
-                    util::decompress_snappy_file(&input1_path.to_path_buf(), tmpdir)?;
+            if Codec::from_path(input1_path).is_compressed() {
+                let decompressed_path = util::decompress_file(input1_path, tmpdir)?;
                 self.arg_input1 = decompressed_path;
             }
 
@@ -531,6 +745,7 @@ impl Args {
                 .with_comment_prefix(comment_char.as_deref())
                 .with_separator(tsvtab_delim(&self.arg_input1, delim))
                 .with_infer_schema_length(num_rows)
+                .with_dtype_overwrite(left_dtypes)
                 .with_try_parse_dates(try_parsedates)
                 .low_memory(low_memory)
                 .with_ignore_errors(ignore_errors)
@@ -543,10 +758,9 @@ impl Args {
         }
 
         let mut right_lf = {
-            // check if the right input file is snappy compressed
-            if input2_path.extension().and_then(std::ffi::OsStr::to_str) == Some("sz") {
-                let decompressed_path =
-                    util::decompress_snappy_file(&input2_path.to_path_buf(), tmpdir)?;
+            // check if the right input file is compressed
+            if Codec::from_path(input2_path).is_compressed() {
+                let decompressed_path = util::decompress_file(input2_path, tmpdir)?;
                 self.arg_input2 = decompressed_path;
             }
 
@@ -556,6 +770,7 @@ impl Args {
                 .with_comment_prefix(comment_char.as_deref())
                 .with_separator(tsvtab_delim(&self.arg_input2, delim))
                 .with_infer_schema_length(num_rows)
+                .with_dtype_overwrite(right_dtypes)
                 .with_try_parse_dates(try_parsedates)
                 .low_memory(low_memory)
                 .with_ignore_errors(ignore_errors)
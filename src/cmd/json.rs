@@ -0,0 +1,347 @@
+static USAGE: &str = r#"
+Convert JSON to CSV.
+
+Unlike `jsonl`, which converts newline-delimited JSON, this command converts a
+JSON document with an array of records, such as a typical API export. The array
+is streamed one record at a time, so the document can be larger than memory.
+By default, the array is expected at the top level of the document. Use
+--pointer to convert an array nested in the document instead. If the value
+found is a single object rather than an array, it is converted to one record.
+
+Nested objects are flattened into columns named by joining the keys on the path
+with --separator, e.g. {"a": {"b": 1}} becomes the column "a.b". Values that
+are not objects are converted to a column named "value". Arrays are handled
+according to --arrays:
+    stringify  Write the array as a JSON string (default).
+    explode    Write one row per array element, with the other columns repeated.
+               When a record has several arrays, a row is written for each
+               combination of their elements.
+    index      Write each element to its own column, suffixed with its
+               position, e.g. "tags.0", "tags.1".
+
+The headers are the union of the columns of all the records, in order of first
+appearance, so records don't need to have the same keys. Missing values are
+written as empty fields. To do this in one pass, the flattened records are
+spooled to a temporary file before writing the CSV.
+
+Examples:
+    # convert the array of records under the "data" key of an API response
+    qsv json --pointer /data response.json
+
+    # write a row for each item of each order
+    qsv json --arrays explode orders.json -o order_items.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_json.rs.
+
+Usage:
+    qsv json [options] [<input>]
+    qsv json --help
+
+json options:
+    -p, --pointer <ptr>    A JSON Pointer (RFC 6901) to the records to convert,
+                           e.g. "/data/items" or "/results/0/rows".
+    --jql <selector>       A jql selector for the records to convert, e.g.
+                           '"data""items"'. Unlike --pointer, this loads the
+                           whole document into memory. Only available when qsv
+                           is built with the fetch feature, which provides jql.
+                           See https://github.com/yamafaktory/jql for the syntax.
+                           Mutually exclusive with --pointer.
+    -s, --separator <sep>  The separator used to join the keys of nested
+                           objects into column names. [default: .]
+    -a, --arrays <mode>    How to convert arrays. One of stringify, explode
+                           or index. [default: stringify]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The delimiter to use when writing CSV data.
+                           Must be a single character. [default: ,]
+"#;
+
+use std::{fmt, io};
+
+use indexmap::IndexSet;
+use serde::{
+    de::{self, value::MapAccessDeserializer, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess},
+    Deserialize, Deserializer,
+};
+use serde_json::Value;
+
+use crate::{
+    config::{Config, Delimiter, DEFAULT_RDR_BUFFER_CAPACITY},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:      Option<String>,
+    flag_pointer:   Option<String>,
+    flag_jql:       Option<String>,
+    flag_separator: String,
+    flag_arrays:    String,
+    flag_output:    Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Clone, Copy)]
+enum ArrayMode {
+    Stringify,
+    Explode,
+    Index,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let arrays = match args.flag_arrays.to_lowercase().as_str() {
+        "stringify" => ArrayMode::Stringify,
+        "explode" => ArrayMode::Explode,
+        "index" => ArrayMode::Index,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --arrays mode: {}. Use stringify, explode or index.",
+                args.flag_arrays
+            )
+        },
+    };
+    if args.flag_pointer.is_some() && args.flag_jql.is_some() {
+        return fail_incorrectusage_clierror!("--pointer and --jql are mutually exclusive.");
+    }
+    let path = match args.flag_pointer {
+        Some(ref pointer) => parse_pointer(pointer)?,
+        None => Vec::new(),
+    };
+
+    let mut flattener = Flattener {
+        separator: args.flag_separator,
+        arrays,
+        headers: IndexSet::new(),
+    };
+
+    // first pass: flatten the records to a temp file, collecting the headers
+    let mut spool = util::RecordSpool::new()?;
+    let mut on_record = |record: Value| -> CliResult<()> {
+        for row in flattener.flatten(&record) {
+            spool.push(&row)?;
+        }
+        Ok(())
+    };
+
+    let rdr = io::BufReader::with_capacity(
+        DEFAULT_RDR_BUFFER_CAPACITY,
+        Config::new(&args.arg_input).io_reader()?,
+    );
+    if let Some(ref selector) = args.flag_jql {
+        for record in select_jql(rdr, selector)? {
+            on_record(record)?;
+        }
+    } else {
+        let mut deserializer = serde_json::Deserializer::from_reader(rdr);
+        let found = RecordStream {
+            path:      &path,
+            on_record: &mut on_record,
+        }
+        .deserialize(&mut deserializer)
+        .and_then(|found| deserializer.end().map(|()| found));
+        match found {
+            Ok(true) => {},
+            Ok(false) => {
+                return fail_clierror!(
+                    "Nothing found at {}.",
+                    args.flag_pointer.unwrap_or_default()
+                )
+            },
+            Err(e) => return fail_clierror!("Cannot convert JSON: {e}"),
+        }
+    }
+
+    // second pass: write the spooled records, now that all the headers are known
+    let mut wtr = Config::new(&args.flag_output)
+        .delimiter(args.flag_delimiter)
+        .writer()?;
+    spool.write_csv(&flattener.headers, &mut wtr)?;
+    Ok(wtr.flush()?)
+}
+
+/// Parses a JSON Pointer into its reference tokens, e.g. "/a~1b/0" into ["a/b", "0"].
+fn parse_pointer(pointer: &str) -> CliResult<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(tokens) = pointer.strip_prefix('/') else {
+        return fail_incorrectusage_clierror!(
+            "Invalid --pointer: {pointer}. A JSON Pointer starts with \"/\"."
+        );
+    };
+    Ok(tokens
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+#[cfg(all(feature = "fetch", feature = "feature_capable"))]
+fn select_jql(rdr: impl io::Read, selector: &str) -> CliResult<Vec<Value>> {
+    let value: Value = serde_json::from_reader(rdr)?;
+    match jql_runner::runner::raw(selector, &value)? {
+        Value::Array(records) => Ok(records),
+        record @ Value::Object(_) => Ok(vec![record]),
+        _ => fail_clierror!("The jql selector must select an array or an object."),
+    }
+}
+
+#[cfg(not(all(feature = "fetch", feature = "feature_capable")))]
+fn select_jql(_rdr: impl io::Read, _selector: &str) -> CliResult<Vec<Value>> {
+    fail_incorrectusage_clierror!(
+        "--jql is only available when qsv is built with the fetch feature. Use --pointer instead."
+    )
+}
+
+/// Streams the records at `path` in a JSON document to `on_record`, skipping everything
+/// else without keeping it in memory. Deserializes to whether `path` was found.
+struct RecordStream<'a, F> {
+    path:      &'a [String],
+    on_record: &'a mut F,
+}
+
+impl<'de, F> DeserializeSeed<'de> for RecordStream<'_, F>
+where
+    F: FnMut(Value) -> CliResult<()>,
+{
+    type Value = bool;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, F> de::Visitor<'de> for RecordStream<'_, F>
+where
+    F: FnMut(Value) -> CliResult<()>,
+{
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of records or an object")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<bool, A::Error> {
+        let Some((token, rest)) = self.path.split_first() else {
+            // every element of the array is a record
+            while let Some(record) = seq.next_element::<Value>()? {
+                (self.on_record)(record).map_err(de::Error::custom)?;
+            }
+            return Ok(true);
+        };
+
+        let position = token.parse::<usize>().ok();
+        let mut found = false;
+        let mut i = 0_usize;
+        loop {
+            if Some(i) == position {
+                let stream = RecordStream {
+                    path:      rest,
+                    on_record: &mut *self.on_record,
+                };
+                match seq.next_element_seed(stream)? {
+                    Some(in_element) => found = in_element,
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+            i += 1;
+        }
+        Ok(found)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<bool, A::Error> {
+        let Some((token, rest)) = self.path.split_first() else {
+            // a single object is a single record
+            let record = Value::deserialize(MapAccessDeserializer::new(map))?;
+            (self.on_record)(record).map_err(de::Error::custom)?;
+            return Ok(true);
+        };
+
+        let mut found = false;
+        while let Some(key) = map.next_key::<String>()? {
+            if !found && key == *token {
+                found = map.next_value_seed(RecordStream {
+                    path:      rest,
+                    on_record: &mut *self.on_record,
+                })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(found)
+    }
+}
+
+struct Flattener {
+    separator: String,
+    arrays:    ArrayMode,
+    // the union of the columns of the records seen so far, in order of first appearance
+    headers:   IndexSet<String>,
+}
+
+impl Flattener {
+    /// Flattens a record into one or more rows (more than one only when exploding arrays)
+    /// of (column index, value) pairs.
+    fn flatten(&mut self, record: &Value) -> Vec<Vec<(usize, String)>> {
+        let mut rows = vec![Vec::new()];
+        if record.is_object() {
+            self.flatten_value("", record, &mut rows);
+        } else {
+            self.flatten_value("value", record, &mut rows);
+        }
+        rows
+    }
+
+    fn flatten_value(&mut self, column: &str, value: &Value, rows: &mut Vec<Vec<(usize, String)>>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    let column = if column.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{column}{}{key}", self.separator)
+                    };
+                    self.flatten_value(&column, value, rows);
+                }
+            },
+            Value::Array(values) => match self.arrays {
+                ArrayMode::Stringify => self.push(column, value.to_string(), rows),
+                ArrayMode::Index => {
+                    for (i, value) in values.iter().enumerate() {
+                        let column = format!("{column}{}{i}", self.separator);
+                        self.flatten_value(&column, value, rows);
+                    }
+                },
+                ArrayMode::Explode if values.is_empty() => self.push(column, String::new(), rows),
+                ArrayMode::Explode => {
+                    // each element gets its own copy of the rows flattened so far
+                    let flattened = std::mem::take(rows);
+                    for value in values {
+                        let mut element_rows = flattened.clone();
+                        self.flatten_value(column, value, &mut element_rows);
+                        rows.append(&mut element_rows);
+                    }
+                },
+            },
+            Value::String(s) => self.push(column, s.clone(), rows),
+            Value::Number(n) => self.push(column, n.to_string(), rows),
+            Value::Bool(b) => self.push(column, b.to_string(), rows),
+            Value::Null => self.push(column, String::new(), rows),
+        }
+    }
+
+    fn push(&mut self, column: &str, value: String, rows: &mut [Vec<(usize, String)>]) {
+        let i = match self.headers.get_index_of(column) {
+            Some(i) => i,
+            None => self.headers.insert_full(column.to_owned()).0,
+        };
+        for row in rows {
+            row.push((i, value.clone()));
+        }
+    }
+}
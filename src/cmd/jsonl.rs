@@ -8,6 +8,8 @@ fields from the input.
 Also, it will fail if the JSON documents are not consistent with one another,
 as the first JSON line will be used to infer the headers of the CSV output.
 
+To convert a JSON document with an array of records instead, use the `json` command.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_jsonl.rs.
 
 Usage:
@@ -0,0 +1,683 @@
+static USAGE: &str = r#"
+Interactively browse a CSV in the terminal, one screenful at a time.
+
+Unlike `table`, lens never loads the whole file into memory. It uses an index
+to read only the records on screen, so even huge files open instantly. If the
+CSV doesn't have an index yet, one is created first (see `qsv index`).
+
+The header row always stays on top, and columns can be frozen on the left so
+they stay in view when scrolling horizontally. Values longer than --max-width
+characters are truncated.
+
+Keys:
+    Down/Up, j/k             Scroll down/up one row
+    PageDown/PageUp, Space/b Scroll down/up one page
+    Home/End, g/G            Go to the first/last row
+    Right/Left, l/h          Scroll right/left one column
+    f/F                      Freeze one more/one less column
+    :                        Jump to a row number
+    /                        Search with a regex, going to the first row with a
+                             match as you type. Enter confirms the search and
+                             Esc cancels it. Matching cells are highlighted.
+    n/N                      Go to the next/previous row with a match
+    q, Esc, Ctrl-C           Quit
+
+Usage:
+    qsv lens [options] [<input>]
+    qsv lens --help
+
+lens options:
+    -f, --freeze <n>       The number of columns to freeze on the left.
+                           [default: 0]
+    -w, --max-width <n>    The maximum display width of a column.
+                           [default: 30]
+    -i, --ignore-case      Search case insensitively.
+
+Common options:
+    -h, --help             Display this message
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers, and columns are labeled by position.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character. (default: ,)
+"#;
+
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use regex::bytes::Regex;
+use serde::Deserialize;
+
+use crate::{
+    cmd::search,
+    config::{Config, Delimiter},
+    index::Indexed,
+    util, CliResult,
+};
+
+// the number of rows searched per keystroke while typing a search,
+// so typing stays responsive on huge files
+const INCREMENTAL_SEARCH_ROWS: u64 = 10_000;
+// the number of rows read at a time when searching backwards
+const BACKWARD_SEARCH_CHUNK: u64 = 1_000;
+// search's default regex size limits, in MB
+const REGEX_SIZE_LIMIT: usize = 50;
+const REGEX_DFA_SIZE_LIMIT: usize = 10;
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:        Option<String>,
+    flag_freeze:      usize,
+    flag_max_width:   usize,
+    flag_ignore_case: bool,
+    flag_no_headers:  bool,
+    flag_delimiter:   Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let Some(input) = &args.arg_input else {
+        return fail_incorrectusage_clierror!("lens needs a file to browse, it cannot read stdin.");
+    };
+    if !io::stdout().is_terminal() {
+        return fail_incorrectusage_clierror!("lens needs an interactive terminal.");
+    }
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    if rconfig.is_compressed() || rconfig.is_columnar() || rconfig.is_remote() {
+        return fail_incorrectusage_clierror!(
+            "lens cannot browse compressed, Parquet/Arrow or remote files."
+        );
+    }
+
+    let mut idx = if let Some(idx) = rconfig.indexed()? {
+        idx
+    } else {
+        // lens needs an index to page through the file, so we create it like
+        // the autoindexing of the other commands, using the same delimiter
+        winfo!("Indexing {input}...");
+        rconfig.autoindex_file();
+        match rconfig.indexed()? {
+            Some(idx) => idx,
+            None => return fail_clierror!("Cannot index {input}."),
+        }
+    };
+
+    let headers: Vec<String> = if args.flag_no_headers {
+        (1..=idx.byte_headers()?.len())
+            .map(|i| i.to_string())
+            .collect()
+    } else {
+        idx.byte_headers()?
+            .iter()
+            .map(|h| String::from_utf8_lossy(h).into_owned())
+            .collect()
+    };
+    let mut lens = Lens::new(
+        idx,
+        headers,
+        args.flag_max_width,
+        args.flag_ignore_case,
+        args.flag_freeze,
+    );
+
+    let _terminal = TerminalGuard::new()?;
+    lens.run()
+}
+
+/// Puts the terminal in raw mode on an alternate screen, restoring it when dropped,
+/// even if lens bails out with an error.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // there's nothing more we can do if restoring the terminal fails
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[derive(PartialEq)]
+enum Prompt {
+    None,
+    Search,
+    Jump,
+}
+
+struct Lens {
+    idx:           Indexed<fs::File, fs::File>,
+    headers:       Vec<String>,
+    num_rows:      u64,
+    max_width:     usize,
+    ignore_case:   bool,
+    // the number of frozen columns
+    freeze:        usize,
+    // the first row on screen
+    top:           u64,
+    // the first unfrozen column on screen
+    left:          usize,
+    // the records on screen
+    page:          Vec<csv::ByteRecord>,
+    regex:         Option<Regex>,
+    prompt:        Prompt,
+    // the text typed at the prompt
+    input:         String,
+    // the first row on screen when the search prompt was opened
+    search_origin: u64,
+    message:       String,
+}
+
+impl Lens {
+    fn new(
+        idx: Indexed<fs::File, fs::File>,
+        headers: Vec<String>,
+        max_width: usize,
+        ignore_case: bool,
+        freeze: usize,
+    ) -> Lens {
+        let freeze = freeze.min(headers.len().saturating_sub(1));
+        Lens {
+            num_rows: idx.count(),
+            idx,
+            headers,
+            max_width: max_width.max(1),
+            ignore_case,
+            freeze,
+            top: 0,
+            left: freeze,
+            page: Vec::new(),
+            regex: None,
+            prompt: Prompt::None,
+            input: String::new(),
+            search_origin: 0,
+            message: String::new(),
+        }
+    }
+
+    fn run(&mut self) -> CliResult<()> {
+        loop {
+            let (width, height) = terminal::size()?;
+            self.draw(width as usize, height as usize)?;
+
+            // other events, e.g. resizing the terminal, just redraw the screen
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let page_rows = u64::from(height).saturating_sub(2).max(1);
+            if !self.handle_key(key, page_rows)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Handles a key press, returning false when it's time to quit.
+    fn handle_key(&mut self, key: KeyEvent, page_rows: u64) -> CliResult<bool> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Ok(false);
+        }
+        match self.prompt {
+            Prompt::Search => self.handle_search_key(key)?,
+            Prompt::Jump => self.handle_jump_key(key),
+            Prompt::None => {
+                self.message.clear();
+                let last = self.num_rows.saturating_sub(1);
+                let last_column = self.headers.len().saturating_sub(1);
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                    KeyCode::Down | KeyCode::Char('j') => self.top = (self.top + 1).min(last),
+                    KeyCode::Up | KeyCode::Char('k') => self.top = self.top.saturating_sub(1),
+                    KeyCode::PageDown | KeyCode::Char(' ') => {
+                        self.top = (self.top + page_rows).min(last);
+                    },
+                    KeyCode::PageUp | KeyCode::Char('b') => {
+                        self.top = self.top.saturating_sub(page_rows);
+                    },
+                    KeyCode::Home | KeyCode::Char('g') => self.top = 0,
+                    KeyCode::End | KeyCode::Char('G') => {
+                        self.top = self.num_rows.saturating_sub(page_rows);
+                    },
+                    KeyCode::Right | KeyCode::Char('l') => {
+                        self.left = (self.left + 1).min(last_column);
+                    },
+                    KeyCode::Left | KeyCode::Char('h') => {
+                        self.left = self.left.saturating_sub(1).max(self.freeze);
+                    },
+                    KeyCode::Char('f') => {
+                        self.freeze = (self.freeze + 1).min(last_column);
+                        self.left = self.left.max(self.freeze);
+                    },
+                    KeyCode::Char('F') => self.freeze = self.freeze.saturating_sub(1),
+                    KeyCode::Char(':') => {
+                        self.prompt = Prompt::Jump;
+                        self.input.clear();
+                    },
+                    KeyCode::Char('/') => {
+                        self.prompt = Prompt::Search;
+                        self.input.clear();
+                        self.search_origin = self.top;
+                    },
+                    KeyCode::Char('n') => self.find_next(true)?,
+                    KeyCode::Char('N') => self.find_next(false)?,
+                    _ => {},
+                }
+            },
+        }
+        Ok(true)
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) -> CliResult<()> {
+        match key.code {
+            KeyCode::Esc => {
+                self.prompt = Prompt::None;
+                self.regex = None;
+                self.top = self.search_origin;
+            },
+            KeyCode::Enter => {
+                self.prompt = Prompt::None;
+                if self.regex.is_some() {
+                    // typing only searches the rows right after the origin,
+                    // so now search the rest of the file too
+                    if let Some(row) = self.find(self.search_origin, true, None)? {
+                        self.top = row;
+                    } else {
+                        self.top = self.search_origin;
+                        self.message = format!("No match for /{}/", self.input);
+                    }
+                }
+            },
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.incremental_search()?;
+            },
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.incremental_search()?;
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn incremental_search(&mut self) -> CliResult<()> {
+        self.top = self.search_origin;
+        self.message.clear();
+        if self.input.is_empty() {
+            self.regex = None;
+            return Ok(());
+        }
+        match search::build_regex(
+            &self.input,
+            self.ignore_case,
+            false,
+            REGEX_SIZE_LIMIT,
+            REGEX_DFA_SIZE_LIMIT,
+        ) {
+            Ok(regex) => self.regex = Some(regex),
+            Err(_) => {
+                // most likely a regex that isn't finished yet
+                self.regex = None;
+                self.message = "(incomplete regex)".to_string();
+                return Ok(());
+            },
+        }
+        if let Some(row) = self.find(self.search_origin, true, Some(INCREMENTAL_SEARCH_ROWS))? {
+            self.top = row;
+        }
+        Ok(())
+    }
+
+    fn handle_jump_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.prompt = Prompt::None,
+            KeyCode::Enter => {
+                self.prompt = Prompt::None;
+                match self.input.parse::<u64>() {
+                    Ok(row) if row >= 1 => {
+                        self.top = (row - 1).min(self.num_rows.saturating_sub(1));
+                    },
+                    _ => self.message = format!("Invalid row number: {}", self.input),
+                }
+            },
+            KeyCode::Backspace => {
+                self.input.pop();
+            },
+            KeyCode::Char(c) if c.is_ascii_digit() => self.input.push(c),
+            _ => {},
+        }
+    }
+
+    fn find_next(&mut self, forward: bool) -> CliResult<()> {
+        if self.regex.is_none() {
+            self.message = "Press / to search".to_string();
+            return Ok(());
+        }
+        let start = if forward {
+            self.top + 1
+        } else if let Some(start) = self.top.checked_sub(1) {
+            start
+        } else {
+            self.message = "No earlier match".to_string();
+            return Ok(());
+        };
+        match self.find(start, forward, None)? {
+            Some(row) => self.top = row,
+            None if forward => self.message = "No later match".to_string(),
+            None => self.message = "No earlier match".to_string(),
+        }
+        Ok(())
+    }
+
+    /// Finds the first row with a match, starting at row `start` and going forward
+    /// or backward through at most `limit` rows.
+    fn find(&mut self, start: u64, forward: bool, limit: Option<u64>) -> CliResult<Option<u64>> {
+        let Some(regex) = &self.regex else {
+            return Ok(None);
+        };
+        if start >= self.num_rows {
+            return Ok(None);
+        }
+        let mut record = csv::ByteRecord::new();
+        if forward {
+            let end = limit.map_or(self.num_rows, |limit| {
+                start.saturating_add(limit).min(self.num_rows)
+            });
+            self.idx.seek(start)?;
+            for row in start..end {
+                if !self.idx.read_byte_record(&mut record)? {
+                    break;
+                }
+                if record.iter().any(|field| regex.is_match(field)) {
+                    return Ok(Some(row));
+                }
+            }
+            return Ok(None);
+        }
+
+        // the index only seeks forward, so search backward a chunk of rows at a time
+        let stop = limit.map_or(0, |limit| start.saturating_sub(limit));
+        let mut end = start + 1;
+        while end > stop {
+            let chunk_start = end.saturating_sub(BACKWARD_SEARCH_CHUNK).max(stop);
+            self.idx.seek(chunk_start)?;
+            let mut found = None;
+            for row in chunk_start..end {
+                if !self.idx.read_byte_record(&mut record)? {
+                    break;
+                }
+                if record.iter().any(|field| regex.is_match(field)) {
+                    found = Some(row);
+                }
+            }
+            if found.is_some() {
+                return Ok(found);
+            }
+            end = chunk_start;
+        }
+        Ok(None)
+    }
+
+    fn load_page(&mut self, rows: usize) -> CliResult<()> {
+        self.page.clear();
+        if self.top >= self.num_rows {
+            return Ok(());
+        }
+        self.idx.seek(self.top)?;
+        let mut record = csv::ByteRecord::new();
+        while self.page.len() < rows && self.idx.read_byte_record(&mut record)? {
+            self.page.push(record.clone());
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, width: usize, height: usize) -> CliResult<()> {
+        self.load_page(height.saturating_sub(2))?;
+
+        // size the columns to fit the header and the values on screen
+        let gutter = self.num_rows.to_string().len();
+        let widths: Vec<usize> = self
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                self.page
+                    .iter()
+                    .filter_map(|record| record.get(i))
+                    .map(|field| String::from_utf8_lossy(field).chars().count())
+                    .chain(std::iter::once(header.chars().count()))
+                    .max()
+                    .unwrap_or(1)
+                    .clamp(1, self.max_width)
+            })
+            .collect();
+
+        // show the frozen columns, then as many columns from `left` on as fit,
+        // after the row numbers and their 2-space gap
+        let mut columns = Vec::new();
+        let mut used = gutter + 2;
+        for i in (0..self.freeze).chain(self.left..self.headers.len()) {
+            if used + 2 >= width {
+                break;
+            }
+            let column_width = widths[i].min(width - used - 2);
+            columns.push((i, column_width));
+            used += 2 + column_width;
+        }
+
+        let mut out = io::BufWriter::new(io::stdout());
+        queue!(
+            out,
+            Clear(ClearType::All),
+            MoveTo(0, 0),
+            SetAttribute(Attribute::Bold),
+            Print(" ".repeat(gutter + 2))
+        )?;
+        for &(i, column_width) in &columns {
+            let separator = if i + 1 == self.freeze { " |" } else { "  " };
+            queue!(
+                out,
+                Print(cell_text(self.headers[i].as_bytes(), column_width)),
+                Print(separator)
+            )?;
+        }
+        queue!(out, SetAttribute(Attribute::Reset))?;
+
+        for (y, record) in self.page.iter().enumerate() {
+            let row_number = self.top + y as u64 + 1;
+            queue!(
+                out,
+                MoveTo(0, (y + 1) as u16),
+                SetAttribute(Attribute::Dim),
+                Print(format!("{row_number:>gutter$}")),
+                SetAttribute(Attribute::Reset),
+                Print("  ")
+            )?;
+            for &(i, column_width) in &columns {
+                let field = record.get(i).unwrap_or_default();
+                let text = cell_text(field, column_width);
+                let matched = self
+                    .regex
+                    .as_ref()
+                    .is_some_and(|regex| regex.is_match(field));
+                if matched {
+                    queue!(
+                        out,
+                        SetAttribute(Attribute::Reverse),
+                        Print(text),
+                        SetAttribute(Attribute::NoReverse)
+                    )?;
+                } else {
+                    queue!(out, Print(text))?;
+                }
+                let separator = if i + 1 == self.freeze { " |" } else { "  " };
+                queue!(out, Print(separator))?;
+            }
+        }
+
+        let status = match self.prompt {
+            Prompt::Search => format!("/{} {}", self.input, self.message),
+            Prompt::Jump => format!("Go to row: {}", self.input),
+            Prompt::None => {
+                let first_column = columns.iter().find(|(i, _)| *i >= self.freeze);
+                format!(
+                    "rows {}-{} of {} | columns {}-{} of {} | {}",
+                    (self.top + 1).min(self.num_rows),
+                    self.top + self.page.len() as u64,
+                    self.num_rows,
+                    first_column.map_or(0, |(i, _)| i + 1),
+                    columns.last().map_or(0, |(i, _)| i + 1),
+                    self.headers.len(),
+                    if self.message.is_empty() {
+                        "/ search  : go to row  q quit"
+                    } else {
+                        &self.message
+                    }
+                )
+            },
+        };
+        queue!(
+            out,
+            MoveTo(0, height.saturating_sub(1) as u16),
+            SetAttribute(Attribute::Reverse),
+            Print(cell_text(status.as_bytes(), width)),
+            SetAttribute(Attribute::Reset)
+        )?;
+        Ok(out.flush()?)
+    }
+}
+
+/// Formats a field to exactly `width` characters, truncating it with an ellipsis
+/// if needed and replacing control characters such as newlines with spaces.
+fn cell_text(field: &[u8], width: usize) -> String {
+    let field = String::from_utf8_lossy(field);
+    let mut text = String::with_capacity(width);
+    let mut len = 0;
+    for (i, c) in field.chars().enumerate() {
+        if i + 1 == width && field.chars().nth(width).is_some() {
+            text.push('…');
+            len += 1;
+            break;
+        }
+        if i == width {
+            break;
+        }
+        text.push(if c.is_control() { ' ' } else { c });
+        len += 1;
+    }
+    text.extend(std::iter::repeat(' ').take(width - len));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use csv_index::RandomAccessSimple;
+
+    use super::*;
+
+    /// a lens over an indexed CSV file with a "value" column holding `rows`
+    fn lens_with_rows(dir: &tempfile::TempDir, rows: &[String]) -> Lens {
+        let path = dir.path().join("data.csv");
+        let idx_path = dir.path().join("data.csv.idx");
+        let mut data = String::from("value\n");
+        for row in rows {
+            data.push_str(row);
+            data.push('\n');
+        }
+        fs::write(&path, data).unwrap();
+
+        let mut rdr = csv::Reader::from_path(&path).unwrap();
+        let mut idx_file = fs::File::create(&idx_path).unwrap();
+        RandomAccessSimple::create(&mut rdr, &mut idx_file).unwrap();
+        let idx = Indexed::open(
+            csv::Reader::from_path(&path).unwrap(),
+            fs::File::open(&idx_path).unwrap(),
+        )
+        .unwrap();
+
+        let mut lens = Lens::new(idx, vec!["value".to_string()], 20, false, 0);
+        lens.regex = Some(Regex::new("match").unwrap());
+        lens
+    }
+
+    /// more rows than a backward search chunk, with matches in different chunks
+    fn search_rows() -> Vec<String> {
+        (0..2500)
+            .map(|row| match row {
+                5 | 1200 | 1500 | 2400 => format!("match {row}"),
+                _ => format!("row {row}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cell_text_pads_short_fields() {
+        assert_eq!(cell_text(b"abc", 5), "abc  ");
+        assert_eq!(cell_text(b"", 3), "   ");
+    }
+
+    #[test]
+    fn cell_text_truncates_long_fields() {
+        assert_eq!(cell_text(b"abcdef", 4), "abc…");
+        assert_eq!(cell_text("日本語です".as_bytes(), 3), "日本…");
+        // a field that fits exactly isn't truncated
+        assert_eq!(cell_text(b"abcd", 4), "abcd");
+    }
+
+    #[test]
+    fn cell_text_replaces_control_chars() {
+        assert_eq!(cell_text(b"a\nb\tc", 6), "a b c ");
+    }
+
+    #[test]
+    fn find_forward() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut lens = lens_with_rows(&dir, &search_rows());
+
+        assert_eq!(lens.find(0, true, None).unwrap(), Some(5));
+        assert_eq!(lens.find(5, true, None).unwrap(), Some(5));
+        assert_eq!(lens.find(6, true, None).unwrap(), Some(1200));
+        assert_eq!(lens.find(2401, true, None).unwrap(), None);
+        assert_eq!(lens.find(2500, true, None).unwrap(), None);
+        // only searches `limit` rows
+        assert_eq!(lens.find(6, true, Some(100)).unwrap(), None);
+        assert_eq!(lens.find(6, true, Some(1195)).unwrap(), Some(1200));
+    }
+
+    #[test]
+    fn find_backward() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut lens = lens_with_rows(&dir, &search_rows());
+
+        // the closest earlier match, not the first match of the chunk
+        assert_eq!(lens.find(2499, false, None).unwrap(), Some(2400));
+        assert_eq!(lens.find(2399, false, None).unwrap(), Some(1500));
+        assert_eq!(lens.find(1499, false, None).unwrap(), Some(1200));
+        assert_eq!(lens.find(999, false, None).unwrap(), Some(5));
+        // the first chunk (101..=1100) has no match, so the search continues in the next one
+        assert_eq!(lens.find(1100, false, None).unwrap(), Some(5));
+        assert_eq!(lens.find(4, false, None).unwrap(), None);
+        // only searches `limit` rows
+        assert_eq!(lens.find(999, false, Some(500)).unwrap(), None);
+        assert_eq!(lens.find(999, false, Some(994)).unwrap(), Some(5));
+    }
+}
@@ -6,6 +6,7 @@ pub mod applydp;
 pub mod behead;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod cat;
+pub mod compress;
 pub mod count;
 pub mod datefmt;
 pub mod dedup;
@@ -35,8 +36,11 @@ pub mod fmt;
 #[cfg(all(feature = "foreach", target_family = "unix", not(feature = "lite")))]
 pub mod foreach;
 pub mod frequency;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod fwf;
 #[cfg(all(feature = "geocode", feature = "feature_capable"))]
 pub mod geocode;
+pub mod groupby;
 pub mod headers;
 pub mod index;
 pub mod input;
@@ -45,11 +49,17 @@ pub mod join;
 #[cfg(feature = "polars")]
 pub mod joinp;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod json;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod jsonl;
+#[cfg(all(feature = "lens", feature = "feature_capable"))]
+pub mod lens;
 #[cfg(feature = "luau")]
 pub mod luau;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod partition;
+#[cfg(feature = "polars")]
+pub mod pivot;
 pub mod pseudo;
 #[cfg(all(feature = "python", feature = "feature_capable"))]
 pub mod python;
@@ -75,6 +85,8 @@ pub mod sqlp;
 pub mod stats;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod table;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod template;
 #[cfg(all(feature = "to", feature = "feature_capable"))]
 pub mod to;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
@@ -82,3 +94,5 @@ pub mod tojsonl;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 pub mod transpose;
 pub mod validate;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub mod xml;
@@ -0,0 +1,348 @@
+static USAGE: &str = r#"
+Pivots CSV data from long to wide format using the Pola.rs engine. The unique values
+of the <on> column/s become new columns, with one row per unique combination of the
+--index column/s, aggregating the --values column/s with the --agg function.
+
+With the --unpivot option, it does the inverse operation (also known as "melt"),
+converting wide data to long format. The <on> columns are unpivoted into two columns:
+the variable column with the original column names and the value column with their values.
+The --index columns are kept as identifier columns.
+
+Returns the shape of the result (number of rows, number of columns) to stderr.
+
+Examples:
+
+  # count the number of cases per borough & year, with one column per year
+   qsv pivot year cases.csv --index borough --values case_id --agg len
+
+  # the total sales per region, with one column per quarter, sorted by quarter
+   qsv pivot quarter sales.csv --index region --values amount --agg sum --sort-columns
+
+  # multiple value columns are pivoted as <value>_<on value> columns
+   qsv pivot quarter sales.csv --index region --values amount,units --agg sum
+
+  # write the pivoted data to a Parquet file
+   qsv pivot quarter sales.csv -i region -v amount -a mean --format parquet -o pivot.parquet
+
+  # unpivot the quarterly columns back into rows
+   qsv pivot --unpivot Q1,Q2,Q3,Q4 pivot.csv --index region --variable-name quarter
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_pivot.rs.
+
+Usage:
+    qsv pivot [options] <on> <input>
+    qsv pivot --help
+
+pivot arguments:
+    <on>                      The column/s whose values will be the new column names.
+                              When unpivoting, the columns to unpivot into rows.
+                              Columns are referenced by name. Specify multiple columns
+                              by separating them with a comma.
+    <input>                   The input CSV file. It needs to have headers.
+                              Stdin is not supported.
+
+pivot options:
+    -i, --index <cols>        The column/s to use as the row index. Each unique combination
+                              of the index columns will be a row in the output.
+                              If not set, all the columns not in <on> or --values are used.
+                              When unpivoting, the identifier columns. If not set, there
+                              are no identifier columns.
+    -v, --values <cols>       The column/s with the values to aggregate.
+                              If not set, all the columns not in <on> or --index are used.
+                              Note that at least one of --index or --values must be set.
+    -a, --agg <func>          The aggregation function to use when there are several
+                              values for an index/column combination. Valid values are:
+                                first, last, sum, mean, median, min, max,
+                                count - the number of non-null values
+                                len - the number of values
+                              [default: first]
+    --sort-columns            Sort the pivoted column names. Otherwise, they're in
+                              the order they first appear in the input.
+    --maintain-order          Keep the index rows in the order they first appear in the
+                              input. Otherwise, the row order is not deterministic.
+    --col-separator <arg>     The separator used to generate the column names when there
+                              are several value columns.
+                              [default: _]
+    --unpivot                 Unpivot (melt) the <on> columns from wide to long format.
+    --variable-name <arg>     The name of the unpivoted variable column.
+                              [default: variable]
+    --value-name <arg>        The name of the unpivoted value column.
+                              [default: value]
+
+                              POLARS CSV INPUT PARSING OPTIONS:
+    --try-parsedates          Automatically try to parse dates/datetimes and time.
+                              If parsing fails, columns remain as strings.
+    --infer-len <arg>         The number of rows to scan when inferring the schema of the CSV.
+                              Set to 0 to do a full table scan (warning: very slow).
+                              [default: 1000]
+    --ignore-errors           Ignore errors when parsing the CSV. If set, rows with errors
+                              will be skipped. If not set, pivot will fail.
+
+                              OUTPUT FORMAT OPTIONS:
+    --format <arg>            The output format to use. Valid values are:
+                                csv      Comma-separated values
+                                json     JSON
+                                jsonl    JSONL (JSON Lines)
+                                parquet  Apache Parquet
+                                arrow    Apache Arrow IPC
+                                avro     Apache Avro
+                              [default: csv]
+    --datetime-format <fmt>   The datetime format to use writing datetimes. CSV only.
+                              See https://docs.rs/chrono/latest/chrono/format/strftime/index.html
+                              for the list of valid format specifiers.
+    --date-format <fmt>       The date format to use writing dates. CSV only.
+    --time-format <fmt>       The time format to use writing times. CSV only.
+    --float-precision <arg>   The number of digits of precision to use when writing floats.
+                              CSV only.
+    --wnull-value <arg>       The string to use when WRITING null values. CSV only.
+                              [default: <empty string>]
+    --compression <arg>       The compression codec to use when writing arrow, avro or
+                              parquet files. See `qsv sqlp --help` for valid values.
+                              [default: zstd]
+    --compress-level <arg>    The compression level to use when using zstd or gzip
+                              compression with parquet files.
+    --statistics              Compute column statistics when writing parquet files.
+
+Common options:
+    -h, --help                Display this message
+    -o, --output <file>       Write output to <file> instead of stdout.
+    -d, --delimiter <arg>     The field delimiter for reading and writing CSV data.
+                              Must be a single character. [default: ,]
+    -Q, --quiet               Do not return result shape to stderr.
+"#;
+
+use std::{env, path::Path};
+
+use polars::{
+    lazy::{
+        dsl::{col, len, Expr},
+        frame::pivot::{pivot, pivot_stable},
+    },
+    prelude::{LazyCsvReader, LazyFileListReader, LazyFrame, MeltArgs},
+};
+use serde::Deserialize;
+use smartstring::SmartString;
+use tempfile::tempdir;
+
+use crate::{
+    cmd::{
+        joinp::tsvtab_delim,
+        sqlp::{compress_output_if_needed, OutputMode, OutputOptions},
+    },
+    config::{Codec, Delimiter},
+    util, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_on:               String,
+    arg_input:            String,
+    flag_index:           Option<String>,
+    flag_values:          Option<String>,
+    flag_agg:             String,
+    flag_sort_columns:    bool,
+    flag_maintain_order:  bool,
+    flag_col_separator:   String,
+    flag_unpivot:         bool,
+    flag_variable_name:   String,
+    flag_value_name:      String,
+    flag_try_parsedates:  bool,
+    flag_infer_len:       usize,
+    flag_ignore_errors:   bool,
+    flag_format:          String,
+    flag_datetime_format: Option<String>,
+    flag_date_format:     Option<String>,
+    flag_time_format:     Option<String>,
+    flag_float_precision: Option<usize>,
+    flag_wnull_value:     String,
+    flag_compression:     String,
+    flag_compress_level:  Option<i32>,
+    flag_statistics:      bool,
+    flag_output:          Option<String>,
+    flag_delimiter:       Option<Delimiter>,
+    flag_quiet:           bool,
+}
+
+/// splits a comma-delimited list of column names
+fn split_columns(cols: &str) -> Vec<String> {
+    cols.split(',').map(str::to_string).collect()
+}
+
+/// the aggregation expression applied to the values of each index/column combination.
+/// col("") refers to the values being aggregated, just like pl.element() in Python Polars.
+fn agg_expr(agg: &str) -> CliResult<Expr> {
+    let values = col("");
+    Ok(match agg.to_ascii_lowercase().as_str() {
+        "first" => values.first(),
+        "last" => values.last(),
+        "sum" => values.sum(),
+        "mean" => values.mean(),
+        "median" => values.median(),
+        "min" => values.min(),
+        "max" => values.max(),
+        "count" => values.count(),
+        "len" => len(),
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid aggregation function: {agg}. Valid values are: first, last, sum, mean, \
+                 median, min, max, count, len."
+            )
+        },
+    })
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let mut args: Args = util::get_args(USAGE, argv)?;
+
+    let output_mode: OutputMode = match args.flag_format.parse() {
+        Ok(output_mode) => output_mode,
+        Err(e) => return fail_incorrectusage_clierror!("{e}"),
+    };
+
+    let delim = if let Some(delimiter) = args.flag_delimiter {
+        delimiter.as_byte()
+    } else {
+        b','
+    };
+
+    let comment_char = if let Ok(comment_char) = env::var("QSV_COMMENT_CHAR") {
+        Some(comment_char)
+    } else {
+        None
+    };
+
+    let input_path = Path::new(&args.arg_input);
+    if !input_path.exists() {
+        return fail_clierror!("Input file {} does not exist.", args.arg_input);
+    }
+
+    // polars can't read compressed CSVs, so we decompress it first
+    let tmpdir = tempdir()?;
+    if Codec::from_path(input_path).is_compressed() {
+        args.arg_input = util::decompress_file(input_path, &tmpdir)?;
+    }
+
+    let lf = LazyCsvReader::new(&args.arg_input)
+        .has_header(true)
+        .with_missing_is_null(true)
+        .with_comment_prefix(comment_char.as_deref())
+        .with_separator(tsvtab_delim(&args.arg_input, delim))
+        .with_infer_schema_length(if args.flag_infer_len == 0 {
+            None
+        } else {
+            Some(args.flag_infer_len)
+        })
+        .with_try_parse_dates(args.flag_try_parsedates)
+        .with_ignore_errors(args.flag_ignore_errors)
+        .finish()?;
+
+    let on_cols = split_columns(&args.arg_on);
+    let index_cols = args.flag_index.as_deref().map(split_columns);
+
+    let mut result_df = if args.flag_unpivot {
+        unpivot(lf, on_cols, index_cols.unwrap_or_default(), &args)?
+    } else {
+        let value_cols = args.flag_values.as_deref().map(split_columns);
+        let agg = agg_expr(&args.flag_agg)?;
+
+        let df = lf.collect()?;
+
+        // the index and values columns default to the columns not otherwise used
+        let remaining_cols = |used_cols: &[String]| -> Vec<String> {
+            df.get_column_names()
+                .into_iter()
+                .filter(|name| !on_cols.iter().any(|c| c == name))
+                .filter(|name| !used_cols.iter().any(|c| c == name))
+                .map(str::to_string)
+                .collect()
+        };
+        let (index_cols, value_cols) = match (index_cols, value_cols) {
+            (Some(index_cols), Some(value_cols)) => (index_cols, value_cols),
+            (Some(index_cols), None) => {
+                let value_cols = remaining_cols(&index_cols);
+                (index_cols, value_cols)
+            },
+            (None, Some(value_cols)) => (remaining_cols(&value_cols), value_cols),
+            (None, None) => {
+                return fail_incorrectusage_clierror!(
+                    "At least one of --index or --values must be set."
+                )
+            },
+        };
+        if index_cols.is_empty() || value_cols.is_empty() {
+            return fail_incorrectusage_clierror!(
+                "There are no columns left to use as the pivot index or values."
+            );
+        }
+
+        let separator = Some(args.flag_col_separator.as_str());
+        if args.flag_maintain_order {
+            pivot_stable(
+                &df,
+                value_cols,
+                index_cols,
+                on_cols,
+                args.flag_sort_columns,
+                Some(agg),
+                separator,
+            )?
+        } else {
+            pivot(
+                &df,
+                value_cols,
+                index_cols,
+                on_cols,
+                args.flag_sort_columns,
+                Some(agg),
+                separator,
+            )?
+        }
+    };
+
+    if args.flag_wnull_value == "<empty string>" {
+        args.flag_wnull_value.clear();
+    }
+
+    let result_shape = result_df.shape();
+    output_mode.write_dataframe(
+        &mut result_df,
+        delim,
+        OutputOptions {
+            output:          args.flag_output.clone(),
+            datetime_format: args.flag_datetime_format,
+            date_format:     args.flag_date_format,
+            time_format:     args.flag_time_format,
+            float_precision: args.flag_float_precision,
+            wnull_value:     args.flag_wnull_value,
+            compression:     args.flag_compression,
+            compress_level:  args.flag_compress_level,
+            statistics:      args.flag_statistics,
+        },
+    )?;
+
+    compress_output_if_needed(args.flag_output)?;
+
+    if !args.flag_quiet {
+        eprintln!("{result_shape:?}");
+    }
+
+    Ok(())
+}
+
+/// unpivot (melt) the on columns into variable/value rows, keeping the index columns
+fn unpivot(
+    lf: LazyFrame,
+    on_cols: Vec<String>,
+    index_cols: Vec<String>,
+    args: &Args,
+) -> CliResult<polars::prelude::DataFrame> {
+    let melt_args = MeltArgs {
+        id_vars:       index_cols.into_iter().map(SmartString::from).collect(),
+        value_vars:    on_cols.into_iter().map(SmartString::from).collect(),
+        variable_name: Some(SmartString::from(args.flag_variable_name.as_str())),
+        value_name:    Some(SmartString::from(args.flag_value_name.as_str())),
+        streamable:    true,
+    };
+
+    Ok(lf.melt(melt_args).collect()?)
+}
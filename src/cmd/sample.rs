@@ -14,6 +14,35 @@ This command is intended to provide a means to sample from a CSV data set that
 is too big to fit into memory (for example, for use with commands like
 'qsv stats' with the '--everything' option). 
 
+Besides uniform random sampling, the following sampling methods are supported
+(only one can be used at a time):
+
+  --bernoulli      Each record is sampled independently with the probability given by
+                   <sample-size> (e.g. 0.1 samples ~10 percent of the records). The
+                   sample is streamed in constant memory, so it's suitable for stdin.
+  --systematic     Every k-th record is sampled, where k is <sample-size> (e.g. 10
+                   samples every 10th record). If <sample-size> is between 0 and 1,
+                   k is its inverse (e.g. 0.25 samples every 4th record). Uses
+                   the index to seek to the sampled records if one is present.
+  --stratified     Up to <sample-size> records are randomly sampled from each stratum,
+                   i.e. each distinct value of the given column. The strata are
+                   output in the order they first appear in the CSV. If an index is
+                   present, only the row numbers of the sampled records are kept
+                   in memory, and the index is used to seek to them.
+  --weighted       <sample-size> records are randomly sampled, with a probability
+                   proportional to the weight in the given numeric column. Records
+                   with a non-numeric, zero or negative weight are never sampled.
+                   Like --stratified, uses the index to seek to the sampled records
+                   if one is present.
+  --cluster        <sample-size> clusters (i.e. distinct values of the given column)
+                   are randomly sampled, and all the records of each sampled cluster
+                   are output. If <sample-size> is between 0 and 1, it's a percentage
+                   of the clusters. Requires reading the input twice, so it does not
+                   work with stdin.
+
+The sampled records are output in their input order with the --bernoulli,
+--systematic, --weighted & --cluster methods.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_sample.rs.
 
 Usage:
@@ -45,6 +74,15 @@ sample options:
                               2.1 GB/s throughput though slow initialization.
                            [default: standard]
 
+    --bernoulli            Use Bernoulli sampling. See above.
+    --systematic <arg>     Use systematic sampling, starting with the first record
+                           ("first") or a random record within the first interval
+                           ("random"). See above.
+    --stratified <col>     Use stratified sampling on the given column. See above.
+    --weighted <col>       Use weighted sampling with the weights in the given
+                           column. See above.
+    --cluster <col>        Use cluster sampling on the given column. See above.
+
     --user-agent <agent>   Specify custom user agent to use when the input is a URL.
                            It supports the following variables -
                            $QSV_VERSION, $QSV_TARGET, $QSV_BIN_NAME, $QSV_KIND and $QSV_COMMAND.
@@ -64,9 +102,10 @@ Common options:
                            Must be a single character. (default: ,)
 "#;
 
-use std::{io, str::FromStr};
+use std::{cmp, collections::BinaryHeap, fs, io, str::FromStr};
 
-use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use indexmap::{IndexMap, IndexSet};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, RngCore, SeedableRng};
 use rand_hc::Hc128Rng;
 use rand_xoshiro::Xoshiro256Plus;
 use serde::Deserialize;
@@ -76,6 +115,8 @@ use url::Url;
 
 use crate::{
     config::{Config, Delimiter},
+    index::Indexed,
+    select::SelectColumns,
     util, CliResult,
 };
 
@@ -88,6 +129,11 @@ struct Args {
     flag_delimiter:  Option<Delimiter>,
     flag_seed:       Option<u64>,
     flag_rng:        String,
+    flag_bernoulli:  bool,
+    flag_systematic: Option<String>,
+    flag_stratified: Option<String>,
+    flag_weighted:   Option<String>,
+    flag_cluster:    Option<String>,
     flag_user_agent: Option<String>,
     flag_timeout:    Option<u16>,
 }
@@ -151,6 +197,173 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .delimiter(args.flag_delimiter)
         .writer()?;
 
+    let sampling_methods = [
+        args.flag_bernoulli,
+        args.flag_systematic.is_some(),
+        args.flag_stratified.is_some(),
+        args.flag_weighted.is_some(),
+        args.flag_cluster.is_some(),
+    ];
+    if sampling_methods.iter().filter(|&&method| method).count() > 1 {
+        return fail_incorrectusage_clierror!(
+            "Only one of --bernoulli, --systematic, --stratified, --weighted or --cluster can be \
+             used."
+        );
+    }
+
+    if args.flag_bernoulli {
+        if sample_size <= 0.0 || sample_size >= 1.0 {
+            return fail_incorrectusage_clierror!(
+                "The sample size must be a probability between 0 and 1 exclusive with --bernoulli."
+            );
+        }
+        let mut rng = new_rng(&rng_kind, args.flag_seed);
+        let mut rdr = rconfig.reader()?;
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+        for row in rdr.byte_records() {
+            let row = row?;
+            if rng.gen_bool(sample_size) {
+                wtr.write_byte_record(&row)?;
+            }
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    if let Some(ref start) = args.flag_systematic {
+        let interval = if sample_size == 0.0 {
+            0
+        } else if sample_size < 1.0 {
+            (1.0 / sample_size).round() as usize
+        } else {
+            sample_size as usize
+        };
+        if interval == 0 {
+            return fail_incorrectusage_clierror!("The sample size cannot be zero.");
+        }
+        let first = match start.to_ascii_lowercase().as_str() {
+            "first" => 0,
+            "random" => new_rng(&rng_kind, args.flag_seed).gen_range(0..interval),
+            _ => {
+                return fail_incorrectusage_clierror!(
+                    "Invalid --systematic start `{start}`. Valid values are: first, random."
+                )
+            },
+        };
+        if let Some(mut idx) = rconfig.indexed()? {
+            rconfig.write_headers(&mut *idx, &mut wtr)?;
+            let rownums = (first as u64..idx.count()).step_by(interval);
+            write_indexed_records(&mut idx, rownums, &mut wtr)?;
+        } else {
+            let mut rdr = rconfig.reader()?;
+            rconfig.write_headers(&mut rdr, &mut wtr)?;
+            for row in rdr.byte_records().skip(first).step_by(interval) {
+                wtr.write_byte_record(&row?)?;
+            }
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    if let Some(ref column) = args.flag_stratified {
+        if sample_size < 1.0 {
+            return fail_incorrectusage_clierror!(
+                "The sample size must be the number of records to sample from each stratum with \
+                 --stratified."
+            );
+        }
+        let mut rdr = rconfig.reader()?;
+        let col = column_index(&rconfig, &rdr.byte_headers()?.clone(), column)?;
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+        let stratum = |row: &csv::ByteRecord| row.get(col).unwrap_or_default().to_vec();
+        let mut rng = new_rng(&rng_kind, args.flag_seed);
+        if let Some(mut idx) = rconfig.indexed()? {
+            // only keep the row numbers of the sampled records, and seek to them
+            let rows = rdr
+                .byte_records()
+                .enumerate()
+                .map(|(rownum, row)| row.map(|row| (stratum(&row), rownum as u64)));
+            let sampled = sample_stratified(rows, sample_size as usize, &mut rng)?;
+            write_indexed_records(&mut idx, sampled, &mut wtr)?;
+        } else {
+            let rows = rdr
+                .byte_records()
+                .map(|row| row.map(|row| (stratum(&row), row)));
+            for row in sample_stratified(rows, sample_size as usize, &mut rng)? {
+                wtr.write_byte_record(&row)?;
+            }
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    if let Some(ref column) = args.flag_weighted {
+        #[allow(clippy::cast_precision_loss)]
+        if sample_size < 1.0 {
+            let Ok(row_count) = util::count_rows(&rconfig) else {
+                return fail!("Cannot get rowcount. Percentage sampling requires a rowcount.");
+            };
+            sample_size *= row_count as f64;
+        }
+        let mut rdr = rconfig.reader()?;
+        let col = column_index(&rconfig, &rdr.byte_headers()?.clone(), column)?;
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+        let weight = |row: &csv::ByteRecord| {
+            simdutf8::basic::from_utf8(row.get(col).unwrap_or_default())
+                .ok()
+                .and_then(|w| w.trim().parse::<f64>().ok())
+                .unwrap_or_default()
+        };
+        let mut rng = new_rng(&rng_kind, args.flag_seed);
+        if let Some(mut idx) = rconfig.indexed()? {
+            // only keep the row numbers of the sampled records, and seek to them
+            let rows = rdr
+                .byte_records()
+                .enumerate()
+                .map(|(rownum, row)| row.map(|row| (weight(&row), rownum as u64)));
+            let sampled = sample_weighted(rows, sample_size as usize, &mut rng)?;
+            write_indexed_records(&mut idx, sampled, &mut wtr)?;
+        } else {
+            let rows = rdr
+                .byte_records()
+                .map(|row| row.map(|row| (weight(&row), row)));
+            for row in sample_weighted(rows, sample_size as usize, &mut rng)? {
+                wtr.write_byte_record(&row)?;
+            }
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    if let Some(ref column) = args.flag_cluster {
+        if rconfig.is_stdin() {
+            return fail_incorrectusage_clierror!("--cluster sampling does not support stdin.");
+        }
+        // first pass - collect the clusters
+        let mut rdr = rconfig.reader()?;
+        let col = column_index(&rconfig, &rdr.byte_headers()?.clone(), column)?;
+        let mut clusters = IndexSet::new();
+        for row in rdr.byte_records() {
+            clusters.insert(row?.get(col).unwrap_or_default().to_vec());
+        }
+        let mut clusters: Vec<Vec<u8>> = clusters.into_iter().collect();
+        #[allow(clippy::cast_precision_loss)]
+        if sample_size < 1.0 {
+            sample_size *= clusters.len() as f64;
+        }
+        let mut rng = new_rng(&rng_kind, args.flag_seed);
+        clusters.shuffle(&mut rng); //DevSkim: ignore DS148264
+        clusters.truncate(sample_size as usize);
+        let sampled_clusters: IndexSet<Vec<u8>> = clusters.into_iter().collect();
+
+        // second pass - output the records of the sampled clusters
+        let mut rdr = rconfig.reader()?;
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+        for row in rdr.byte_records() {
+            let row = row?;
+            if sampled_clusters.contains(row.get(col).unwrap_or_default()) {
+                wtr.write_byte_record(&row)?;
+            }
+        }
+        return Ok(wtr.flush()?);
+    }
+
     if let Some(mut idx) = rconfig.indexed()? {
         // the index is present, so we can use random indexing
         #[allow(clippy::cast_precision_loss)]
@@ -160,57 +373,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         rconfig.write_headers(&mut *idx, &mut wtr)?;
 
         let mut all_indices = (0..idx.count()).collect::<Vec<_>>();
-
-        match rng_kind {
-            RngKind::Standard => {
-                log::info!(
-                    "doing standard sample_random_access. Seed: {:?}",
-                    args.flag_seed
-                );
-                let mut rng: StdRng = match args.flag_seed {
-                    None => StdRng::from_rng(rand::thread_rng()).unwrap(),
-                    Some(seed) => StdRng::seed_from_u64(seed), //DevSkim: ignore DS148264
-                };
-                SliceRandom::shuffle(&mut *all_indices, &mut rng); //DevSkim: ignore DS148264
-            },
-            RngKind::Faster => {
-                log::info!(
-                    "doing --faster sample_random_access. Seed: {:?}",
-                    args.flag_seed
-                );
-
-                let mut rng = match args.flag_seed {
-                    None => Xoshiro256Plus::from_rng(rand::thread_rng()).unwrap(),
-                    Some(seed) => Xoshiro256Plus::seed_from_u64(seed), //DevSkim: ignore DS148264
-                };
-                SliceRandom::shuffle(&mut *all_indices, &mut rng); //DevSkim: ignore DS148264
-            },
-            RngKind::Cryptosecure => {
-                log::info!(
-                    "doing cryptosecure sample_random_access. Seed: {:?}",
-                    args.flag_seed
-                );
-                let seed_32 = match args.flag_seed {
-                    None => rand::thread_rng().gen::<[u8; 32]>(),
-                    Some(seed) => {
-                        let seed_u8 = seed.to_le_bytes();
-                        let mut seed_32 = [0u8; 32];
-                        seed_32[..8].copy_from_slice(&seed_u8);
-                        seed_32
-                    },
-                };
-                let mut rng: Hc128Rng = match args.flag_seed {
-                    None => Hc128Rng::from_rng(rand::thread_rng()).unwrap(),
-                    Some(_) => Hc128Rng::from_seed(seed_32),
-                };
-                SliceRandom::shuffle(&mut *all_indices, &mut rng);
-            },
-        }
-
-        for i in all_indices.into_iter().take(sample_size as usize) {
-            idx.seek(i)?;
-            wtr.write_byte_record(&idx.byte_records().next().unwrap()?)?;
-        }
+        log::info!(
+            "doing {rng_kind:?} sample_random_access. Seed: {:?}",
+            args.flag_seed
+        );
+        let mut rng = new_rng(&rng_kind, args.flag_seed);
+        SliceRandom::shuffle(&mut *all_indices, &mut rng); //DevSkim: ignore DS148264
+        all_indices.truncate(sample_size as usize);
+        write_indexed_records(&mut idx, all_indices, &mut wtr)?;
     } else {
         // the index is not present, so we have to do reservoir sampling
         #[allow(clippy::cast_precision_loss)]
@@ -222,7 +392,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
         let mut rdr = rconfig.reader()?;
         rconfig.write_headers(&mut rdr, &mut wtr)?;
-        let sampled = sample_reservoir(&mut rdr, sample_size as u64, args.flag_seed, &rng_kind)?;
+        log::info!(
+            "doing {rng_kind:?} sample_reservoir. Seed: {:?}",
+            args.flag_seed
+        );
+        let sampled = sample_reservoir(
+            &mut rdr,
+            sample_size as u64,
+            &mut new_rng(&rng_kind, args.flag_seed),
+        )?;
         for row in sampled {
             wtr.write_byte_record(&row)?;
         }
@@ -234,8 +412,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 fn sample_reservoir<R: io::Read>(
     rdr: &mut csv::Reader<R>,
     sample_size: u64,
-    seed: Option<u64>,
-    rng_kind: &RngKind,
+    rng: &mut impl Rng,
 ) -> CliResult<Vec<csv::ByteRecord>> {
     // The following algorithm has been adapted from:
     // https://en.wikipedia.org/wiki/Reservoir_sampling
@@ -245,81 +422,154 @@ fn sample_reservoir<R: io::Read>(
         reservoir.push(row?);
     }
 
+    let mut random: usize;
+    // Now do the sampling.
+    for (i, row) in records {
+        random = rng.gen_range(0..=i);
+        if random < sample_size as usize {
+            reservoir[random] = row?;
+        }
+    }
+
+    Ok(reservoir)
+}
+
+/// returns the RNG of the given kind, seeded with the given seed if any
+fn new_rng(rng_kind: &RngKind, seed: Option<u64>) -> Box<dyn RngCore> {
     match *rng_kind {
-        RngKind::Standard => {
-            log::info!("doing standard sample_random_access. Seed: {seed:?}",);
-            let mut rng: StdRng = match seed {
-                None => StdRng::from_rng(rand::thread_rng()).unwrap(),
-                // the non-cryptographic seed_from_u64 is sufficient for our use case
-                // as we're optimizing for performance
-                Some(seed) => StdRng::seed_from_u64(seed), //DevSkim: ignore DS148264
-            };
+        RngKind::Standard => Box::new(match seed {
+            None => StdRng::from_rng(rand::thread_rng()).unwrap(),
+            // the non-cryptographic seed_from_u64 is sufficient for our use case
+            // as we're optimizing for performance
+            Some(seed) => StdRng::seed_from_u64(seed), //DevSkim: ignore DS148264
+        }),
+        RngKind::Faster => Box::new(match seed {
+            None => Xoshiro256Plus::from_rng(rand::thread_rng()).unwrap(),
+            Some(seed) => Xoshiro256Plus::seed_from_u64(seed), //DevSkim: ignore DS148264
+        }),
+        RngKind::Cryptosecure => Box::new(match seed {
+            None => Hc128Rng::from_rng(rand::thread_rng()).unwrap(),
+            Some(seed) => {
+                let mut seed_32 = [0u8; 32];
+                seed_32[..8].copy_from_slice(&seed.to_le_bytes());
+                Hc128Rng::from_seed(seed_32)
+            },
+        }),
+    }
+}
 
-            let mut random: usize;
-            // Now do the sampling.
-            for (i, row) in records {
-                random = rng.gen_range(0..=i);
-                if random < sample_size as usize {
-                    reservoir[random] = row?;
-                }
-            }
-        },
-        RngKind::Faster => {
-            log::info!("doing --faster sample_random_access. Seed: {seed:?}",);
-
-            let mut rng = match seed {
-                None => Xoshiro256Plus::from_rng(rand::thread_rng()).unwrap(),
-                // the non-cryptographic seed_from_u64 is sufficient for our use case
-                // as we're optimizing for performance
-                Some(seed) => Xoshiro256Plus::seed_from_u64(seed), //DevSkim: ignore DS148264
-            };
+/// writes the records with the given row numbers, seeking to them with the index
+fn write_indexed_records<W: io::Write>(
+    idx: &mut Indexed<fs::File, fs::File>,
+    rownums: impl IntoIterator<Item = u64>,
+    wtr: &mut csv::Writer<W>,
+) -> CliResult<()> {
+    for rownum in rownums {
+        idx.seek(rownum)?;
+        wtr.write_byte_record(&idx.byte_records().next().unwrap()?)?;
+    }
+    Ok(())
+}
+
+/// returns the index of the given column, which must select exactly one column
+fn column_index(rconfig: &Config, headers: &csv::ByteRecord, column: &str) -> CliResult<usize> {
+    let sel = rconfig
+        .clone()
+        .select(SelectColumns::parse(column)?)
+        .selection(headers)?;
+    if sel.len() != 1 {
+        return fail_incorrectusage_clierror!("`{column}` must select exactly one column.");
+    }
+    Ok(sel[0])
+}
 
-            let mut random: usize;
-            // Now do the sampling.
-            for (i, row) in records {
-                random = rng.gen_range(0..=i);
-                if random < sample_size as usize {
-                    reservoir[random] = row?;
-                }
+/// reservoir samples up to sample_size rows from each stratum, i.e. each distinct
+/// value of the stratum column. The rows are (stratum, item) pairs, where the item is
+/// the record, or its row number when the records are read back with the index.
+fn sample_stratified<T>(
+    rows: impl Iterator<Item = csv::Result<(Vec<u8>, T)>>,
+    sample_size: usize,
+    rng: &mut impl Rng,
+) -> CliResult<Vec<T>> {
+    // the number of rows seen and the reservoir of each stratum
+    let mut strata: IndexMap<Vec<u8>, (usize, Vec<T>)> = IndexMap::new();
+    for row in rows {
+        let (stratum, item) = row?;
+        let (seen, reservoir) = strata
+            .entry(stratum)
+            .or_insert_with(|| (0, Vec::with_capacity(sample_size)));
+        if reservoir.len() < sample_size {
+            reservoir.push(item);
+        } else {
+            let random = rng.gen_range(0..=*seen);
+            if random < sample_size {
+                reservoir[random] = item;
             }
+        }
+        *seen += 1;
+    }
+    Ok(strata
+        .into_values()
+        .flat_map(|(_, reservoir)| reservoir)
+        .collect())
+}
 
-            // if let Some(seed) = seed {
-            //     fastrand::seed(seed); //DevSkim: ignore DS148264
-            // }
-
-            // let mut random: usize;
-            // for (i, row) in records {
-            //     random = fastrand::usize(0..=i); //DevSkim: ignore DS148264
-            //     if random < sample_size as usize {
-            //         reservoir[random] = row?;
-            //     }
-            // }
-        },
-        RngKind::Cryptosecure => {
-            log::info!("doing cryptosecure sample_random_access. Seed: {seed:?}",);
-
-            let seed_32 = match seed {
-                None => rand::thread_rng().gen::<[u8; 32]>(),
-                Some(seed) => {
-                    let seed_u8 = seed.to_le_bytes();
-                    let mut seed_32 = [0u8; 32];
-                    seed_32[..8].copy_from_slice(&seed_u8);
-                    seed_32
-                },
-            };
-            let mut rng: Hc128Rng = match seed {
-                None => Hc128Rng::from_rng(rand::thread_rng()).unwrap(),
-                Some(_) => Hc128Rng::from_seed(seed_32),
-            };
+/// a sampled row of weighted sampling, ordered by its key
+/// so the row with the smallest key is at the top of the heap
+struct WeightedRow<T> {
+    key:    f64,
+    rownum: usize,
+    item:   T,
+}
 
-            for (i, row) in records {
-                let random = rng.gen_range(0..=i);
-                if random < sample_size as usize {
-                    reservoir[random] = row?;
-                }
-            }
-        },
+impl<T> PartialEq for WeightedRow<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
     }
+}
 
-    Ok(reservoir)
+impl<T> Eq for WeightedRow<T> {}
+
+impl<T> PartialOrd for WeightedRow<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for WeightedRow<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        other.key.total_cmp(&self.key)
+    }
+}
+
+/// weighted random sampling without replacement using the A-Res algorithm:
+/// https://en.wikipedia.org/wiki/Reservoir_sampling#Algorithm_A-Res
+/// The rows are (weight, item) pairs, where the item is the record, or its
+/// row number when the records are read back with the index.
+fn sample_weighted<T>(
+    rows: impl Iterator<Item = csv::Result<(f64, T)>>,
+    sample_size: usize,
+    rng: &mut impl Rng,
+) -> CliResult<Vec<T>> {
+    if sample_size == 0 {
+        return Ok(vec![]);
+    }
+    let mut heap: BinaryHeap<WeightedRow<T>> = BinaryHeap::with_capacity(sample_size + 1);
+    for (rownum, row) in rows.enumerate() {
+        let (weight, item) = row?;
+        if weight <= 0.0 || !weight.is_finite() {
+            continue;
+        }
+        let key = rng.gen::<f64>().powf(1.0 / weight);
+        if heap.len() < sample_size {
+            heap.push(WeightedRow { key, rownum, item });
+        } else if heap.peek().map_or(false, |min| key > min.key) {
+            heap.pop();
+            heap.push(WeightedRow { key, rownum, item });
+        }
+    }
+    // output the sampled rows in their input order
+    let mut sampled = heap.into_vec();
+    sampled.sort_unstable_by_key(|weighted| weighted.rownum);
+    Ok(sampled.into_iter().map(|weighted| weighted.item).collect())
 }
@@ -97,7 +97,7 @@ use crate::{
     cmd::stats::Stats,
     config::{Config, Delimiter, DEFAULT_RDR_BUFFER_CAPACITY},
     select::SelectColumns,
-    util, CliResult,
+    statscache, util, CliResult,
 };
 
 #[derive(Deserialize, Clone)]
@@ -438,6 +438,10 @@ fn get_stats_records(args: &Args) -> CliResult<(ByteRecord, Vec<Stats>, AHashMap
         flag_median:          false,
         flag_quartiles:       false,
         flag_mad:             false,
+        flag_approx:          false,
+        flag_quality:         false,
+        flag_percentiles:     None,
+        flag_histogram:       None,
         flag_nulls:           false,
         flag_round:           4,
         flag_infer_dates:     true,
@@ -457,11 +461,7 @@ fn get_stats_records(args: &Args) -> CliResult<(ByteRecord, Vec<Stats>, AHashMap
     let stats_binary_encoded_path = canonical_input_path.with_extension("stats.csv.bin.sz");
 
     let stats_bin_current = if stats_binary_encoded_path.exists() {
-        let stats_bin_metadata = std::fs::metadata(&stats_binary_encoded_path)?;
-
-        let input_metadata = std::fs::metadata(args.arg_input.clone().unwrap())?;
-
-        if stats_bin_metadata.modified()? > input_metadata.modified()? {
+        if statscache::is_current(&stats_binary_encoded_path, &canonical_input_path) {
             info!("Valid stats.csv.bin.sz file found!");
             true
         } else {
@@ -650,6 +650,7 @@ fn get_unique_values(
         flag_asc:            false,
         flag_no_nulls:       true,
         flag_ignore_case:    args.flag_ignore_case,
+        flag_stats_mode:     "none".to_string(),
         flag_jobs:           Some(util::njobs(args.flag_jobs)),
         flag_output:         None,
         flag_no_headers:     args.flag_no_headers,
@@ -74,7 +74,7 @@ Common options:
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 use indicatif::{HumanCount, ProgressBar, ProgressDrawTarget};
 use log::{debug, info};
-use regex::bytes::RegexBuilder;
+use regex::bytes::{Regex, RegexBuilder};
 use serde::Deserialize;
 
 use crate::{
@@ -107,21 +107,34 @@ struct Args {
     flag_quiet:          bool,
 }
 
+/// Compiles the regex used to match fields. Size limits are in MB.
+/// Unicode support can also be enabled with the QSV_REGEX_UNICODE env var.
+pub fn build_regex(
+    regex: &str,
+    ignore_case: bool,
+    unicode: bool,
+    size_limit: usize,
+    dfa_size_limit: usize,
+) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(regex)
+        .case_insensitive(ignore_case)
+        .unicode(unicode || util::get_envvar_flag("QSV_REGEX_UNICODE"))
+        .size_limit(size_limit * (1 << 20))
+        .dfa_size_limit(dfa_size_limit * (1 << 20))
+        .build()
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
-    let regex_unicode = if util::get_envvar_flag("QSV_REGEX_UNICODE") {
-        true
-    } else {
-        args.flag_unicode
-    };
 
     debug!("Compiling regular expression <{}>", args.arg_regex);
-    let pattern = RegexBuilder::new(&args.arg_regex)
-        .case_insensitive(args.flag_ignore_case)
-        .unicode(regex_unicode)
-        .size_limit(args.flag_size_limit * (1 << 20))
-        .dfa_size_limit(args.flag_dfa_size_limit * (1 << 20))
-        .build()?;
+    let pattern = build_regex(
+        &args.arg_regex,
+        args.flag_ignore_case,
+        args.flag_unicode,
+        args.flag_size_limit,
+        args.flag_dfa_size_limit,
+    )?;
     debug!("Successfully compiled regular expression!");
 
     let rconfig = Config::new(&args.arg_input)
@@ -232,13 +232,13 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
-// multithreaded streaming snappy compression
+// multithreaded streaming snappy compression, returning the number of bytes read
 pub fn compress<R: Read, W: Write + Send + 'static>(
     mut src: R,
     dst: W,
     jobs: usize,
     buf_size: usize,
-) -> CliResult<()> {
+) -> CliResult<u64> {
     let mut writer = ParCompressBuilder::<Snap>::new()
         .num_threads(jobs)?
         // the buffer size must be at least gzp::DICT_SIZE
@@ -249,10 +249,10 @@ pub fn compress<R: Read, W: Write + Send + 'static>(
         })?
         .pin_threads(Some(0))
         .from_writer(dst);
-    io::copy(&mut src, &mut writer)?;
+    let input_bytes = io::copy(&mut src, &mut writer)?;
     writer.finish()?;
 
-    Ok(())
+    Ok(input_bytes)
 }
 
 // single-threaded streaming snappy decompression
@@ -5,6 +5,28 @@ reverse, unique or random (with optional seed) order.
 The sort is done in lexicographical order.
 https://en.wikipedia.org/wiki/Lexicographic_order
 
+To sort on several columns, each with its own direction and comparison, use --by
+with a sort-spec - a comma-separated list of sort keys of the form:
+
+    <column>[:asc|:desc][:<type>]
+
+where <column> is a column name or 1-based column index (as with --select), the
+direction defaults to asc, and <type> is one of:
+    str      - compare as strings (the default)
+    i        - compare as strings, disregarding case
+    n        - compare numerically. Non-numeric values are sorted first.
+    natural  - natural/version ordering, i.e. runs of digits are compared numerically,
+               so "file2" < "file10" and "1.9.2" < "1.10.0"
+    date     - compare as dates. Unparseable dates are sorted first. Set the
+               QSV_PREFER_DMY environment variable to parse ambiguous dates as dmy.
+
+  e.g. sort by country ascending, then by revenue descending numerically and then
+  by created date ascending:
+    qsv sort --by country:asc,revenue:desc:n,created:asc:date data.csv
+
+The same sort-spec can be used with extsort to sort larger-than-memory CSVs, and
+with sortcheck to check if a CSV is sorted that way.
+
 Note that this requires reading all of the CSV data into memory. If
 you need to sort a large file that may not fit into memory, use the
 extsort command instead.
@@ -18,6 +40,9 @@ Usage:
 sort options:
     -s, --select <arg>      Select a subset of columns to sort.
                             See 'qsv select --help' for the format details.
+    --by <spec>             Sort using the given sort-spec (see above) instead of
+                            --select. Cannot be used with --numeric, --reverse,
+                            --ignore-case or --random.
     -N, --numeric           Compare according to string numerical value
     -R, --reverse           Reverse order
     -i, --ignore-case       Compare strings disregarding case
@@ -63,14 +88,21 @@ Common options:
                             CSV into memory using CONSERVATIVE heuristics.
 "#;
 
-use std::{cmp, str::FromStr};
+use std::{cmp, iter, str::FromStr};
 
 // use fastrand; //DevSkim: ignore DS148264
+use qsv_dateparser::parse_with_preference;
 use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use rand_hc::Hc128Rng;
 use rand_xoshiro::Xoshiro256Plus;
-use rayon::slice::ParallelSliceMut;
-use serde::Deserialize;
+use rayon::{
+    iter::{IntoParallelIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+use serde::{
+    de::{Deserializer, Error},
+    Deserialize,
+};
 use simdutf8::basic::from_utf8;
 use strum_macros::EnumString;
 
@@ -78,7 +110,7 @@ use self::Number::{Float, Int};
 use crate::{
     cmd::dedup::iter_cmp_ignore_case,
     config::{Config, Delimiter},
-    select::SelectColumns,
+    select::{SelectColumns, Selection},
     util, CliResult,
 };
 
@@ -86,6 +118,7 @@ use crate::{
 struct Args {
     arg_input:        Option<String>,
     flag_select:      SelectColumns,
+    flag_by:          Option<SortSpec>,
     flag_numeric:     bool,
     flag_reverse:     bool,
     flag_ignore_case: bool,
@@ -136,10 +169,20 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
+    if args.flag_by.is_some() && (numeric || reverse || random || args.flag_ignore_case) {
+        return fail_incorrectusage_clierror!(
+            "--by cannot be used with --numeric, --reverse, --ignore-case or --random."
+        );
+    }
+
     let mut rdr = rconfig.reader()?;
 
     let headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
+    let sort_keys = match args.flag_by {
+        Some(ref spec) => Some(spec.resolve(&headers, !rconfig.no_headers)?),
+        None => None,
+    };
 
     // set RAYON_NUM_THREADS
     util::njobs(args.flag_jobs);
@@ -151,6 +194,27 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let mut all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
     match (numeric, reverse, random, faster) {
+        // --by sort-spec stable or --faster unstable parallel sort
+        _ if sort_keys.is_some() => {
+            // safety: we just checked that sort_keys is_some()
+            let sort_keys = sort_keys.as_ref().unwrap();
+            // parse the dates of each record once, instead of on every comparison
+            let mut keyed: Vec<(DateKeys, csv::ByteRecord)> = all
+                .into_par_iter()
+                .map(|r| (sort_keys.date_keys(&r), r))
+                .collect();
+            let compare = |(d1, r1): &(DateKeys, csv::ByteRecord),
+                           (d2, r2): &(DateKeys, csv::ByteRecord)| {
+                sort_keys.compare_keyed(r1, d1, r2, d2)
+            };
+            if faster {
+                keyed.par_sort_unstable_by(compare);
+            } else {
+                keyed.par_sort_by(compare);
+            }
+            all = keyed.into_iter().map(|(_, r)| r).collect();
+        },
+
         // --random sort
         (_, _, true, _) => {
             match rng_kind {
@@ -261,15 +325,25 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let mut prev: Option<csv::ByteRecord> = None;
+    let mut prev_dates = DateKeys::new();
     rconfig.write_headers(&mut rdr, &mut wtr)?;
     for r in all {
         if args.flag_unique {
+            let dates = sort_keys
+                .as_ref()
+                .map(|sort_keys| sort_keys.date_keys(&r))
+                .unwrap_or_default();
             match prev {
-                Some(other_r) => match iter_cmp(sel.select(&r), sel.select(&other_r)) {
-                    cmp::Ordering::Equal => (),
-                    _ => {
+                Some(other_r) => {
+                    let comparison = match sort_keys {
+                        Some(ref sort_keys) => {
+                            sort_keys.compare_keyed(&r, &dates, &other_r, &prev_dates)
+                        },
+                        None => iter_cmp(sel.select(&r), sel.select(&other_r)),
+                    };
+                    if comparison != cmp::Ordering::Equal {
                         wtr.write_byte_record(&r)?;
-                    },
+                    }
                 },
                 None => {
                     wtr.write_byte_record(&r)?;
@@ -277,6 +351,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             }
 
             prev = Some(r);
+            prev_dates = dates;
         } else {
             wtr.write_byte_record(&r)?;
         }
@@ -369,3 +444,248 @@ where
         None => None,
     }
 }
+
+/// How the values of a sort key are compared
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortKeyType {
+    Str,
+    IgnoreCase,
+    Numeric,
+    Natural,
+    Date,
+}
+
+#[derive(Clone, Debug)]
+struct SortSpecKey {
+    column:     String,
+    descending: bool,
+    key_type:   SortKeyType,
+}
+
+/// A parsed sort-spec, e.g. `country:asc,revenue:desc:n,created:asc:date`.
+/// Its columns are resolved against the headers with `resolve`, like `SelectColumns`.
+#[derive(Clone, Debug)]
+pub struct SortSpec(Vec<SortSpecKey>);
+
+impl SortSpec {
+    pub fn parse(spec: &str) -> Result<SortSpec, String> {
+        let mut keys = Vec::new();
+        for key in spec.split(',') {
+            // peel off the direction & type from the end, so column names
+            // with colons still work
+            let mut column = key.trim();
+            let (mut descending, mut key_type) = (None, None);
+            while let Some((rest, modifier)) = column.rsplit_once(':') {
+                match modifier.to_ascii_lowercase().as_str() {
+                    "asc" if descending.is_none() => descending = Some(false),
+                    "desc" if descending.is_none() => descending = Some(true),
+                    "str" | "s" if key_type.is_none() => key_type = Some(SortKeyType::Str),
+                    "i" | "ignore-case" if key_type.is_none() => {
+                        key_type = Some(SortKeyType::IgnoreCase);
+                    },
+                    "n" | "numeric" if key_type.is_none() => {
+                        key_type = Some(SortKeyType::Numeric);
+                    },
+                    "natural" | "version" | "v" if key_type.is_none() => {
+                        key_type = Some(SortKeyType::Natural);
+                    },
+                    "date" | "d" if key_type.is_none() => key_type = Some(SortKeyType::Date),
+                    _ => break,
+                }
+                column = rest;
+            }
+            if column.is_empty() {
+                return Err(format!("Invalid sort key `{key}`: no column given."));
+            }
+            keys.push(SortSpecKey {
+                column:     column.to_string(),
+                descending: descending.unwrap_or(false),
+                key_type:   key_type.unwrap_or(SortKeyType::Str),
+            });
+        }
+        Ok(SortSpec(keys))
+    }
+
+    /// resolves the columns of the sort keys to their indices. Each key
+    /// must select exactly one column.
+    pub fn resolve(&self, headers: &csv::ByteRecord, use_names: bool) -> Result<SortKeys, String> {
+        let mut keys = Vec::with_capacity(self.0.len());
+        for key in &self.0 {
+            let sel = SelectColumns::parse(&key.column)?.selection(headers, use_names)?;
+            if sel.len() != 1 {
+                return Err(format!(
+                    "Sort key `{}` must select exactly one column.",
+                    key.column
+                ));
+            }
+            keys.push((sel[0], key.descending, key.key_type));
+        }
+        Ok(SortKeys {
+            keys,
+            prefer_dmy: util::get_envvar_flag("QSV_PREFER_DMY"),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for SortSpec {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<SortSpec, D::Error> {
+        let raw = String::deserialize(d)?;
+        SortSpec::parse(&raw).map_err(D::Error::custom)
+    }
+}
+
+/// The values of the date sort keys of a record, parsed to Unix timestamps in microseconds.
+/// Dates are slow to parse, so they're parsed once per record with `SortKeys::date_keys`,
+/// instead of on every comparison. Empty if there are no date sort keys.
+pub type DateKeys = Vec<Option<i64>>;
+
+/// The sort keys of a `SortSpec` resolved to column indices
+#[derive(Clone, Debug)]
+pub struct SortKeys {
+    keys:       Vec<(usize, bool, SortKeyType)>,
+    prefer_dmy: bool,
+}
+
+impl SortKeys {
+    /// sort keys for the selected columns with the same --numeric, --reverse &
+    /// --ignore-case semantics as the sort command
+    pub fn from_selection(
+        sel: &Selection,
+        numeric: bool,
+        reverse: bool,
+        ignore_case: bool,
+    ) -> SortKeys {
+        let key_type = if numeric {
+            SortKeyType::Numeric
+        } else if ignore_case {
+            SortKeyType::IgnoreCase
+        } else {
+            SortKeyType::Str
+        };
+        SortKeys {
+            keys:       sel.iter().map(|&idx| (idx, reverse, key_type)).collect(),
+            prefer_dmy: false,
+        }
+    }
+
+    /// parses the values of the date sort keys of a record
+    pub fn date_keys(&self, record: &csv::ByteRecord) -> DateKeys {
+        self.date_keys_by(|idx| record.get(idx))
+    }
+
+    /// same as date_keys, for records stored as a Vec of fields
+    pub fn date_keys_of_fields(&self, fields: &[Vec<u8>]) -> DateKeys {
+        self.date_keys_by(|idx| fields.get(idx).map(Vec::as_slice))
+    }
+
+    fn date_keys_by<'a>(&self, field: impl Fn(usize) -> Option<&'a [u8]>) -> DateKeys {
+        self.keys
+            .iter()
+            .filter(|&&(_, _, key_type)| key_type == SortKeyType::Date)
+            .map(|&(idx, ..)| self.parse_date(field(idx).unwrap_or_default()))
+            .collect()
+    }
+
+    /// compares two records on each of the sort keys in turn,
+    /// with the date keys of the records already parsed by date_keys
+    pub fn compare_keyed(
+        &self,
+        a: &csv::ByteRecord,
+        a_dates: &[Option<i64>],
+        b: &csv::ByteRecord,
+        b_dates: &[Option<i64>],
+    ) -> cmp::Ordering {
+        self.compare_by(|idx| a.get(idx), a_dates, |idx| b.get(idx), b_dates)
+    }
+
+    /// same as compare_keyed, for records stored as a Vec of fields
+    pub fn compare_fields(
+        &self,
+        a: &[Vec<u8>],
+        a_dates: &[Option<i64>],
+        b: &[Vec<u8>],
+        b_dates: &[Option<i64>],
+    ) -> cmp::Ordering {
+        self.compare_by(
+            |idx| a.get(idx).map(Vec::as_slice),
+            a_dates,
+            |idx| b.get(idx).map(Vec::as_slice),
+            b_dates,
+        )
+    }
+
+    fn compare_by<'a>(
+        &self,
+        a: impl Fn(usize) -> Option<&'a [u8]>,
+        a_dates: &[Option<i64>],
+        b: impl Fn(usize) -> Option<&'a [u8]>,
+        b_dates: &[Option<i64>],
+    ) -> cmp::Ordering {
+        // the index of the next date key in the DateKeys
+        let mut date_idx = 0;
+        for &(idx, descending, key_type) in &self.keys {
+            let x = a(idx).unwrap_or_default();
+            let y = b(idx).unwrap_or_default();
+            let ordering = match key_type {
+                SortKeyType::Str => x.cmp(y),
+                SortKeyType::IgnoreCase => iter_cmp_ignore_case(iter::once(x), iter::once(y)),
+                SortKeyType::Numeric => iter_cmp_num(iter::once(x), iter::once(y)),
+                SortKeyType::Natural => natural_cmp(x, y),
+                SortKeyType::Date => {
+                    date_idx += 1;
+                    a_dates[date_idx - 1].cmp(&b_dates[date_idx - 1])
+                },
+            };
+            match ordering {
+                cmp::Ordering::Equal => (),
+                non_eq if descending => return non_eq.reverse(),
+                non_eq => return non_eq,
+            }
+        }
+        cmp::Ordering::Equal
+    }
+
+    #[inline]
+    fn parse_date(&self, bytes: &[u8]) -> Option<i64> {
+        from_utf8(bytes)
+            .ok()
+            .and_then(|s| parse_with_preference(s, self.prefer_dmy).ok())
+            .map(|date| date.timestamp_micros())
+    }
+}
+
+/// Natural/version ordering - runs of digits are compared numerically,
+/// e.g. "file2" < "file10" and "1.9.2" < "1.10.0"
+pub fn natural_cmp(a: &[u8], b: &[u8]) -> cmp::Ordering {
+    fn digits_end(s: &[u8], start: usize) -> usize {
+        s[start..]
+            .iter()
+            .position(|c| !c.is_ascii_digit())
+            .map_or(s.len(), |pos| start + pos)
+    }
+    fn trim_zeros(digits: &[u8]) -> &[u8] {
+        let zeros = digits.iter().take_while(|&&c| c == b'0').count();
+        &digits[zeros..]
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+            let (end_a, end_b) = (digits_end(a, i), digits_end(b, j));
+            let (num_a, num_b) = (trim_zeros(&a[i..end_a]), trim_zeros(&b[j..end_b]));
+            // the longer run of digits (without leading zeros) is the bigger number
+            match num_a.len().cmp(&num_b.len()).then_with(|| num_a.cmp(num_b)) {
+                cmp::Ordering::Equal => (),
+                non_eq => return non_eq,
+            }
+            (i, j) = (end_a, end_b);
+        } else {
+            match a[i].cmp(&b[j]) {
+                cmp::Ordering::Equal => (),
+                non_eq => return non_eq,
+            }
+            (i, j) = (i + 1, j + 1);
+        }
+    }
+    (a.len() - i).cmp(&(b.len() - j))
+}
@@ -29,6 +29,11 @@ Usage:
 sort options:
     -s, --select <arg>      Select a subset of columns to check for sort.
                             See 'qsv select --help' for the format details.
+    --by <spec>             Check if the CSV is sorted according to the given sort-spec
+                            (e.g. country:asc,revenue:desc:n) instead of --select,
+                            i.e. in the same order as `qsv sort --by <spec>`.
+                            See 'qsv sort --help' for the sort-spec format details.
+                            Cannot be used with --ignore-case.
     -i, --ignore-case       Compare strings disregarding case
     --all                   Check all records. Do not stop/short-circuit the check 
                             on the first unsorted record.
@@ -62,7 +67,10 @@ use indicatif::{HumanCount, ProgressBar, ProgressDrawTarget};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    cmd::{dedup, sort::iter_cmp},
+    cmd::{
+        dedup,
+        sort::{iter_cmp, SortSpec},
+    },
     config::{Config, Delimiter},
     select::SelectColumns,
     util, CliResult,
@@ -73,6 +81,7 @@ use crate::{
 struct Args {
     arg_input:        Option<String>,
     flag_select:      SelectColumns,
+    flag_by:          Option<SortSpec>,
     flag_ignore_case: bool,
     flag_all:         bool,
     flag_no_headers:  bool,
@@ -98,10 +107,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
+    if args.flag_by.is_some() && ignore_case {
+        return fail_incorrectusage_clierror!("--by cannot be used with --ignore-case.");
+    }
+
     let mut rdr = rconfig.reader()?;
 
     let headers = rdr.byte_headers()?.clone();
     let sel = rconfig.selection(&headers)?;
+    let sort_keys = match args.flag_by {
+        Some(ref spec) => Some(spec.resolve(&headers, !rconfig.no_headers)?),
+        None => None,
+    };
     let record_count;
 
     // prep progress bar
@@ -136,6 +153,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut unsorted_breaks: u64 = 0;
 
     rdr.read_byte_record(&mut record)?;
+    // the parsed dates of the date sort keys of record & next_record
+    let mut record_dates = sort_keys
+        .as_ref()
+        .map(|sort_keys| sort_keys.date_keys(&record))
+        .unwrap_or_default();
+    let mut next_dates = Vec::new();
     loop {
         #[cfg(any(feature = "feature_capable", feature = "lite"))]
         if show_progress {
@@ -148,7 +171,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         };
         let a = sel.select(&record);
         let b = sel.select(&next_record);
-        let comparison = if ignore_case {
+        let comparison = if let Some(ref sort_keys) = sort_keys {
+            next_dates = sort_keys.date_keys(&next_record);
+            sort_keys.compare_keyed(&record, &record_dates, &next_record, &next_dates)
+        } else if ignore_case {
             dedup::iter_cmp_ignore_case(a, b)
         } else {
             iter_cmp(a, b)
@@ -160,12 +186,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             },
             cmp::Ordering::Less => {
                 record.clone_from(&next_record);
+                std::mem::swap(&mut record_dates, &mut next_dates);
             },
             cmp::Ordering::Greater => {
                 sorted = false;
                 if args.flag_all || do_json {
                     unsorted_breaks += 1;
                     record.clone_from(&next_record);
+                    std::mem::swap(&mut record_dates, &mut next_dates);
                 } else {
                     break;
                 }
@@ -100,8 +100,8 @@ Example queries:
    qsv sqlp small_dummy.csv "select * from read_csv('data.csv') order by col1 desc limit 100"
 
   Note that sqlp will automatically use this "fast path" optimization when there is only 
-  one input CSV file, no CSV parsing options are used, its not a SQL script and the
-  `--no-optimizations` flag is not set.
+  one input CSV file, no CSV parsing options are used, its not a SQL script, the
+  `--no-optimizations` flag is not set and the input file has no stats cache (see --stats-mode).
 
   # use stdin as input
    cat data.csv | qsv sqlp - 'select * from stdin'
@@ -159,6 +159,14 @@ sqlp options:
     --infer-len <arg>         The number of rows to scan when inferring the schema of the CSV.
                               Set to 0 to do a full table scan (warning: very slow).
                               [default: 1000]
+    --stats-mode <arg>        How to use the stats cache of the input files. Valid values are:
+                                auto - if an input file has a current stats cache (i.e.
+                                       `qsv stats` was run & its results were cached),
+                                       use its inferred types for the Integer, Float &
+                                       String columns instead of inferring them from
+                                       the first --infer-len rows.
+                                none - don't use the stats cache.
+                              [default: auto]
     --low-memory              Use low memory mode when parsing CSVs. This will use less memory
                               but will be slower. It will also process LazyFrames in streaming mode.
                               Only use this when you get out of memory errors.
@@ -225,6 +233,7 @@ use std::{
     io::{Read, Write},
     path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
     time::Instant,
 };
 
@@ -233,7 +242,7 @@ use polars::{
     prelude::{
         CsvWriter, DataFrame, GzipLevel, IpcCompression, IpcWriter, JsonFormat, JsonWriter,
         LazyCsvReader, LazyFileListReader, NullValues, ParquetCompression, ParquetWriter,
-        SerWriter, ZstdLevel,
+        PolarsResult, SerWriter, ZstdLevel,
     },
     sql::SQLContext,
 };
@@ -243,6 +252,7 @@ use serde::Deserialize;
 use crate::{
     cmd::joinp::tsvtab_delim,
     config::{Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
+    statscache::StatsCache,
     util,
     util::process_input,
     CliResult,
@@ -258,6 +268,7 @@ struct Args {
     flag_format:                String,
     flag_try_parsedates:        bool,
     flag_infer_len:             usize,
+    flag_stats_mode:            String,
     flag_low_memory:            bool,
     flag_no_optimizations:      bool,
     flag_ignore_errors:         bool,
@@ -277,7 +288,7 @@ struct Args {
 }
 
 #[derive(Default, Clone, PartialEq)]
-enum OutputMode {
+pub enum OutputMode {
     #[default]
     Csv,
     Json,
@@ -288,6 +299,37 @@ enum OutputMode {
     None,
 }
 
+/// the output options of the `--format` output modes.
+/// Also used by the other Polars-powered commands that support the same output formats.
+#[derive(Default, Clone)]
+pub struct OutputOptions {
+    pub output:          Option<String>,
+    pub datetime_format: Option<String>,
+    pub date_format:     Option<String>,
+    pub time_format:     Option<String>,
+    pub float_precision: Option<usize>,
+    pub wnull_value:     String,
+    pub compression:     String,
+    pub compress_level:  Option<i32>,
+    pub statistics:      bool,
+}
+
+impl From<Args> for OutputOptions {
+    fn from(args: Args) -> Self {
+        OutputOptions {
+            output:          args.flag_output,
+            datetime_format: args.flag_datetime_format,
+            date_format:     args.flag_date_format,
+            time_format:     args.flag_time_format,
+            float_precision: args.flag_float_precision,
+            wnull_value:     args.flag_wnull_value,
+            compression:     args.flag_compression,
+            compress_level:  args.flag_compress_level,
+            statistics:      args.flag_statistics,
+        }
+    }
+}
+
 // shamelessly copied from
 // https://github.com/pola-rs/polars-cli/blob/main/src/main.rs
 impl OutputMode {
@@ -295,7 +337,7 @@ impl OutputMode {
         &self,
         query: &str,
         ctx: &mut SQLContext,
-        mut delim: u8,
+        delim: u8,
         args: Args,
     ) -> CliResult<(usize, usize)> {
         let mut df = DataFrame::default();
@@ -304,105 +346,7 @@ impl OutputMode {
                 .execute(query)
                 .and_then(polars::prelude::LazyFrame::collect)?;
 
-            // we don't want to write anything if the output mode is None
-            if matches!(self, OutputMode::None) {
-                return Ok(());
-            }
-
-            let w = match args.flag_output {
-                Some(path) => {
-                    delim = tsvtab_delim(path.clone(), delim);
-                    Box::new(File::create(path)?) as Box<dyn Write>
-                },
-                None => Box::new(io::stdout()) as Box<dyn Write>,
-            };
-            let mut w = io::BufWriter::with_capacity(256_000, w);
-
-            let out_result = match self {
-                OutputMode::Csv => CsvWriter::new(&mut w)
-                    .with_separator(delim)
-                    .with_datetime_format(args.flag_datetime_format)
-                    .with_date_format(args.flag_date_format)
-                    .with_time_format(args.flag_time_format)
-                    .with_float_precision(args.flag_float_precision)
-                    .with_null_value(args.flag_wnull_value)
-                    .include_bom(util::get_envvar_flag("QSV_OUTPUT_BOM"))
-                    .finish(&mut df),
-                OutputMode::Json => JsonWriter::new(&mut w)
-                    .with_json_format(JsonFormat::Json)
-                    .finish(&mut df),
-                OutputMode::Jsonl => JsonWriter::new(&mut w)
-                    .with_json_format(JsonFormat::JsonLines)
-                    .finish(&mut df),
-                OutputMode::Parquet => {
-                    let compression: PqtCompression = args
-                        .flag_compression
-                        .parse()
-                        .unwrap_or(PqtCompression::Uncompressed);
-
-                    let parquet_compression = match compression {
-                        PqtCompression::Uncompressed => ParquetCompression::Uncompressed,
-                        PqtCompression::Snappy => ParquetCompression::Snappy,
-                        PqtCompression::Lz4Raw => ParquetCompression::Lz4Raw,
-                        PqtCompression::Gzip => {
-                            let gzip_level = args
-                                .flag_compress_level
-                                .unwrap_or_else(|| DEFAULT_GZIP_COMPRESSION_LEVEL.into())
-                                as u8;
-                            ParquetCompression::Gzip(Some(GzipLevel::try_new(gzip_level)?))
-                        },
-                        PqtCompression::Zstd => {
-                            let zstd_level = args
-                                .flag_compress_level
-                                .unwrap_or(DEFAULT_ZSTD_COMPRESSION_LEVEL);
-                            ParquetCompression::Zstd(Some(ZstdLevel::try_new(zstd_level)?))
-                        },
-                    };
-
-                    ParquetWriter::new(&mut w)
-                        .with_row_group_size(Some(768 ^ 2))
-                        .with_statistics(args.flag_statistics)
-                        .with_compression(parquet_compression)
-                        .finish(&mut df)
-                        .map(|_| ())
-                },
-                OutputMode::Arrow => {
-                    let compression: ArrowCompression = args
-                        .flag_compression
-                        .parse()
-                        .unwrap_or(ArrowCompression::Uncompressed);
-
-                    let ipc_compression: Option<IpcCompression> = match compression {
-                        ArrowCompression::Uncompressed => None,
-                        ArrowCompression::Lz4 => Some(IpcCompression::LZ4),
-                        ArrowCompression::Zstd => Some(IpcCompression::ZSTD),
-                    };
-
-                    IpcWriter::new(&mut w)
-                        .with_compression(ipc_compression)
-                        .finish(&mut df)
-                },
-                OutputMode::Avro => {
-                    let compression: QsvAvroCompression = args
-                        .flag_compression
-                        .parse()
-                        .unwrap_or(QsvAvroCompression::Uncompressed);
-
-                    let avro_compression = match compression {
-                        QsvAvroCompression::Uncompressed => None,
-                        QsvAvroCompression::Deflate => Some(AvroCompression::Deflate),
-                        QsvAvroCompression::Snappy => Some(AvroCompression::Snappy),
-                    };
-
-                    AvroWriter::new(&mut w)
-                        .with_compression(avro_compression)
-                        .finish(&mut df)
-                },
-                OutputMode::None => Ok(()),
-            };
-
-            w.flush()?;
-            out_result
+            self.write_dataframe(&mut df, delim, args.into())
         };
 
         match execute_inner() {
@@ -412,6 +356,116 @@ impl OutputMode {
             },
         }
     }
+
+    /// writes the DataFrame using the output mode and options.
+    /// Note that Snappy compression of the output is done separately
+    /// with compress_output_if_needed()
+    pub fn write_dataframe(
+        &self,
+        df: &mut DataFrame,
+        mut delim: u8,
+        options: OutputOptions,
+    ) -> PolarsResult<()> {
+        // we don't want to write anything if the output mode is None
+        if matches!(self, OutputMode::None) {
+            return Ok(());
+        }
+
+        let w = match options.output {
+            Some(path) => {
+                delim = tsvtab_delim(path.clone(), delim);
+                Box::new(File::create(path)?) as Box<dyn Write>
+            },
+            None => Box::new(io::stdout()) as Box<dyn Write>,
+        };
+        let mut w = io::BufWriter::with_capacity(256_000, w);
+
+        let out_result = match self {
+            OutputMode::Csv => CsvWriter::new(&mut w)
+                .with_separator(delim)
+                .with_datetime_format(options.datetime_format)
+                .with_date_format(options.date_format)
+                .with_time_format(options.time_format)
+                .with_float_precision(options.float_precision)
+                .with_null_value(options.wnull_value)
+                .include_bom(util::get_envvar_flag("QSV_OUTPUT_BOM"))
+                .finish(df),
+            OutputMode::Json => JsonWriter::new(&mut w)
+                .with_json_format(JsonFormat::Json)
+                .finish(df),
+            OutputMode::Jsonl => JsonWriter::new(&mut w)
+                .with_json_format(JsonFormat::JsonLines)
+                .finish(df),
+            OutputMode::Parquet => {
+                let compression: PqtCompression = options
+                    .compression
+                    .parse()
+                    .unwrap_or(PqtCompression::Uncompressed);
+
+                let parquet_compression = match compression {
+                    PqtCompression::Uncompressed => ParquetCompression::Uncompressed,
+                    PqtCompression::Snappy => ParquetCompression::Snappy,
+                    PqtCompression::Lz4Raw => ParquetCompression::Lz4Raw,
+                    PqtCompression::Gzip => {
+                        let gzip_level = options
+                            .compress_level
+                            .unwrap_or_else(|| DEFAULT_GZIP_COMPRESSION_LEVEL.into())
+                            as u8;
+                        ParquetCompression::Gzip(Some(GzipLevel::try_new(gzip_level)?))
+                    },
+                    PqtCompression::Zstd => {
+                        let zstd_level = options
+                            .compress_level
+                            .unwrap_or(DEFAULT_ZSTD_COMPRESSION_LEVEL);
+                        ParquetCompression::Zstd(Some(ZstdLevel::try_new(zstd_level)?))
+                    },
+                };
+
+                ParquetWriter::new(&mut w)
+                    .with_row_group_size(Some(768 ^ 2))
+                    .with_statistics(options.statistics)
+                    .with_compression(parquet_compression)
+                    .finish(df)
+                    .map(|_| ())
+            },
+            OutputMode::Arrow => {
+                let compression: ArrowCompression = options
+                    .compression
+                    .parse()
+                    .unwrap_or(ArrowCompression::Uncompressed);
+
+                let ipc_compression: Option<IpcCompression> = match compression {
+                    ArrowCompression::Uncompressed => None,
+                    ArrowCompression::Lz4 => Some(IpcCompression::LZ4),
+                    ArrowCompression::Zstd => Some(IpcCompression::ZSTD),
+                };
+
+                IpcWriter::new(&mut w)
+                    .with_compression(ipc_compression)
+                    .finish(df)
+            },
+            OutputMode::Avro => {
+                let compression: QsvAvroCompression = options
+                    .compression
+                    .parse()
+                    .unwrap_or(QsvAvroCompression::Uncompressed);
+
+                let avro_compression = match compression {
+                    QsvAvroCompression::Uncompressed => None,
+                    QsvAvroCompression::Deflate => Some(AvroCompression::Deflate),
+                    QsvAvroCompression::Snappy => Some(AvroCompression::Snappy),
+                };
+
+                AvroWriter::new(&mut w)
+                    .with_compression(avro_compression)
+                    .finish(df)
+            },
+            OutputMode::None => Ok(()),
+        };
+
+        w.flush()?;
+        out_result
+    }
 }
 
 impl FromStr for OutputMode {
@@ -499,6 +553,17 @@ impl FromStr for QsvAvroCompression {
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
 
+    let use_stats_cache = match args.flag_stats_mode.as_str() {
+        "auto" => true,
+        "none" => false,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Invalid --stats-mode: {}. Valid values are: auto, none.",
+                args.flag_stats_mode
+            )
+        },
+    };
+
     let tmpdir = tempfile::tempdir()?;
 
     let mut skip_input = false;
@@ -621,6 +686,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         && std::path::Path::new(&args.arg_input[0])
             .extension()
             .map_or(false, |ext| ext.eq_ignore_ascii_case("csv"))
+        // the fast path can't use the column types of the stats cache
+        && !(use_stats_cache && StatsCache::load(&args.arg_input[0]).is_some())
     {
         // replace all instances of the FROM clause case-insensitive in the SQL query with the
         // read_csv function using a regex
@@ -672,6 +739,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     alias = table_aliases.get(table_name).unwrap(),
                 );
             }
+            // use the column types of the stats cache, if available
+            let dtypes = if use_stats_cache {
+                StatsCache::load(table).map(|stats_cache| {
+                    Arc::new(stats_cache.polars_dtypes(args.flag_try_parsedates))
+                })
+            } else {
+                None
+            };
             let lf = LazyCsvReader::new(table)
                 .has_header(true)
                 .with_missing_is_null(true)
@@ -679,6 +754,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 .with_null_values(Some(NullValues::AllColumns(rnull_values.clone())))
                 .with_separator(tsvtab_delim(table, delim))
                 .with_infer_schema_length(Some(args.flag_infer_len))
+                .with_dtype_overwrite(dtypes)
                 .with_try_parse_dates(args.flag_try_parsedates)
                 .with_ignore_errors(args.flag_ignore_errors)
                 .truncate_ragged_lines(args.flag_truncate_ragged_lines)
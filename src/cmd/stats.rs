@@ -16,6 +16,21 @@ The following additional "non-streaming" statistics require loading the entire f
 cardinality, mode/antimode, median, MAD, quartiles and its related measures (IQR,
 lower/upper fences & skewness).
 
+For very large files, the --approx option computes the cardinality, median, MAD & quartiles
+in constant memory instead, using a HyperLogLog sketch for the cardinality and a t-digest
+for the rest. These approximate results are mergeable, so they can also be computed in
+parallel with an index. The approximated columns are listed in the "approx_columns" field
+of <FILESTEM>.stats.csv.json.
+
+The --quality option adds data quality signals for each column: the number of values
+outside the inner/outer fences, the number of values with leading/trailing whitespace or
+mixed case, whether the column is sorted, and its most common character-class patterns.
+
+Percentiles & histograms of numeric and date columns can also be computed with the
+--percentiles and --histogram options. They're exact when the column values are already
+loaded into memory for the "non-streaming" statistics above. Otherwise, they're
+approximated in constant memory using a t-digest sketch.
+
 When computing “non-streaming” statistics, an Out-Of-Memory (OOM) heuristic check is done.
 If the file is larger than the available memory minus a headroom buffer of 20% (which can be
 adjusted using the QSV_FREEMEMORY_HEADROOM_PCT environment variable), processing will be
@@ -94,6 +109,43 @@ stats options:
     --quartiles               Compute the quartiles, the IQR, the lower/upper inner/outer
                               fences and skewness.
                               This requires loading all CSV data in memory.
+    --approx                  Approximate the cardinality, median, MAD & quartiles in constant
+                              memory instead of loading all CSV data in memory. The
+                              cardinality is estimated with a HyperLogLog sketch (with
+                              a ~1% standard error), and the median, MAD & quartiles with a
+                              t-digest. Note that the mode/s & antimode/s still require
+                              loading all CSV data in memory.
+    --quality                 Compute data quality statistics:
+                                outside_inner_fences - the number of values outside the
+                                  lower/upper inner fences (i.e. mild & extreme outliers)
+                                outside_outer_fences - the number of values outside the
+                                  lower/upper outer fences (i.e. extreme outliers)
+                                whitespace_padded - the number of values with leading or
+                                  trailing whitespace
+                                mixed_case - the number of values with both uppercase
+                                  and lowercase letters
+                                sort_order - Ascending, Descending, Constant or Unsorted.
+                                  Numbers & dates are compared by value, strings
+                                  lexicographically.
+                                top_patterns - the 3 most common character-class patterns
+                                  with their counts, where uppercase letters are "A",
+                                  lowercase letters "a", digits "9" & whitespace " ".
+                                  e.g. "AAA-999:120, AA-999:15"
+                              The fences are the ones computed by --quartiles. If
+                              --quartiles is not set, they're approximated with a t-digest.
+    --percentiles <list>      Compute the given comma-separated list of percentiles (0-100),
+                              e.g. 5,10,90,99. Each percentile is returned in its own
+                              "p<N>" column (e.g. p5, p10, p90 & p99), after all the
+                              other statistics.
+                              The percentiles are exact when the median, MAD or quartiles
+                              are also computed. Otherwise, they're approximated using
+                              a t-digest, which works with arbitrarily large CSV files.
+    --histogram <bins>        Compute a histogram of each numeric & date column with
+                              <bins> equal-width bins between its min and max values.
+                              The histograms are written as JSON to
+                              <FILESTEM>.stats.histogram.json. Like --percentiles, the bin
+                              counts are only exact when the median, MAD or quartiles
+                              are also computed.
     --round <decimal_places>  Round statistics to <decimal_places>. Rounding is done following
                               Midpoint Nearest Even (aka "Bankers Rounding") rule.
                               https://docs.rs/rust_decimal/latest/rust_decimal/enum.RoundingStrategy.html
@@ -185,6 +237,7 @@ with ~500 tests.
 */
 
 use std::{
+    collections::HashMap,
     default::Default,
     fmt, fs, io,
     io::Write,
@@ -223,6 +276,10 @@ pub struct Args {
     pub flag_median:          bool,
     pub flag_mad:             bool,
     pub flag_quartiles:       bool,
+    pub flag_approx:          bool,
+    pub flag_quality:         bool,
+    pub flag_percentiles:     Option<String>,
+    pub flag_histogram:       Option<usize>,
     pub flag_round:           u32,
     pub flag_nulls:           bool,
     pub flag_infer_dates:     bool,
@@ -253,6 +310,10 @@ struct StatsArgs {
     flag_median:          bool,
     flag_mad:             bool,
     flag_quartiles:       bool,
+    flag_approx:          bool,
+    flag_quality:         bool,
+    flag_percentiles:     Option<String>,
+    flag_histogram:       Option<usize>,
     flag_round:           u32,
     flag_nulls:           bool,
     flag_infer_dates:     bool,
@@ -267,9 +328,12 @@ struct StatsArgs {
     date_generated:       String,
     compute_duration_ms:  u64,
     qsv_version:          String,
+    approx_columns:       Vec<String>,
 }
 
 static INFER_DATE_FLAGS: OnceLock<Vec<bool>> = OnceLock::new();
+// the (column name, percentile) pairs of --percentiles
+static PERCENTILES: OnceLock<Vec<(String, f64)>> = OnceLock::new();
 static RECORD_COUNT: OnceLock<u64> = OnceLock::new();
 
 // number of milliseconds per day
@@ -288,6 +352,36 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         args.flag_median = false;
         args.flag_quartiles = false;
         args.flag_mad = false;
+        args.flag_quality = false;
+        args.flag_percentiles = None;
+        args.flag_histogram = None;
+    }
+
+    if let Some(ref percentiles) = args.flag_percentiles {
+        let mut parsed: Vec<(String, f64)> = Vec::new();
+        for pct_str in percentiles.split(',').map(str::trim) {
+            match pct_str.parse::<f64>() {
+                Ok(pct) if (0.0..=100.0).contains(&pct) => {
+                    if parsed.iter().any(|(_, p)| (p - pct).abs() < f64::EPSILON) {
+                        return fail_incorrectusage_clierror!(
+                            "Duplicate percentile: {pct_str}. Percentiles can only be specified \
+                             once."
+                        );
+                    }
+                    parsed.push((format!("p{pct_str}"), pct));
+                },
+                _ => {
+                    return fail_incorrectusage_clierror!(
+                        "Invalid percentile: {pct_str}. Percentiles must be between 0 and 100."
+                    )
+                },
+            }
+        }
+        // safety: PERCENTILES is only set here
+        PERCENTILES.set(parsed).unwrap();
+    }
+    if args.flag_histogram == Some(0) {
+        return fail_incorrectusage_clierror!("--histogram must be greater than zero.");
     }
 
     // inferring boolean requires inferring cardinality
@@ -314,6 +408,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         flag_median:          args.flag_median,
         flag_mad:             args.flag_mad,
         flag_quartiles:       args.flag_quartiles,
+        flag_approx:          args.flag_approx,
+        flag_quality:         args.flag_quality,
+        flag_percentiles:     args.flag_percentiles.clone(),
+        flag_histogram:       args.flag_histogram,
         flag_round:           args.flag_round,
         flag_nulls:           args.flag_nulls,
         flag_infer_dates:     args.flag_infer_dates,
@@ -339,6 +437,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         // so cached stats are automatically invalidated
         // when the qsv version changes
         qsv_version:          env!("CARGO_PKG_VERSION").to_string(),
+        // flag the columns with approximate results
+        approx_columns:       args.approx_columns(),
     };
 
     // create a temporary file to store the <FILESTEM>.stats.csv file
@@ -358,10 +458,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         // read from stdin and write to a temp file
         log::info!("Reading from stdin");
         let mut stdin_file = NamedTempFile::new()?;
-        let stdin = std::io::stdin();
-        let mut stdin_handle = stdin.lock();
-        std::io::copy(&mut stdin_handle, &mut stdin_file)?;
-        drop(stdin_handle);
+        // io_reader transparently decompresses compressed stdin
+        let mut stdin_rdr = fconfig.io_reader()?;
+        std::io::copy(&mut stdin_rdr, &mut stdin_file)?;
+        drop(stdin_rdr);
         let (_file, tempfile_path) = stdin_file
             .keep()
             .or(Err("Cannot keep temporary file".to_string()))?;
@@ -457,6 +557,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         && existing_stats_args_json.flag_delimiter
                             == current_stats_args.flag_delimiter
                         && existing_stats_args_json.flag_nulls == current_stats_args.flag_nulls
+                        && existing_stats_args_json.flag_approx == current_stats_args.flag_approx
+                        && existing_stats_args_json.flag_quality == current_stats_args.flag_quality
+                        && existing_stats_args_json.flag_percentiles
+                            == current_stats_args.flag_percentiles
+                        && existing_stats_args_json.flag_histogram
+                            == current_stats_args.flag_histogram
                         && existing_stats_args_json.qsv_version == current_stats_args.qsv_version)
             {
                 log::info!(
@@ -476,12 +582,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             let start_time = std::time::Instant::now();
 
             // we're loading the entire file into memory, we need to check avail mem
+            // unless we're approximating the non-streaming stats
             if args.flag_everything
                 || args.flag_mode
-                || args.flag_cardinality
-                || args.flag_median
-                || args.flag_quartiles
-                || args.flag_mad
+                || !args.flag_approx
+                    && (args.flag_cardinality
+                        || args.flag_median
+                        || args.flag_quartiles
+                        || args.flag_mad)
             {
                 util::mem_file_check(&path, false, args.flag_memcheck)?;
             }
@@ -497,7 +605,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             let record_count = RECORD_COUNT.get_or_init(|| util::count_rows(&fconfig).unwrap());
             log::info!("scanning {record_count} records...");
 
-            let (headers, stats) = match fconfig.indexed()? {
+            let (headers, mut stats) = match fconfig.indexed()? {
                 None => args.sequential_stats(&args.flag_dates_whitelist),
                 Some(idx) => {
                     let idx_count = idx.count();
@@ -513,6 +621,29 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 },
             }?;
 
+            if let Some(bins) = args.flag_histogram {
+                let histogram_path =
+                    stats_path(&path, fconfig.is_stdin())?.with_extension("histogram.json");
+                let histograms: Vec<serde_json::Value> = headers
+                    .iter()
+                    .zip(stats.iter_mut())
+                    .enumerate()
+                    .filter_map(|(i, (header, stat))| {
+                        let field = if args.flag_no_headers {
+                            i.to_string()
+                        } else {
+                            String::from_utf8_lossy(header).to_string()
+                        };
+                        stat.histogram(&field, bins, args.flag_round)
+                    })
+                    .collect();
+                fs::write(
+                    histogram_path,
+                    // safety: we know that the histograms are JSON serializable
+                    serde_json::to_string_pretty(&histograms).unwrap(),
+                )?;
+            }
+
             // clone a copy of stats so we can binary encode it to disk later
             if write_stats_binout {
                 stats_for_encoding.clone_from(&stats);
@@ -718,11 +849,18 @@ impl Args {
                     .expect("File seek failed.");
                 let it = idx.byte_records().take(chunk_size);
                 // safety: this will only return an Error if the channel has been disconnected
-                send.send(args.compute(&sel, it)).unwrap();
+                send.send((i, args.compute(&sel, it))).unwrap();
             });
         }
         drop(send);
-        Ok((headers, merge_all(recv.iter()).unwrap_or_default()))
+
+        // merge the chunks in file order, as the --quality sort order depends on it
+        let mut chunks: Vec<(usize, Vec<Stats>)> = recv.iter().collect();
+        chunks.sort_unstable_by_key(|(i, _)| *i);
+        Ok((
+            headers,
+            merge_all(chunks.into_iter().map(|(_, stats)| stats)).unwrap_or_default(),
+        ))
     }
 
     fn stats_to_records(&self, stats: Vec<Stats>) -> Vec<csv::StringRecord> {
@@ -804,7 +942,7 @@ impl Args {
     }
 
     #[inline]
-    fn new_stats(&self, record_len: usize) -> Vec<Stats> {
+    pub fn new_stats(&self, record_len: usize) -> Vec<Stats> {
         let mut stats: Vec<Stats> = Vec::with_capacity(record_len);
         stats.extend(
             repeat(Stats::new(WhichStats {
@@ -818,6 +956,10 @@ impl Args {
                 quartiles:     self.flag_everything || self.flag_quartiles,
                 mode:          self.flag_everything || self.flag_mode,
                 typesonly:     self.flag_typesonly,
+                percentiles:   self.flag_percentiles.is_some(),
+                histogram:     self.flag_histogram.is_some(),
+                approx:        self.flag_approx,
+                quality:       self.flag_quality,
             }))
             .take(record_len),
         );
@@ -880,8 +1022,43 @@ impl Args {
                 "antimode_occurrences",
             ]);
         }
+        if self.flag_quality {
+            fields.extend_from_slice(&[
+                "outside_inner_fences",
+                "outside_outer_fences",
+                "whitespace_padded",
+                "mixed_case",
+                "sort_order",
+                "top_patterns",
+            ]);
+        }
+        if self.flag_percentiles.is_some() {
+            if let Some(percentiles) = PERCENTILES.get() {
+                fields.extend(percentiles.iter().map(|(name, _)| name.as_str()));
+            }
+        }
         csv::StringRecord::from(fields)
     }
+
+    /// the stats columns with approximate results, i.e. the sketched cardinality, median, MAD
+    /// & quartiles with --approx, and the percentiles when they're computed with a t-digest
+    fn approx_columns(&self) -> Vec<String> {
+        let all = self.flag_everything;
+        let values_loaded =
+            !self.flag_approx && (all || self.flag_median || self.flag_quartiles || self.flag_mad);
+        let percentile_names = PERCENTILES.get().map_or(&[][..], Vec::as_slice);
+        self.stat_headers()
+            .iter()
+            .filter(|header| match *header {
+                "cardinality" | "median" | "mad" | "lower_outer_fence" | "lower_inner_fence"
+                | "q1" | "q2_median" | "q3" | "iqr" | "upper_inner_fence" | "upper_outer_fence"
+                | "skewness" => self.flag_approx,
+                "outside_inner_fences" | "outside_outer_fences" => !values_loaded,
+                _ => !values_loaded && percentile_names.iter().any(|(name, _)| name == header),
+            })
+            .map(str::to_string)
+            .collect()
+    }
 }
 
 /// returns the path to the stats file
@@ -962,6 +1139,10 @@ struct WhichStats {
     quartiles:     bool,
     mode:          bool,
     typesonly:     bool,
+    percentiles:   bool,
+    histogram:     bool,
+    approx:        bool,
+    quality:       bool,
 }
 
 impl Commute for WhichStats {
@@ -983,6 +1164,9 @@ pub struct Stats {
     median:        Option<Unsorted<f64>>,
     mad:           Option<Unsorted<f64>>,
     quartiles:     Option<Unsorted<f64>>,
+    distribution:  Option<Distribution>,
+    hll:           Option<HyperLogLog>,
+    quality:       Option<Quality>,
     which:         WhichStats,
 }
 
@@ -1013,17 +1197,45 @@ impl Stats {
         if which.dist {
             online = Some(stats::OnlineStats::default());
         }
-        if which.mode || which.cardinality {
-            modes = Some(stats::Unsorted::default());
-        }
-        if which.quartiles {
-            quartiles = Some(stats::Unsorted::default());
-        } else if which.median {
-            median = Some(stats::Unsorted::default());
-        }
-        if which.mad {
-            mad = Some(stats::Unsorted::default());
+        let mut hll = None;
+        if which.approx {
+            // the cardinality, median, MAD & quartiles are sketched in constant memory
+            if which.mode {
+                modes = Some(stats::Unsorted::default());
+            }
+            if which.cardinality {
+                hll = Some(HyperLogLog::default());
+            }
+        } else {
+            if which.mode || which.cardinality {
+                modes = Some(stats::Unsorted::default());
+            }
+            if which.quartiles {
+                quartiles = Some(stats::Unsorted::default());
+            } else if which.median {
+                median = Some(stats::Unsorted::default());
+            }
+            if which.mad {
+                mad = Some(stats::Unsorted::default());
+            }
         }
+        // percentiles & histograms are exact if we're already loading the values
+        // in memory, otherwise, they're approximated using a t-digest
+        let values_loaded = which.median || which.quartiles || which.mad;
+        let needs_distribution = which.percentiles
+            || which.histogram
+            || which.quality
+            || (which.approx && values_loaded);
+        let distribution = if !needs_distribution {
+            None
+        } else if values_loaded && !which.approx {
+            // the values are only kept once, in the distribution. The Unsorted for
+            // the median, MAD & quartiles are loaded from it when the stats are output
+            (median, mad, quartiles) = (None, None, None);
+            Some(Distribution::Exact(Vec::new()))
+        } else {
+            Some(Distribution::Approx(TDigest::default()))
+        };
         Stats {
             typ: FieldType::default(),
             sum,
@@ -1035,12 +1247,19 @@ impl Stats {
             median,
             mad,
             quartiles,
+            distribution,
+            hll,
+            quality: if which.quality {
+                Some(Quality::default())
+            } else {
+                None
+            },
             which,
         }
     }
 
     #[inline]
-    fn add(&mut self, sample: &[u8], infer_dates: bool, infer_boolean: bool, prefer_dmy: bool) {
+    pub fn add(&mut self, sample: &[u8], infer_dates: bool, infer_boolean: bool, prefer_dmy: bool) {
         let (sample_type, timestamp_val) =
             FieldType::from_sample(infer_dates, prefer_dmy, sample, self.typ);
         self.typ.merge(sample_type);
@@ -1067,8 +1286,13 @@ impl Stats {
         if let Some(v) = self.modes.as_mut() {
             v.add(sample.to_vec());
         };
+        if let Some(v) = self.hll.as_mut() {
+            v.add(sample);
+        };
         if sample_type == TNull {
             self.nullcount += 1;
+        } else if let Some(v) = self.quality.as_mut() {
+            v.add(sample);
         }
         match t {
             TNull => {
@@ -1098,6 +1322,12 @@ impl Stats {
                     if let Some(v) = self.quartiles.as_mut() {
                         v.add(n);
                     }
+                    if let Some(v) = self.distribution.as_mut() {
+                        v.add(n);
+                    }
+                    if let Some(v) = self.quality.as_mut() {
+                        v.numeric_order.add(n);
+                    }
                     if let Some(v) = self.online.as_mut() {
                         v.add(&n);
                     }
@@ -1140,6 +1370,12 @@ impl Stats {
                     if let Some(v) = self.quartiles.as_mut() {
                         v.add(n);
                     }
+                    if let Some(v) = self.distribution.as_mut() {
+                        v.add(n);
+                    }
+                    if let Some(v) = self.quality.as_mut() {
+                        v.numeric_order.add(n);
+                    }
                     if let Some(v) = self.online.as_mut() {
                         v.add(&n);
                     }
@@ -1148,8 +1384,53 @@ impl Stats {
         }
     }
 
+    /// with an exact distribution, the numeric values are only kept in the distribution.
+    /// Load them in the Unsorted used to compute the median, MAD & quartiles.
+    fn load_exact_values(&mut self) {
+        let Some(Distribution::Exact(values)) = self.distribution.as_ref() else {
+            return;
+        };
+        let unsorted = || Some(values.iter().copied().collect::<Unsorted<f64>>());
+        if self.which.quartiles {
+            self.quartiles = unsorted();
+        } else if self.which.median {
+            self.median = unsorted();
+        }
+        if self.which.mad {
+            self.mad = unsorted();
+        }
+    }
+
+    /// free the Unsorted loaded by load_exact_values, once they've been used
+    fn unload_exact_values(&mut self) {
+        if self
+            .distribution
+            .as_ref()
+            .is_some_and(Distribution::is_exact)
+        {
+            (self.median, self.mad, self.quartiles) = (None, None, None);
+        }
+    }
+
     #[allow(clippy::wrong_self_convention)]
     pub fn to_record(&mut self, round_places: u32, infer_boolean: bool) -> csv::StringRecord {
+        // stats is also called by the `schema` and `tojsonl` commands to infer a schema,
+        // sparsity is not required by those cmds and we don't necessarily have the
+        // record_count when called by those cmds, so just set sparsity to nullcount
+        // (i.e. divide by 1) so we don't panic.
+        let record_count = *RECORD_COUNT.get().unwrap_or(&1);
+        self.to_record_with_count(round_places, infer_boolean, record_count)
+    }
+
+    /// same as to_record, but computes sparsity using the given record_count.
+    /// Used by the `groupby` command, where each group has its own record count.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_record_with_count(
+        &mut self,
+        round_places: u32,
+        infer_boolean: bool,
+        record_count: u64,
+    ) -> csv::StringRecord {
         // we're doing typesonly and not inferring boolean, just return the type
         if self.which.typesonly && !infer_boolean {
             return csv::StringRecord::from(vec![self.typ.to_string()]);
@@ -1167,9 +1448,15 @@ impl Stats {
         // should that be enabled
         let mut cardinality = 0_usize;
         let mut mc_pieces = Vec::with_capacity(7);
+        // with --approx, the cardinality is estimated with HyperLogLog
+        let approx_cardinality = self.hll.as_ref().map(HyperLogLog::cardinality);
         match self.modes.as_mut() {
             None => {
-                if self.which.cardinality {
+                if let Some(approx_cardinality) = approx_cardinality {
+                    cardinality = approx_cardinality;
+                    let mut buffer = itoa::Buffer::new();
+                    mc_pieces.push(buffer.format(cardinality).to_owned());
+                } else if self.which.cardinality {
                     mc_pieces.push(empty());
                 }
                 if self.which.mode {
@@ -1178,7 +1465,7 @@ impl Stats {
             },
             Some(ref mut v) => {
                 if self.which.cardinality {
-                    cardinality = v.cardinality();
+                    cardinality = approx_cardinality.unwrap_or_else(|| v.cardinality());
                     let mut buffer = itoa::Buffer::new();
                     mc_pieces.push(buffer.format(cardinality).to_owned());
                 }
@@ -1349,24 +1636,41 @@ impl Stats {
         }
 
         // sparsity
-        // stats is also called by the `schema` and `tojsonl` commands to infer a schema,
-        // sparsity is not required by those cmds and we don't necessarily have the
-        // record_count when called by those cmds, so just set sparsity to nullcount
-        // (i.e. divide by 1) so we don't panic.
         #[allow(clippy::cast_precision_loss)]
-        let sparsity: f64 = self.nullcount as f64 / *RECORD_COUNT.get().unwrap_or(&1) as f64;
+        let sparsity: f64 = self.nullcount as f64 / record_count as f64;
         pieces.push(util::round_num(sparsity, round_places));
 
+        self.load_exact_values();
+
+        // with --approx, the median, MAD & quartiles are approximated with a t-digest
+        let sketch = if self.which.approx && matches!(typ, TInteger | TFloat | TDate | TDateTime) {
+            self.distribution.as_mut().map(|v| {
+                v.prepare();
+                &*v
+            })
+        } else {
+            None
+        };
+
         // median
         let mut existing_median = None;
-        if let Some(v) = self.median.as_mut().and_then(|v| {
-            if let TNull | TString = typ {
-                None
-            } else {
-                existing_median = v.median();
-                existing_median
-            }
-        }) {
+        if let Some(v) = self
+            .median
+            .as_mut()
+            .and_then(|v| {
+                if let TNull | TString = typ {
+                    None
+                } else {
+                    existing_median = v.median();
+                    existing_median
+                }
+            })
+            .or_else(|| {
+                sketch
+                    .filter(|_| self.which.median)
+                    .and_then(|v| v.percentile(50.0))
+            })
+        {
             if typ == TDateTime || typ == TDate {
                 pieces.push(timestamp_ms_to_rfc3339(v as i64, typ));
             } else {
@@ -1377,13 +1681,22 @@ impl Stats {
         }
 
         // median absolute deviation (MAD)
-        if let Some(v) = self.mad.as_mut().and_then(|v| {
-            if let TNull | TString = typ {
-                None
-            } else {
-                v.mad(existing_median)
-            }
-        }) {
+        if let Some(v) = self
+            .mad
+            .as_mut()
+            .and_then(|v| {
+                if let TNull | TString = typ {
+                    None
+                } else {
+                    v.mad(existing_median)
+                }
+            })
+            .or_else(|| {
+                sketch
+                    .filter(|_| self.which.mad)
+                    .and_then(Distribution::mad)
+            })
+        {
             if typ == TDateTime || typ == TDate {
                 // like stddev, return MAD in days
                 pieces.push(util::round_num(
@@ -1398,10 +1711,23 @@ impl Stats {
         }
 
         // quartiles
-        match self.quartiles.as_mut().and_then(|v| match typ {
-            TInteger | TFloat | TDate | TDateTime => v.quartiles(),
-            _ => None,
-        }) {
+        let mut fences = None;
+        match self
+            .quartiles
+            .as_mut()
+            .and_then(|v| match typ {
+                TInteger | TFloat | TDate | TDateTime => v.quartiles(),
+                _ => None,
+            })
+            .or_else(|| {
+                sketch.filter(|_| self.which.quartiles).and_then(|v| {
+                    Some((
+                        v.percentile(25.0)?,
+                        v.percentile(50.0)?,
+                        v.percentile(75.0)?,
+                    ))
+                })
+            }) {
             None => {
                 if self.which.quartiles {
                     pieces.extend_from_slice(&[
@@ -1434,6 +1760,7 @@ impl Stats {
                 let uif = 1.5_f64.mul_add(iqr, q3);
                 // upper_outer_fence = "q3 + (3.0 * iqr)"
                 let uof = 3.0_f64.mul_add(iqr, q3);
+                fences = Some((lof, lif, uif, uof));
 
                 // calculate skewness using Quantile-based measures
                 // https://en.wikipedia.org/wiki/Skewness#Quantile-based_measures
@@ -1477,13 +1804,134 @@ impl Stats {
                 pieces.push(util::round_num(skewness, round_places));
             },
         }
+        self.unload_exact_values();
 
         // mode/modes/antimodes & cardinality
         // append it here to preserve legacy ordering of columns
         pieces.extend_from_slice(&mc_pieces);
 
+        // data quality
+        if let Some(quality) = self.quality.as_ref() {
+            let numeric = matches!(typ, TInteger | TFloat | TDate | TDateTime);
+            let distribution = self.distribution.as_mut().filter(|_| numeric);
+            let mut outside_fences = (empty(), empty());
+            if let Some(v) = distribution {
+                v.prepare();
+                // use the fences computed by --quartiles if available
+                if let Some((lof, lif, uif, uof)) = fences.or_else(|| {
+                    let (q1, q3) = (v.percentile(25.0)?, v.percentile(75.0)?);
+                    let iqr = q3 - q1;
+                    Some((
+                        3.0f64.mul_add(-iqr, q1),
+                        1.5f64.mul_add(-iqr, q1),
+                        1.5_f64.mul_add(iqr, q3),
+                        3.0_f64.mul_add(iqr, q3),
+                    ))
+                }) {
+                    outside_fences = (
+                        v.count_outside(lif, uif).to_string(),
+                        v.count_outside(lof, uof).to_string(),
+                    );
+                }
+            }
+            let sort_order = if numeric {
+                quality.numeric_order.to_string()
+            } else {
+                quality.lexical_order.to_string()
+            };
+            pieces.extend_from_slice(&[
+                outside_fences.0,
+                outside_fences.1,
+                quality.whitespace_padded.to_string(),
+                quality.mixed_case.to_string(),
+                sort_order,
+                quality.top_patterns(3),
+            ]);
+        }
+
+        // percentiles
+        if self.which.percentiles {
+            let percentiles = PERCENTILES.get().map_or(&[][..], Vec::as_slice);
+            match self
+                .distribution
+                .as_mut()
+                .filter(|_| matches!(typ, TInteger | TFloat | TDate | TDateTime))
+            {
+                None => pieces.extend(repeat(empty()).take(percentiles.len())),
+                Some(v) => {
+                    v.prepare();
+                    for (_, pct) in percentiles {
+                        pieces.push(match v.percentile(*pct) {
+                            None => empty(),
+                            Some(p) if typ == TDateTime || typ == TDate => {
+                                timestamp_ms_to_rfc3339(p as i64, typ)
+                            },
+                            Some(p) => util::round_num(p, round_places),
+                        });
+                    }
+                },
+            }
+        }
+
         csv::StringRecord::from(pieces)
     }
+
+    /// the histogram of a numeric/date column as a JSON object, with `bins` equal-width
+    /// bins between its min & max values. Returns None for other column types.
+    pub fn histogram(
+        &mut self,
+        field: &str,
+        bins: usize,
+        round_places: u32,
+    ) -> Option<serde_json::Value> {
+        let typ = self.typ;
+        if !matches!(typ, TInteger | TFloat | TDate | TDateTime) {
+            return None;
+        }
+        let distribution = self.distribution.as_mut()?;
+        distribution.prepare();
+        let (min, max, counts) = distribution.histogram(bins)?;
+
+        #[allow(clippy::cast_precision_loss)]
+        let width = (max - min) / counts.len() as f64;
+        let is_date = typ == TDate || typ == TDateTime;
+        let fmt_value = |v: f64| -> serde_json::Value {
+            if is_date {
+                serde_json::Value::String(timestamp_ms_to_rfc3339(v as i64, typ))
+            } else {
+                serde_json::from_str(&util::round_num(v, round_places))
+                    .unwrap_or(serde_json::Value::Null)
+            }
+        };
+        let bins_json: Vec<serde_json::Value> = counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                #[allow(clippy::cast_precision_loss)]
+                let lower = width.mul_add(i as f64, min);
+                let upper = if i == counts.len() - 1 {
+                    max
+                } else {
+                    #[allow(clippy::cast_precision_loss)]
+                    width.mul_add((i + 1) as f64, min)
+                };
+                serde_json::json!({
+                    "lower": fmt_value(lower),
+                    "upper": fmt_value(upper),
+                    "count": count,
+                })
+            })
+            .collect();
+
+        Some(serde_json::json!({
+            "field": field,
+            "type": typ.to_string(),
+            "exact": distribution.is_exact(),
+            "min": fmt_value(min),
+            "max": fmt_value(max),
+            "bins": bins_json,
+        }))
+    }
 }
 
 impl Commute for Stats {
@@ -1498,6 +1946,9 @@ impl Commute for Stats {
         self.modes.merge(other.modes);
         self.median.merge(other.median);
         self.quartiles.merge(other.quartiles);
+        self.distribution.merge(other.distribution);
+        self.hll.merge(other.hll);
+        self.quality.merge(other.quality);
         self.which.merge(other.which);
     }
 }
@@ -1855,6 +2306,573 @@ impl Commute for TypedMinMax {
     }
 }
 
+/// `Distribution` keeps track of the values of a numeric/date column to compute
+/// its percentiles & histogram.
+/// The values are kept in memory when they're already needed to compute the
+/// median/quartiles/MAD, so the results are exact. The Stats then don't keep another
+/// copy of them in their Unsorted. Otherwise, they're approximated in constant memory
+/// using a t-digest.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+enum Distribution {
+    Exact(Vec<f64>),
+    Approx(TDigest),
+}
+
+impl Distribution {
+    #[inline]
+    fn add(&mut self, n: f64) {
+        match self {
+            Distribution::Exact(values) => values.push(n),
+            Distribution::Approx(digest) => digest.add(n),
+        }
+    }
+
+    const fn is_exact(&self) -> bool {
+        matches!(self, Distribution::Exact(_))
+    }
+
+    /// sort the exact values & compress the t-digest, so we can query them
+    fn prepare(&mut self) {
+        match self {
+            Distribution::Exact(values) => values.sort_unstable_by(f64::total_cmp),
+            Distribution::Approx(digest) => digest.compress(),
+        }
+    }
+
+    /// the percentile (0-100) of the values, using linear interpolation between
+    /// the closest ranks for exact values. prepare() must be called first.
+    fn percentile(&self, pct: f64) -> Option<f64> {
+        let q = (pct / 100.0).clamp(0.0, 1.0);
+        match self {
+            Distribution::Exact(values) => {
+                if values.is_empty() {
+                    return None;
+                }
+                #[allow(clippy::cast_precision_loss)]
+                let rank = q * (values.len() - 1) as f64;
+                let lower = rank.floor() as usize;
+                let upper = rank.ceil() as usize;
+                let frac = rank - rank.floor();
+                Some((values[upper] - values[lower]).mul_add(frac, values[lower]))
+            },
+            Distribution::Approx(digest) => digest.quantile(q),
+        }
+    }
+
+    /// the median absolute deviation of the values. prepare() must be called first.
+    fn mad(&self) -> Option<f64> {
+        let median = self.percentile(50.0)?;
+        match self {
+            Distribution::Exact(values) => {
+                let mut deviations: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
+                deviations.sort_unstable_by(f64::total_cmp);
+                Distribution::Exact(deviations).percentile(50.0)
+            },
+            Distribution::Approx(digest) => digest.mad(median),
+        }
+    }
+
+    /// the number of values less than lower or greater than upper.
+    /// prepare() must be called first.
+    fn count_outside(&self, lower: f64, upper: f64) -> u64 {
+        match self {
+            Distribution::Exact(values) => {
+                values.iter().filter(|&&v| v < lower || v > upper).count() as u64
+            },
+            Distribution::Approx(digest) => {
+                #[allow(clippy::cast_precision_loss)]
+                let count = digest.count() as f64;
+                (count * (digest.cdf(lower) + 1.0 - digest.cdf(upper))).round() as u64
+            },
+        }
+    }
+
+    /// the min, max & the counts of the values in `bins` equal-width bins between them.
+    /// prepare() must be called first.
+    fn histogram(&self, bins: usize) -> Option<(f64, f64, Vec<u64>)> {
+        let (min, max) = match self {
+            Distribution::Exact(values) => (*values.first()?, *values.last()?),
+            Distribution::Approx(digest) => (digest.min()?, digest.max()?),
+        };
+        let bins = bins.max(1);
+        #[allow(clippy::cast_precision_loss)]
+        let width = (max - min) / bins as f64;
+        let mut counts = vec![0_u64; bins];
+        match self {
+            Distribution::Exact(values) => {
+                for value in values {
+                    let bin = if width > 0.0 {
+                        (((value - min) / width) as usize).min(bins - 1)
+                    } else {
+                        0
+                    };
+                    counts[bin] += 1;
+                }
+            },
+            Distribution::Approx(digest) => {
+                #[allow(clippy::cast_precision_loss)]
+                let total = digest.count() as f64;
+                let mut prev_cdf = 0.0;
+                for (i, count) in counts.iter_mut().enumerate() {
+                    #[allow(clippy::cast_precision_loss)]
+                    let upper_cdf = if i == bins - 1 {
+                        1.0
+                    } else {
+                        digest.cdf(width.mul_add((i + 1) as f64, min))
+                    };
+                    *count = (total * (upper_cdf - prev_cdf)).round() as u64;
+                    prev_cdf = upper_cdf;
+                }
+            },
+        }
+        Some((min, max, counts))
+    }
+}
+
+impl Commute for Distribution {
+    #[inline]
+    fn merge(&mut self, other: Distribution) {
+        match (self, other) {
+            (Distribution::Exact(values), Distribution::Exact(other_values)) => {
+                values.extend(other_values);
+            },
+            (Distribution::Approx(digest), Distribution::Approx(other_digest)) => {
+                digest.merge(other_digest);
+            },
+            // all the stats of a column are created with the same WhichStats,
+            // so they're always of the same kind
+            _ => unreachable!("cannot merge exact and approximate distributions"),
+        }
+    }
+}
+
+// the t-digest compression parameter. Higher values are more accurate,
+// but use more memory. 100 keeps the digest under ~300 centroids.
+const TDIGEST_COMPRESSION: f64 = 100.0;
+// the number of values buffered before they're merged into the centroids
+const TDIGEST_BUFFER_SIZE: usize = 512;
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq)]
+struct Centroid {
+    mean:   f64,
+    weight: f64,
+}
+
+/// `TDigest` is a mergeable sketch to approximate quantiles in constant memory.
+/// This is a merging t-digest, using the k1 scale function
+/// (see https://arxiv.org/abs/1902.04023).
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+struct TDigest {
+    centroids: Vec<Centroid>,
+    buffer:    Vec<f64>,
+    count:     u64,
+    min:       Option<f64>,
+    max:       Option<f64>,
+}
+
+impl TDigest {
+    #[inline]
+    fn add(&mut self, n: f64) {
+        if n.is_nan() {
+            return;
+        }
+        self.count += 1;
+        self.min = Some(self.min.map_or(n, |min| min.min(n)));
+        self.max = Some(self.max.map_or(n, |max| max.max(n)));
+        self.buffer.push(n);
+        if self.buffer.len() >= TDIGEST_BUFFER_SIZE {
+            self.compress();
+        }
+    }
+
+    const fn count(&self) -> u64 {
+        self.count
+    }
+
+    const fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    const fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    /// merge the buffered values into the centroids
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut centroids = std::mem::take(&mut self.centroids);
+        centroids.extend(
+            self.buffer
+                .drain(..)
+                .map(|mean| Centroid { mean, weight: 1.0 }),
+        );
+        self.centroids = Self::merge_centroids(centroids);
+    }
+
+    fn merge_centroids(mut centroids: Vec<Centroid>) -> Vec<Centroid> {
+        centroids.sort_unstable_by(|a, b| a.mean.total_cmp(&b.mean));
+        let total: f64 = centroids.iter().map(|c| c.weight).sum();
+
+        // k1 scale function & its inverse, which limit the size of the centroids,
+        // so they're smaller at the tails where we need more accuracy
+        let k = |q: f64| {
+            TDIGEST_COMPRESSION / (2.0 * std::f64::consts::PI) * 2.0f64.mul_add(q, -1.0).asin()
+        };
+        let k_inverse = |k: f64| {
+            let angle = k * 2.0 * std::f64::consts::PI / TDIGEST_COMPRESSION;
+            if angle >= std::f64::consts::FRAC_PI_2 {
+                1.0
+            } else {
+                (angle.sin() + 1.0) / 2.0
+            }
+        };
+
+        let mut merged = Vec::with_capacity(TDIGEST_COMPRESSION as usize * 2);
+        let mut centroids = centroids.into_iter();
+        let Some(mut current) = centroids.next() else {
+            return merged;
+        };
+        let mut weight_so_far = 0.0;
+        let mut q_limit = k_inverse(k(0.0) + 1.0);
+        for next in centroids {
+            let q = (weight_so_far + current.weight + next.weight) / total;
+            if q <= q_limit {
+                // weighted mean of the two centroids
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_so_far += current.weight;
+                merged.push(current);
+                q_limit = k_inverse(k(weight_so_far / total) + 1.0);
+                current = next;
+            }
+        }
+        merged.push(current);
+        merged
+    }
+
+    fn merge(&mut self, other: TDigest) {
+        self.count += other.count;
+        self.min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let mut centroids = std::mem::take(&mut self.centroids);
+        centroids.extend(other.centroids);
+        centroids.extend(
+            self.buffer
+                .drain(..)
+                .chain(other.buffer)
+                .map(|mean| Centroid { mean, weight: 1.0 }),
+        );
+        self.centroids = Self::merge_centroids(centroids);
+    }
+
+    /// the approximate value at quantile q (0.0-1.0). compress() must be called first.
+    /// Interpolates between the centers of the centroids, and the min/max at the ends.
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let (min, max) = (self.min?, self.max?);
+        #[allow(clippy::cast_precision_loss)]
+        let total = self.count as f64;
+        let position = q * total;
+
+        let mut prev_center = 0.0;
+        let mut prev_mean = min;
+        let mut weight_so_far = 0.0;
+        for centroid in &self.centroids {
+            let center = weight_so_far + centroid.weight / 2.0;
+            if position < center {
+                let frac = (position - prev_center) / (center - prev_center);
+                return Some((centroid.mean - prev_mean).mul_add(frac, prev_mean));
+            }
+            prev_center = center;
+            prev_mean = centroid.mean;
+            weight_so_far += centroid.weight;
+        }
+        if total > prev_center {
+            let frac = (position - prev_center) / (total - prev_center);
+            Some((max - prev_mean).mul_add(frac, prev_mean))
+        } else {
+            Some(max)
+        }
+    }
+
+    /// the approximate median absolute deviation, from a t-digest of the absolute
+    /// deviations of the centroids from the median. compress() must be called first.
+    fn mad(&self, median: f64) -> Option<f64> {
+        let (min, max) = (self.min?, self.max?);
+        let deviations = self
+            .centroids
+            .iter()
+            .map(|c| Centroid {
+                mean:   (c.mean - median).abs(),
+                weight: c.weight,
+            })
+            .collect();
+        let centroids = Self::merge_centroids(deviations);
+        let deviations_digest = TDigest {
+            min: centroids.first().map(|c| c.mean),
+            max: Some((min - median).abs().max((max - median).abs())),
+            centroids,
+            buffer: Vec::new(),
+            count: self.count,
+        };
+        deviations_digest.quantile(0.5)
+    }
+
+    /// the approximate fraction of the values <= x. compress() must be called first.
+    fn cdf(&self, x: f64) -> f64 {
+        let (Some(min), Some(max)) = (self.min, self.max) else {
+            return 0.0;
+        };
+        if x < min {
+            return 0.0;
+        }
+        if x >= max {
+            return 1.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let total = self.count as f64;
+
+        let mut prev_center = 0.0;
+        let mut prev_mean = min;
+        let mut weight_so_far = 0.0;
+        for centroid in &self.centroids {
+            let center = weight_so_far + centroid.weight / 2.0;
+            if x < centroid.mean {
+                let frac = if centroid.mean > prev_mean {
+                    (x - prev_mean) / (centroid.mean - prev_mean)
+                } else {
+                    1.0
+                };
+                return frac.mul_add(center - prev_center, prev_center) / total;
+            }
+            prev_center = center;
+            prev_mean = centroid.mean;
+            weight_so_far += centroid.weight;
+        }
+        let frac = (x - prev_mean) / (max - prev_mean);
+        frac.mul_add(total - prev_center, prev_center) / total
+    }
+}
+
+// the max number of distinct character-class patterns tracked per column,
+// so free-form text columns don't use unbounded memory
+const MAX_PATTERNS: usize = 1_000;
+// longer patterns are truncated to this number of characters
+const MAX_PATTERN_LEN: usize = 30;
+
+/// `Quality` keeps track of the --quality statistics of a column
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+struct Quality {
+    whitespace_padded: u64,
+    mixed_case:        u64,
+    lexical_order:     SortOrder<Vec<u8>>,
+    numeric_order:     SortOrder<f64>,
+    patterns:          HashMap<String, u64>,
+    // the count of the values whose pattern was not tracked as we hit MAX_PATTERNS
+    other_patterns:    u64,
+}
+
+impl Quality {
+    /// add a non-null sample. Numbers & dates are added to numeric_order separately.
+    fn add(&mut self, sample: &[u8]) {
+        let value = String::from_utf8_lossy(sample);
+        if value.starts_with(char::is_whitespace) || value.ends_with(char::is_whitespace) {
+            self.whitespace_padded += 1;
+        }
+        if value.chars().any(char::is_uppercase) && value.chars().any(char::is_lowercase) {
+            self.mixed_case += 1;
+        }
+        self.lexical_order.add(sample.to_vec());
+
+        let mut pattern = String::with_capacity(MAX_PATTERN_LEN);
+        for c in value.chars().take(MAX_PATTERN_LEN) {
+            pattern.push(if c.is_uppercase() {
+                'A'
+            } else if c.is_lowercase() {
+                'a'
+            } else if c.is_numeric() {
+                '9'
+            } else if c.is_whitespace() {
+                ' '
+            } else {
+                c
+            });
+        }
+        self.add_pattern(pattern, 1);
+    }
+
+    fn add_pattern(&mut self, pattern: String, count: u64) {
+        if let Some(pattern_count) = self.patterns.get_mut(&pattern) {
+            *pattern_count += count;
+        } else if self.patterns.len() < MAX_PATTERNS {
+            self.patterns.insert(pattern, count);
+        } else {
+            self.other_patterns += count;
+        }
+    }
+
+    /// the n most common patterns with their counts, e.g. "AAA-999:120, AA-999:15"
+    fn top_patterns(&self, n: usize) -> String {
+        self.patterns
+            .iter()
+            // sort by descending count, then by pattern so the output is deterministic
+            .sorted_unstable_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)))
+            .take(n)
+            .map(|(pattern, count)| format!("{pattern}:{count}"))
+            .join(", ")
+    }
+}
+
+impl Commute for Quality {
+    #[inline]
+    fn merge(&mut self, other: Quality) {
+        self.whitespace_padded += other.whitespace_padded;
+        self.mixed_case += other.mixed_case;
+        self.lexical_order.merge(other.lexical_order);
+        self.numeric_order.merge(other.numeric_order);
+        self.other_patterns += other.other_patterns;
+        for (pattern, count) in other.patterns {
+            self.add_pattern(pattern, count);
+        }
+    }
+}
+
+/// `SortOrder` checks if the values of a column are sorted.
+/// Merging assumes that the other values come after this one's, so the chunks of
+/// parallel_stats need to be merged in file order.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+struct SortOrder<T> {
+    first:      Option<T>,
+    last:       Option<T>,
+    ascending:  bool,
+    descending: bool,
+}
+
+impl<T: PartialOrd + Clone> SortOrder<T> {
+    #[inline]
+    fn add(&mut self, value: T) {
+        if let Some(last) = self.last.as_ref() {
+            if value < *last {
+                self.ascending = false;
+            } else if value > *last {
+                self.descending = false;
+            }
+        } else {
+            self.first = Some(value.clone());
+            self.ascending = true;
+            self.descending = true;
+        }
+        self.last = Some(value);
+    }
+}
+
+impl<T: PartialOrd> Commute for SortOrder<T> {
+    #[inline]
+    fn merge(&mut self, other: SortOrder<T>) {
+        if self.first.is_none() {
+            *self = other;
+            return;
+        }
+        if let (Some(last), Some(other_first)) = (self.last.as_ref(), other.first.as_ref()) {
+            let (ascending, descending) = (last <= other_first, last >= other_first);
+            self.ascending = self.ascending && other.ascending && ascending;
+            self.descending = self.descending && other.descending && descending;
+            self.last = other.last;
+        }
+    }
+}
+
+impl<T> fmt::Display for SortOrder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.first.is_none() {
+            return Ok(());
+        }
+        match (self.ascending, self.descending) {
+            (true, true) => write!(f, "Constant"),
+            (true, false) => write!(f, "Ascending"),
+            (false, true) => write!(f, "Descending"),
+            (false, false) => write!(f, "Unsorted"),
+        }
+    }
+}
+
+// the number of bits of the hash used to select a HyperLogLog register.
+// 2^14 registers give a standard error of ~0.8%, using 16KB per column.
+const HLL_PRECISION: u32 = 14;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+// the hasher has fixed seeds, so the sketches of the parallel chunks can be merged
+static HLL_HASHER: OnceLock<ahash::RandomState> = OnceLock::new();
+
+/// `HyperLogLog` estimates the cardinality of a column in constant memory.
+/// See https://en.wikipedia.org/wiki/HyperLogLog
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        HyperLogLog {
+            registers: vec![0; HLL_REGISTERS],
+        }
+    }
+}
+
+impl HyperLogLog {
+    #[inline]
+    fn add(&mut self, sample: &[u8]) {
+        let hash = HLL_HASHER
+            .get_or_init(|| ahash::RandomState::with_seeds(1, 2, 3, 4))
+            .hash_one(sample);
+        // the first HLL_PRECISION bits select the register, and the register keeps
+        // the max position of the leftmost 1-bit of the remaining bits
+        let idx = (hash >> (64 - HLL_PRECISION)) as usize;
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() + 1;
+        self.registers[idx] = self.registers[idx].max(rank as u8);
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn cardinality(&self) -> usize {
+        let m = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let (sum, zeros) = self
+            .registers
+            .iter()
+            .fold((0.0, 0_usize), |(sum, zeros), &register| {
+                (
+                    sum + 2.0f64.powi(-i32::from(register)),
+                    zeros + usize::from(register == 0),
+                )
+            });
+        let estimate = alpha * m * m / sum;
+        if estimate <= 2.5 * m && zeros > 0 {
+            // use linear counting for small cardinalities, which is more accurate
+            (m * (m / zeros as f64).ln()).round() as usize
+        } else {
+            estimate.round() as usize
+        }
+    }
+}
+
+impl Commute for HyperLogLog {
+    #[inline]
+    fn merge(&mut self, other: HyperLogLog) {
+        for (register, other_register) in self.registers.iter_mut().zip(other.registers) {
+            *register = (*register).max(other_register);
+        }
+    }
+}
+
 #[allow(clippy::inline_always)]
 #[inline(always)]
 fn from_bytes<T: std::str::FromStr>(bytes: &[u8]) -> Option<T> {
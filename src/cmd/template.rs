@@ -0,0 +1,274 @@
+static USAGE: &str = r#"
+Renders CSV records through a MiniJinja template (https://docs.rs/minijinja), a
+Jinja2-like template language with variables, filters, conditionals and loops.
+Useful for generating letters, SQL inserts, config files and the like from CSV.
+
+By default, the template is rendered once per record. Each column is available
+as a variable of the same name (e.g. {{ name }}), and all the columns are also
+available through the `row` map, which also works for column names that aren't
+valid identifiers (e.g. {{ row["first name"] }}). The 1-based record number is
+available as `row_number`. Note that all values are strings, so use the int or
+float filters for arithmetic and numeric comparisons
+(e.g. {% if amount|float > 100 %}).
+
+With --whole-file, the template is rendered only once, with the `headers` list
+and the `rows` list of records, so it can loop over them
+(e.g. {% for r in rows %}{{ r.name }}{% endfor %}). This requires loading all
+records into memory.
+
+Besides MiniJinja's builtin filters, the following filters are available:
+    format_number(places, sep)  Format a number with <places> decimal places,
+                                grouping thousands with <sep> if given.
+                                e.g. {{ amount|format_number(2, ",") }}
+    format_date(fmt)            Parse a date in any format qsv recognizes and
+                                format it with a strftime format string.
+                                e.g. {{ due|format_date("%B %d, %Y") }}
+                                Set QSV_PREFER_DMY to parse dates as dd/mm/yyyy.
+    sql_quote                   Quote a value as a SQL string literal, escaping
+                                single quotes. Empty values become NULL.
+                                e.g. INSERT INTO t VALUES ({{ name|sql_quote }});
+
+Each rendering is followed by a newline.
+
+Examples:
+    qsv template --template "Dear {{ name }}, you owe ${{ amount|format_number(2) }}." data.csv
+
+    qsv template --template-file insert.sql.j2 data.csv -o inserts.sql
+
+    qsv template --template-file letter.j2 --outdir letters --filename letter_{}.txt data.csv
+    # creates letter_1.txt, letter_2.txt, etc. in the 'letters' directory
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_template.rs.
+
+Usage:
+    qsv template [options] (--template <str> | --template-file <file>) [<input>]
+    qsv template --help
+
+template options:
+    --template <str>         The template to render.
+    --template-file <file>   The file with the template to render.
+    --whole-file             Render the template once for all the records,
+                             instead of once per record.
+    --outdir <dir>           Write each record's rendering to its own file in
+                             <dir>, which is created if it doesn't exist.
+                             Cannot be used with --whole-file.
+    --filename <filename>    The filename template to use with --outdir, where
+                             "{}" is replaced with the 1-based record number.
+                             [default: {}.txt]
+
+Common options:
+    -h, --help               Display this message
+    -o, --output <file>      Write output to <file> instead of stdout.
+    -d, --delimiter <arg>    The field delimiter for reading CSV data.
+                             Must be a single character. (default: ,)
+"#;
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use minijinja::{context, Environment, Error, ErrorKind, Value};
+use qsv_dateparser::parse_with_preference;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter},
+    util,
+    util::FilenameTemplate,
+    CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:          Option<String>,
+    flag_template:      Option<String>,
+    flag_template_file: Option<String>,
+    flag_whole_file:    bool,
+    flag_outdir:        Option<String>,
+    flag_filename:      FilenameTemplate,
+    flag_output:        Option<String>,
+    flag_delimiter:     Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_whole_file && args.flag_outdir.is_some() {
+        return fail_incorrectusage_clierror!("--outdir cannot be used with --whole-file.");
+    }
+
+    let source = match (args.flag_template, args.flag_template_file) {
+        (Some(template), None) => template,
+        (None, Some(template_file)) => fs::read_to_string(template_file)?,
+        _ => {
+            return fail_incorrectusage_clierror!(
+                "Either --template or --template-file must be given."
+            )
+        },
+    };
+
+    let mut env = Environment::new();
+    let prefer_dmy = util::get_envvar_flag("QSV_PREFER_DMY");
+    env.add_filter("format_number", format_number);
+    env.add_filter("format_date", move |value: Value, fmt: String| {
+        format_date(&value, &fmt, prefer_dmy)
+    });
+    env.add_filter("sql_quote", sql_quote);
+    let template = match env.template_from_str(&source) {
+        Ok(template) => template,
+        Err(e) => return fail_incorrectusage_clierror!("Invalid template: {e}"),
+    };
+
+    let rconfig = Config::new(&args.arg_input).delimiter(args.flag_delimiter);
+    let mut rdr = rconfig.reader()?;
+    let headers: Vec<String> = rdr.headers()?.iter().map(String::from).collect();
+
+    if args.flag_whole_file {
+        let mut rows = Vec::new();
+        for record in rdr.records() {
+            rows.push(row_map(&headers, &record?));
+        }
+        let rendered = match template.render(context! { headers, rows }) {
+            Ok(rendered) => rendered,
+            Err(e) => return fail_clierror!("Cannot render template: {e}"),
+        };
+        let mut wtr = io::BufWriter::new(Config::new(&args.flag_output).io_writer()?);
+        writeln!(wtr, "{rendered}")?;
+        return Ok(wtr.flush()?);
+    }
+
+    let mut wtr = match args.flag_outdir {
+        Some(ref outdir) => {
+            fs::create_dir_all(outdir)?;
+            None
+        },
+        None => Some(io::BufWriter::new(
+            Config::new(&args.flag_output).io_writer()?,
+        )),
+    };
+
+    let mut record = csv::StringRecord::new();
+    let mut row_number = 0_usize;
+    while rdr.read_record(&mut record)? {
+        row_number += 1;
+        let mut ctx: HashMap<&str, Value> = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(header, field)| (header.as_str(), Value::from(field)))
+            .collect();
+        ctx.insert("row", Value::from_serializable(&row_map(&headers, &record)));
+        ctx.insert("row_number", Value::from(row_number));
+
+        let rendered = match template.render(&ctx) {
+            Ok(rendered) => rendered,
+            Err(e) => return fail_clierror!("Cannot render record {row_number}: {e}"),
+        };
+        if let Some(ref mut wtr) = wtr {
+            writeln!(wtr, "{rendered}")?;
+        } else if let Some(ref outdir) = args.flag_outdir {
+            let filename = args.flag_filename.filename(&row_number.to_string());
+            let mut file = io::BufWriter::new(fs::File::create(Path::new(outdir).join(filename))?);
+            writeln!(file, "{rendered}")?;
+            file.flush()?;
+        }
+    }
+    if let Some(mut wtr) = wtr {
+        wtr.flush()?;
+    }
+    Ok(())
+}
+
+/// The columns of a record, keyed by header, in column order.
+fn row_map(
+    headers: &[String],
+    record: &csv::StringRecord,
+) -> serde_json::Map<String, serde_json::Value> {
+    headers
+        .iter()
+        .zip(record.iter())
+        .map(|(header, field)| (header.clone(), serde_json::Value::String(field.to_owned())))
+        .collect()
+}
+
+fn format_number(
+    value: Value,
+    places: Option<usize>,
+    sep: Option<String>,
+) -> Result<String, Error> {
+    let value = value.to_string();
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(String::new());
+    }
+    let Ok(num) = value.parse::<f64>() else {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("format_number: \"{value}\" is not a number"),
+        ));
+    };
+    let formatted = match places {
+        Some(places) => format!("{num:.places$}"),
+        None => num.to_string(),
+    };
+    let Some(sep) = sep.filter(|s| !s.is_empty()) else {
+        return Ok(formatted);
+    };
+
+    // group the digits of the integer part in thousands
+    let (sign, unsigned) = match formatted.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", formatted.as_str()),
+    };
+    let (int_part, frac_part) = match unsigned.find('.') {
+        Some(dot) => unsigned.split_at(dot),
+        None => (unsigned, ""),
+    };
+    let mut grouped = String::with_capacity(formatted.len() + int_part.len() / 3 * sep.len());
+    grouped.push_str(sign);
+    for (i, digit) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            grouped.push_str(&sep);
+        }
+        grouped.push(digit);
+    }
+    grouped.push_str(frac_part);
+    Ok(grouped)
+}
+
+fn format_date(value: &Value, fmt: &str, prefer_dmy: bool) -> Result<String, Error> {
+    let value = value.to_string();
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(String::new());
+    }
+    let Ok(date) = parse_with_preference(value, prefer_dmy) else {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("format_date: \"{value}\" is not a date"),
+        ));
+    };
+    let mut formatted = String::new();
+    if write!(formatted, "{}", date.format(fmt)).is_err() {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("format_date: invalid format string \"{fmt}\""),
+        ));
+    }
+    Ok(formatted)
+}
+
+fn sql_quote(value: Value) -> String {
+    if value.is_undefined() || value.is_none() {
+        return "NULL".to_string();
+    }
+    let value = value.to_string();
+    if value.is_empty() {
+        "NULL".to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
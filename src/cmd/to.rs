@@ -1,5 +1,5 @@
 static USAGE: &str = r#"
-Convert CSV files to PostgreSQL, SQLite, XLSX, Parquet and Data Package.
+Convert CSV files to PostgreSQL, SQLite, XLSX, ODS, Parquet, Data Package, HTML and XML.
 
 POSTGRES
 To convert to postgres you need to supply connection string.
@@ -90,6 +90,14 @@ Print dump to stdout.
 XLSX
 Convert to new xlsx file.
 
+Cells are typed with the column types inferred by the `stats` command, so numbers are
+written as numbers, and dates as real Excel dates. The stats cache of each CSV file is used
+if it's current and was computed with --infer-dates --dates-whitelist all (see
+`qsv stats --help`). Otherwise, `stats` is run with these options.
+Integers with leading zeros (e.g. zip codes) or too long to be exact in a spreadsheet
+(e.g. IDs) are kept as text. Each sheet has a bold and frozen header row, an autofilter
+on the header row, and columns fitted to their contents.
+
 Example:
 
 Load `file1.csv` and `file2.csv' into xlsx file.
@@ -106,6 +114,17 @@ Load files listed in the 'ourdata.infile-list' into xlsx file.
 
     $ qsv to xlsx output.xlsx ourdata.infile-list
 
+ODS
+Convert to new OpenDocument spreadsheet (ods) file, e.g. for LibreOffice. It's written like
+xlsx files, with typed cells, a bold and frozen header row, and fitted columns, but without
+an autofilter.
+
+Example:
+
+Load `file1.csv` and `file2.csv' into ods file.
+
+  $ qsv to ods output.ods file1.csv file2.csv
+
 PARQUET (only available if compiled with `to_parquet` feature)
 Convert to directory of parquet files.  Need to select a directory, it will be created if it does not exists.
 If the `to_parquet` feature is not enabled, a simpler parquet conversion is available using the `sqlp`
@@ -132,6 +151,41 @@ Convert files listed in the 'data.infile-list' into parquet files in myparquetdi
     
         $ qsv to parquet myparquetdir data.infile-list
 
+HTML
+Convert to an HTML page with a table for each CSV file. Put the name of the HTML file
+or `-` for stdout. With several files, each table gets a caption with the filename
+without the extension. Use `--fragment` to only write the tables, e.g. to paste them
+into another page.
+
+Examples:
+
+Convert `file1.csv` to `file1.html`.
+
+  $ qsv to html file1.html file1.csv
+
+Print the tables of `file1.csv` and `file2.csv` to stdout, without the surrounding page.
+
+  $ qsv to html --fragment - file1.csv file2.csv
+
+XML
+Convert to an XML document, with an element for each record, and a child element for
+each field, named after its column. Characters that aren't valid in XML names are
+replaced with underscores. Empty fields are written as empty elements. Put the name of
+the XML file or `-` for stdout. With several files, the records of each file are
+wrapped in a `table` element, with the filename without the extension in its `name`
+attribute. Use `qsv xml` to convert XML back to CSV.
+
+Examples:
+
+Convert `file1.csv` to `file1.xml`, with <data> as the root element and a <row>
+element for each record.
+
+  $ qsv to xml file1.xml file1.csv
+
+Use <books> as the root element and <book> for each record.
+
+  $ qsv to xml --root books --row book books.xml books.csv
+
 DATAPACKAGE
 Generate a datapackage, which contains stats and information about what is in the CSV files.
 
@@ -163,8 +217,11 @@ Usage:
     qsv to postgres [options] <postgres> [<input>...]
     qsv to sqlite [options] <sqlite> [<input>...]
     qsv to xlsx [options] <xlsx> [<input>...]
+    qsv to ods [options] <ods> [<input>...]
     qsv to parquet [options] <parquet> [<input>...]
     qsv to datapackage [options] <datapackage> [<input>...]
+    qsv to html [options] <html> [<input>...]
+    qsv to xml [options] <xml> [<input>...]
     qsv to --help
 
 To options:
@@ -177,8 +234,11 @@ To options:
     -d --drop              Drop tables before loading new data into them (postgres/sqlite only).
     -e --evolve            If loading into existing db, alter existing tables so that new data will load. (postgres/sqlite only).
     -i --pipe              For parquet, allow piping from stdin (using `-`) or from a named pipe.
-    -p --separator <arg>   For xlsx, use this character to help truncate xlsx sheet names.
+    -p --separator <arg>   For xlsx and ods, use this character to help truncate sheet names.
                            Defaults to space.
+    --fragment             For html, only write the tables, without the surrounding page.
+    --root <name>          For xml, the name of the root element. [default: data]
+    --row <name>           For xml, the name of the element for each record. [default: row]
     -j, --jobs <arg>       The number of jobs to run in parallel.
                            When not set, the number of jobs is set to the number of CPUs detected.
                            
@@ -188,19 +248,32 @@ Common options:
                            Must be a single character. (default: ,)
 "#;
 
-use std::{io::Write, path::PathBuf};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
+use chrono::{NaiveDate, NaiveDateTime};
 #[cfg(all(feature = "to_parquet", feature = "feature_capable"))]
 use csvs_convert::csvs_to_parquet_with_options;
 use csvs_convert::{
-    csvs_to_postgres_with_options, csvs_to_sqlite_with_options, csvs_to_xlsx_with_options,
-    make_datapackage, DescribeOptions, Options,
+    csvs_to_postgres_with_options, csvs_to_sqlite_with_options, make_datapackage, DescribeOptions,
+    Options,
 };
 use log::debug;
+use qsv_dateparser::parse_with_preference;
+use quick_xml::escape::escape;
+use rust_xlsxwriter::{ColNum, Format, Workbook};
 use serde::Deserialize;
+use serde_json::json;
+use spreadsheet_ods::{
+    format::{create_date_iso_format, create_datetime_format},
+    CellStyle, Length, WorkBook,
+};
 
 use crate::{
     config::{self, Delimiter},
+    statscache::StatsCache,
     util,
     util::process_input,
     CliError, CliResult,
@@ -217,8 +290,14 @@ struct Args {
     arg_parquet:        Option<String>,
     cmd_xlsx:           bool,
     arg_xlsx:           Option<String>,
+    cmd_ods:            bool,
+    arg_ods:            Option<String>,
     cmd_datapackage:    bool,
     arg_datapackage:    Option<String>,
+    cmd_html:           bool,
+    arg_html:           Option<String>,
+    cmd_xml:            bool,
+    arg_xml:            Option<String>,
     arg_input:          Vec<PathBuf>,
     flag_delimiter:     Option<Delimiter>,
     flag_schema:        Option<String>,
@@ -232,6 +311,9 @@ struct Args {
     flag_print_package: bool,
     flag_quiet:         bool,
     flag_pipe:          bool,
+    flag_fragment:      bool,
+    flag_root:          String,
+    flag_row:           String,
 }
 
 impl From<csvs_convert::Error> for CliError {
@@ -246,6 +328,18 @@ impl From<csvs_convert::DescribeError> for CliError {
     }
 }
 
+impl From<rust_xlsxwriter::XlsxError> for CliError {
+    fn from(err: rust_xlsxwriter::XlsxError) -> CliError {
+        CliError::Other(format!("XLSX error: {err}"))
+    }
+}
+
+impl From<spreadsheet_ods::OdsError> for CliError {
+    fn from(err: spreadsheet_ods::OdsError) -> CliError {
+        CliError::Other(format!("ODS error: {err}"))
+    }
+}
+
 static EMPTY_STDIN_ERRMSG: &str =
     "No data on stdin. Need to add connection string as first argument then the input CSVs";
 
@@ -314,29 +408,72 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 "`to_parquet` feature disabled. `to parquet` subcommand not available."
             );
         }
-    } else if args.cmd_xlsx {
-        debug!("converting to xlsx");
+    } else if args.cmd_xlsx || args.cmd_ods {
         arg_input = process_input(arg_input, &tmpdir, EMPTY_STDIN_ERRMSG)?;
 
-        output =
-            csvs_to_xlsx_with_options(args.arg_xlsx.expect("checked above"), arg_input, options)?;
-        debug!("conversion to xlsx complete");
+        let names = sheet_names(&arg_input, &options.seperator);
+        let mut tables = Vec::with_capacity(arg_input.len());
+        for (input, name) in arg_input.iter().zip(names) {
+            let table = TypedCsv::new(input, name, args.flag_delimiter, tmpdir.path())?;
+            tables.push(table);
+        }
+        if args.cmd_xlsx {
+            debug!("converting to xlsx");
+            let xlsx_path = args.arg_xlsx.as_deref().expect("checked above");
+            write_xlsx(xlsx_path, &mut tables)?;
+            debug!("conversion to xlsx complete");
+        } else {
+            debug!("converting to ods");
+            let ods_path = args.arg_ods.as_deref().expect("checked above");
+            write_ods(ods_path, &mut tables)?;
+            debug!("conversion to ods complete");
+        }
+
+        // only guess the types & stats of the datapackage if they're asked for, as we
+        // already have the types of the sheets
+        output = if args.flag_print_package || options.stats || !options.stats_csv.is_empty() {
+            datapackage(arg_input, &options)?
+        } else {
+            sheets_package(&tables)
+        };
     } else if args.cmd_datapackage {
         debug!("creating datapackage");
         arg_input = process_input(arg_input, &tmpdir, EMPTY_STDIN_ERRMSG)?;
 
-        let describe_options = DescribeOptions::builder()
-            .delimiter(options.delimiter)
-            .stats(options.stats)
-            .threads(options.threads)
-            .stats_csv(options.stats_csv);
-        output = make_datapackage(arg_input, PathBuf::new(), &describe_options.build())?;
+        output = datapackage(arg_input, &options)?;
         let file = std::fs::File::create(args.arg_datapackage.expect("checked above"))?;
         serde_json::to_writer_pretty(file, &output)?;
         debug!("datapackage complete");
+    } else if args.cmd_html || args.cmd_xml {
+        arg_input = process_input(arg_input, &tmpdir, EMPTY_STDIN_ERRMSG)?;
+        let output_file = if args.cmd_html {
+            args.arg_html
+        } else {
+            args.arg_xml
+        };
+        let mut wtr = io::BufWriter::with_capacity(
+            config::DEFAULT_WTR_BUFFER_CAPACITY,
+            config::Config::new(&output_file).io_writer()?,
+        );
+        if args.cmd_html {
+            debug!("converting to html");
+            write_html(
+                &mut wtr,
+                &arg_input,
+                args.flag_delimiter,
+                args.flag_fragment,
+            )?;
+        } else {
+            debug!("converting to xml");
+            let root = xml_name(&args.flag_root);
+            let row = xml_name(&args.flag_row);
+            write_xml(&mut wtr, &arg_input, args.flag_delimiter, &root, &row)?;
+        }
+        return Ok(wtr.flush()?);
     } else {
         return fail_clierror!(
-            "Need to supply either xlsx,parquet,postgres,sqlite,datapackage as subcommand"
+            "Need to supply either xlsx,ods,parquet,postgres,sqlite,datapackage,html,xml as \
+             subcommand"
         );
     }
 
@@ -406,3 +543,444 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     Ok(())
 }
+
+/// The name of a table, i.e. the filename of its CSV without the extension.
+fn table_name(input: &Path) -> String {
+    input
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn write_html(
+    wtr: &mut impl Write,
+    inputs: &[PathBuf],
+    delimiter: Option<Delimiter>,
+    fragment: bool,
+) -> CliResult<()> {
+    if !fragment {
+        writeln!(
+            wtr,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n</head>\n<body>"
+        )?;
+    }
+    for input in inputs {
+        let mut rdr = config::Config::new(&Some(input.to_string_lossy().into_owned()))
+            .delimiter(delimiter)
+            .reader()?;
+        writeln!(wtr, "<table>")?;
+        if inputs.len() > 1 {
+            writeln!(wtr, "<caption>{}</caption>", escape(&table_name(input)))?;
+        }
+        write!(wtr, "<thead>\n<tr>")?;
+        for header in rdr.headers()? {
+            write!(wtr, "<th>{}</th>", escape(header))?;
+        }
+        writeln!(wtr, "</tr>\n</thead>\n<tbody>")?;
+        let mut record = csv::StringRecord::new();
+        while rdr.read_record(&mut record)? {
+            write!(wtr, "<tr>")?;
+            for field in &record {
+                write!(wtr, "<td>{}</td>", escape(field))?;
+            }
+            writeln!(wtr, "</tr>")?;
+        }
+        writeln!(wtr, "</tbody>\n</table>")?;
+    }
+    if !fragment {
+        writeln!(wtr, "</body>\n</html>")?;
+    }
+    Ok(())
+}
+
+fn write_xml(
+    wtr: &mut impl Write,
+    inputs: &[PathBuf],
+    delimiter: Option<Delimiter>,
+    root: &str,
+    row: &str,
+) -> CliResult<()> {
+    writeln!(wtr, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(wtr, "<{root}>")?;
+    // with several files, the records of each file are wrapped in a table element
+    let indent = if inputs.len() > 1 { "    " } else { "  " };
+    for input in inputs {
+        let mut rdr = config::Config::new(&Some(input.to_string_lossy().into_owned()))
+            .delimiter(delimiter)
+            .reader()?;
+        let names: Vec<String> = rdr.headers()?.iter().map(xml_name).collect();
+        if inputs.len() > 1 {
+            writeln!(wtr, r#"  <table name="{}">"#, escape(&table_name(input)))?;
+        }
+        let mut record = csv::StringRecord::new();
+        while rdr.read_record(&mut record)? {
+            writeln!(wtr, "{indent}<{row}>")?;
+            for (name, field) in names.iter().zip(&record) {
+                if field.is_empty() {
+                    writeln!(wtr, "{indent}  <{name}/>")?;
+                } else {
+                    writeln!(wtr, "{indent}  <{name}>{}</{name}>", escape(field))?;
+                }
+            }
+            writeln!(wtr, "{indent}</{row}>")?;
+        }
+        if inputs.len() > 1 {
+            writeln!(wtr, "  </table>")?;
+        }
+    }
+    writeln!(wtr, "</{root}>")?;
+    Ok(())
+}
+
+/// Makes a valid XML element name from a column name, replacing invalid characters
+/// with underscores, and prefixing it with an underscore if it doesn't start with a
+/// letter or an underscore.
+fn xml_name(name: &str) -> String {
+    let mut xml_name: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !xml_name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        xml_name.insert(0, '_');
+    }
+    xml_name
+}
+
+/// The datapackage of the input files, with the types and stats guessed by csvs_convert.
+fn datapackage(inputs: Vec<PathBuf>, options: &Options) -> CliResult<serde_json::Value> {
+    let describe_options = DescribeOptions::builder()
+        .delimiter(options.delimiter)
+        .stats(options.stats)
+        .threads(options.threads)
+        .stats_csv(options.stats_csv.clone())
+        .build();
+    Ok(make_datapackage(inputs, PathBuf::new(), &describe_options)?)
+}
+
+/// Excel's maximum length of sheet names, also used for ods.
+const MAX_SHEET_NAME_LEN: usize = 31;
+
+/// Excel's maximum number of columns in a sheet.
+const MAX_SHEET_COLUMNS: usize = 16_384;
+
+/// The largest integer that spreadsheet numbers (f64) can hold exactly.
+const MAX_EXACT_INTEGER: u64 = (1 << 53) - 1;
+
+/// The maximum width of fitted ods columns, in characters.
+const MAX_ODS_COLUMN_WIDTH: usize = 60;
+
+/// A CSV file to write as a sheet, with the `stats` types of its columns.
+struct TypedCsv {
+    conf:      config::Config,
+    name:      String,
+    headers:   csv::StringRecord,
+    types:     Vec<String>,
+    row_count: u32,
+}
+
+impl TypedCsv {
+    fn new(
+        input: &Path,
+        name: String,
+        delimiter: Option<Delimiter>,
+        tmpdir: &Path,
+    ) -> CliResult<TypedCsv> {
+        let conf =
+            config::Config::new(&Some(input.to_string_lossy().into_owned())).delimiter(delimiter);
+        let headers = conf.reader()?.headers()?.clone();
+        if headers.len() > MAX_SHEET_COLUMNS {
+            return fail_clierror!(
+                "{} has {} columns, more than the {MAX_SHEET_COLUMNS} columns of a sheet.",
+                input.display(),
+                headers.len()
+            );
+        }
+        let types = column_types(input, &headers, delimiter, tmpdir)?;
+        Ok(TypedCsv {
+            conf,
+            name,
+            headers,
+            types,
+            row_count: 0,
+        })
+    }
+}
+
+/// The `stats` types of the columns of a CSV file, from its stats cache if it's current and
+/// the dates of all the columns were inferred, so the same CSV is always converted the same
+/// way. Otherwise, `stats` is run with date inference, without caching its results next to
+/// the CSV file, so they don't end up in the sheets of a later conversion of its directory.
+fn column_types(
+    input: &Path,
+    headers: &csv::StringRecord,
+    delimiter: Option<Delimiter>,
+    tmpdir: &Path,
+) -> CliResult<Vec<String>> {
+    if let Some(stats_cache) = StatsCache::load(input).filter(StatsCache::all_dates_inferred) {
+        return Ok(headers
+            .iter()
+            .map(|header| {
+                stats_cache
+                    .get(header)
+                    .map(|column| column.field_type.clone())
+                    .unwrap_or_default()
+            })
+            .collect());
+    }
+
+    let stats_path = tmpdir.join("column_types.stats.csv");
+    let mut stats_cmd = std::process::Command::new(std::env::current_exe()?);
+    stats_cmd
+        .arg("stats")
+        .arg(input)
+        .args(["--infer-dates", "--dates-whitelist", "all"])
+        .args(["--cache-threshold", "0", "--output"])
+        .arg(&stats_path);
+    if let Some(delimiter) = delimiter {
+        stats_cmd.args(["--delimiter", &char::from(delimiter.as_byte()).to_string()]);
+    }
+    let stats_output = stats_cmd.output()?;
+    if !stats_output.status.success() {
+        return fail_clierror!(
+            "Cannot infer the column types of {}: {}",
+            input.display(),
+            String::from_utf8_lossy(&stats_output.stderr).trim()
+        );
+    }
+
+    let mut rdr = config::Config::new(&Some(stats_path.to_string_lossy().into_owned())).reader()?;
+    let type_idx = rdr
+        .headers()?
+        .iter()
+        .position(|header| header == "type")
+        .unwrap_or(1);
+    let mut types = Vec::with_capacity(headers.len());
+    for record in rdr.records() {
+        types.push(record?.get(type_idx).unwrap_or_default().to_string());
+    }
+    types.resize(headers.len(), String::new());
+    Ok(types)
+}
+
+/// The sheet names of the input files: their filenames without the extension, without the
+/// characters not allowed in sheet names, truncated and made unique.
+fn sheet_names(inputs: &[PathBuf], separator: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let name: String = table_name(input)
+            .chars()
+            .map(|c| {
+                if matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\') {
+                    '_'
+                } else {
+                    c
+                }
+            })
+            .collect();
+        let name = name.trim_matches('\'');
+        let base = if name.is_empty() { "Sheet" } else { name };
+
+        let mut name = truncate_sheet_name(base, separator, MAX_SHEET_NAME_LEN);
+        let mut number = 1;
+        // sheet names are case-insensitive
+        while names.iter().any(|other| other.eq_ignore_ascii_case(&name)) {
+            number += 1;
+            let suffix = format!("_{number}");
+            let truncated = truncate_sheet_name(base, separator, MAX_SHEET_NAME_LEN - suffix.len());
+            name = format!("{truncated}{suffix}");
+        }
+        names.push(name);
+    }
+    names
+}
+
+/// Truncates a sheet name to `max_len` characters, at the last separator if there's one, so
+/// it doesn't end with part of a word.
+fn truncate_sheet_name(name: &str, separator: &str, max_len: usize) -> String {
+    if name.chars().count() <= max_len {
+        return name.to_string();
+    }
+    let truncated: String = name.chars().take(max_len).collect();
+    match truncated.rfind(separator) {
+        Some(idx) if idx > 0 && !separator.is_empty() => truncated[..idx].to_string(),
+        _ => truncated,
+    }
+}
+
+/// A CSV field as a spreadsheet cell, typed with the `stats` type of its column.
+enum CellValue<'a> {
+    Empty,
+    Number(f64),
+    Boolean(bool),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    Text(&'a str),
+}
+
+impl<'a> CellValue<'a> {
+    /// Fields that can't be parsed as the type of their column are kept as text.
+    fn new(field: &'a str, field_type: &str, prefer_dmy: bool) -> CellValue<'a> {
+        if field.is_empty() {
+            return CellValue::Empty;
+        }
+        let value = match field_type {
+            "Integer" => field
+                .parse::<i64>()
+                .ok()
+                .filter(|n| {
+                    n.unsigned_abs() <= MAX_EXACT_INTEGER
+                        && !(field.len() > 1 && field.starts_with('0'))
+                })
+                .map(|n| CellValue::Number(n as f64)),
+            "Float" => field
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .map(CellValue::Number),
+            "Boolean" => match field.to_lowercase().as_str() {
+                "true" | "t" | "yes" | "y" | "1" => Some(CellValue::Boolean(true)),
+                "false" | "f" | "no" | "n" | "0" => Some(CellValue::Boolean(false)),
+                _ => None,
+            },
+            "Date" => parse_with_preference(field, prefer_dmy)
+                .ok()
+                .map(|datetime| CellValue::Date(datetime.date_naive())),
+            "DateTime" => parse_with_preference(field, prefer_dmy)
+                .ok()
+                .map(|datetime| CellValue::DateTime(datetime.naive_utc())),
+            _ => None,
+        };
+        value.unwrap_or(CellValue::Text(field))
+    }
+}
+
+fn write_xlsx(path: &str, tables: &mut [TypedCsv]) -> CliResult<()> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+    let datetime_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+
+    for table in tables {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&table.name)?;
+        for (col, header) in table.headers.iter().enumerate() {
+            worksheet.write_string_with_format(0, col as ColNum, header, &header_format)?;
+        }
+
+        let prefer_dmy = table.conf.get_dmy_preference();
+        let mut rdr = table.conf.reader()?;
+        let mut record = csv::StringRecord::new();
+        let mut row = 0;
+        while rdr.read_record(&mut record)? {
+            row += 1;
+            for (col, (field, field_type)) in record.iter().zip(&table.types).enumerate() {
+                let col = col as ColNum;
+                match CellValue::new(field, field_type, prefer_dmy) {
+                    CellValue::Empty => continue,
+                    CellValue::Number(n) => worksheet.write_number(row, col, n)?,
+                    CellValue::Boolean(b) => worksheet.write_boolean(row, col, b)?,
+                    CellValue::Date(date) => {
+                        worksheet.write_datetime_with_format(row, col, date, &date_format)?
+                    },
+                    CellValue::DateTime(datetime) => worksheet.write_datetime_with_format(
+                        row,
+                        col,
+                        datetime,
+                        &datetime_format,
+                    )?,
+                    CellValue::Text(text) => worksheet.write_string(row, col, text)?,
+                };
+            }
+        }
+        table.row_count = row;
+
+        worksheet.set_freeze_panes(1, 0)?;
+        if !table.headers.is_empty() {
+            worksheet.autofilter(0, 0, row, (table.headers.len() - 1) as ColNum)?;
+        }
+        worksheet.autofit();
+    }
+    workbook.save(path)?;
+    Ok(())
+}
+
+fn write_ods(path: &str, tables: &mut [TypedCsv]) -> CliResult<()> {
+    let mut workbook = WorkBook::new_empty();
+    let mut header_style = CellStyle::new_empty();
+    header_style.set_font_bold();
+    let header_style = workbook.add_cellstyle(header_style);
+    let date_format = workbook.add_datetime_format(create_date_iso_format("qsv_date"));
+    let date_style = workbook.add_cellstyle(CellStyle::new("qsv_date", &date_format));
+    let datetime_format = workbook.add_datetime_format(create_datetime_format("qsv_datetime"));
+    let datetime_style = workbook.add_cellstyle(CellStyle::new("qsv_datetime", &datetime_format));
+
+    for table in tables {
+        let mut sheet = spreadsheet_ods::Sheet::new(&table.name);
+        // the width of the columns, in characters
+        let mut widths: Vec<usize> = Vec::with_capacity(table.headers.len());
+        for (col, header) in table.headers.iter().enumerate() {
+            sheet.set_styled_value(0, col as u32, header, &header_style);
+            widths.push(header.chars().count());
+        }
+
+        let prefer_dmy = table.conf.get_dmy_preference();
+        let mut rdr = table.conf.reader()?;
+        let mut record = csv::StringRecord::new();
+        let mut row = 0;
+        while rdr.read_record(&mut record)? {
+            row += 1;
+            for (col, (field, field_type)) in record.iter().zip(&table.types).enumerate() {
+                widths[col] = widths[col].max(field.chars().count());
+                let col = col as u32;
+                match CellValue::new(field, field_type, prefer_dmy) {
+                    CellValue::Empty => {},
+                    CellValue::Number(n) => sheet.set_value(row, col, n),
+                    CellValue::Boolean(b) => sheet.set_value(row, col, b),
+                    CellValue::Date(date) => sheet.set_styled_value(row, col, date, &date_style),
+                    CellValue::DateTime(datetime) => {
+                        sheet.set_styled_value(row, col, datetime, &datetime_style);
+                    },
+                    CellValue::Text(text) => sheet.set_value(row, col, text),
+                }
+            }
+        }
+        table.row_count = row;
+
+        sheet.split_row_header(0);
+        for (col, width) in widths.into_iter().enumerate() {
+            let width = width.clamp(4, MAX_ODS_COLUMN_WIDTH) as f64;
+            sheet.set_col_width(col as u32, Length::Pt(width * 6.0 + 12.0));
+        }
+        workbook.push_sheet(sheet);
+    }
+    spreadsheet_ods::write_ods(&mut workbook, path)?;
+    Ok(())
+}
+
+/// A datapackage of the sheets, with their `stats` types, for the field summary.
+fn sheets_package(tables: &[TypedCsv]) -> serde_json::Value {
+    let resources: Vec<serde_json::Value> = tables
+        .iter()
+        .map(|table| {
+            let fields: Vec<serde_json::Value> = table
+                .headers
+                .iter()
+                .zip(&table.types)
+                .map(|(name, field_type)| json!({"name": name, "type": field_type}))
+                .collect();
+            json!({
+                "name": table.name,
+                "row_count": table.row_count,
+                "schema": {"fields": fields},
+            })
+        })
+        .collect();
+    json!({ "resources": resources })
+}
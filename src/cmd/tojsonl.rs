@@ -13,6 +13,16 @@ The `tojsonl` command will reuse a `stats.csv.bin.sz` file if it exists and is c
 (i.e. stats generated with --cardinality and --infer-dates options) and will skip
 recomputing stats.
 
+Alternatively, the data types can be taken from a JSON Schema with --schema, such as
+one generated by `qsv schema`, skipping the stats entirely. Columns that are not in
+the schema's properties are converted to strings. Values are not checked against the
+schema, so validate the CSV with `qsv validate` first if needed.
+
+By default, each record becomes a flat JSON object. With --nested, column names are
+treated as paths into nested objects and arrays, with "." separating object keys and
+"[n]" marking array positions. For example, the columns "address.city" and "tags[0]"
+become {"address": {"city": ...}, "tags": [...]}.
+
 For examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_tojsonl.rs.
 
 Usage:
@@ -23,6 +33,10 @@ Tojsonl options:
     --trim                 Trim leading and trailing whitespace from fields
                            before converting to JSON.
     --no-boolean           Do not infer boolean fields.
+    --schema <file>        Take the data types of the columns from this JSON Schema
+                           instead of inferring them from stats.
+    --nested               Build nested objects and arrays from the column names.
+    --array                Write a JSON array of objects instead of JSONL.
     -j, --jobs <arg>       The number of jobs to run in parallel.
                            When not set, the number of jobs is set to the
                            number of CPUs detected.
@@ -38,7 +52,13 @@ Common options:
                            CSV into memory using CONSERVATIVE heuristics.
 "#;
 
-use std::{fmt::Write, path::PathBuf, str::FromStr};
+use std::{
+    fmt::Write,
+    fs,
+    io::{self, Write as _},
+    path::PathBuf,
+    str::FromStr,
+};
 
 use rayon::{
     iter::{IndexedParallelIterator, ParallelIterator},
@@ -50,7 +70,7 @@ use strum_macros::EnumString;
 
 use super::schema::infer_schema_from_stats;
 use crate::{
-    config::{Config, Delimiter},
+    config::{Config, Delimiter, DEFAULT_WTR_BUFFER_CAPACITY},
     util, CliError, CliResult,
 };
 
@@ -59,6 +79,9 @@ struct Args {
     arg_input:       Option<String>,
     flag_trim:       bool,
     flag_no_boolean: bool,
+    flag_schema:     Option<String>,
+    flag_nested:     bool,
+    flag_array:      bool,
     flag_jobs:       Option<usize>,
     flag_batch:      u32,
     flag_delimiter:  Option<Delimiter>,
@@ -82,6 +105,12 @@ enum JsonlType {
     Null,
 }
 
+/// A step on the path to a value in a nested JSON object.
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
@@ -103,14 +132,166 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .unwrap();
     let conf = Config::new(&Some(input_filename.clone())).delimiter(args.flag_delimiter);
 
+    let mut rdr = conf.reader()?;
+    let headers = rdr.headers()?.clone();
+
+    let field_type_vec = match args.flag_schema {
+        Some(ref schema_file) => field_types_from_schema(schema_file, &headers)?,
+        None => infer_field_types(&args, &conf, &input_filename)?,
+    };
+
+    // with --nested, the path to each column's value in the nested object
+    let nested_paths = if args.flag_nested {
+        Some(nested_paths(&headers)?)
+    } else {
+        None
+    };
+
+    let mut wtr = io::BufWriter::with_capacity(
+        DEFAULT_WTR_BUFFER_CAPACITY,
+        Config::new(&args.flag_output).io_writer()?,
+    );
+    let mut first_record = true;
+
+    // amortize memory allocation by reusing record
+    #[allow(unused_assignments)]
+    let mut batch_record = csv::StringRecord::new();
+
+    // reuse batch buffers
+    let batchsize: usize = args.flag_batch as usize;
+    let mut batch = Vec::with_capacity(batchsize);
+    let mut batch_results = Vec::with_capacity(batchsize);
+
+    // set RAYON_NUM_THREADS
+    util::njobs(args.flag_jobs);
+
+    // main loop to read CSV and construct batches for parallel processing.
+    // each batch is processed via Rayon parallel iterator.
+    // loop exits when batch is empty.
+    'batch_loop: loop {
+        for _ in 0..batchsize {
+            match rdr.read_record(&mut batch_record) {
+                Ok(has_data) => {
+                    if has_data {
+                        batch.push(batch_record.clone());
+                    } else {
+                        // nothing else to add to batch
+                        break;
+                    }
+                },
+                Err(e) => {
+                    return fail_clierror!("Error reading file: {e}");
+                },
+            }
+        }
+
+        if batch.is_empty() {
+            // break out of infinite loop when at EOF
+            break 'batch_loop;
+        }
+
+        // process batch in parallel
+        batch
+            .par_iter()
+            .map(|record_item| {
+                let mut record = record_item.clone();
+                let mut json_string = String::new();
+                let mut temp_string2 = String::new();
+
+                let mut header_key = Value::String(String::new());
+                let mut temp_val = Value::String(String::new());
+
+                if args.flag_trim {
+                    record.trim();
+                }
+                if let Some(ref paths) = nested_paths {
+                    return nested_json(&record, paths, &field_type_vec);
+                }
+                write!(json_string, "{{").unwrap();
+                for (idx, field) in record.iter().enumerate() {
+                    let field_val = if let Some(field_type) = field_type_vec.get(idx) {
+                        match field_type {
+                            JsonlType::String => {
+                                if field.is_empty() {
+                                    "null"
+                                } else {
+                                    // we round-trip thru serde_json to escape the str
+                                    // per json spec (https://www.json.org/json-en.html)
+                                    temp_val = field.into();
+                                    temp_string2 = temp_val.to_string();
+                                    &temp_string2
+                                }
+                            },
+                            JsonlType::Null => "null",
+                            JsonlType::Integer | JsonlType::Number => {
+                                if field.is_empty() || is_json_number(field) {
+                                    field
+                                } else {
+                                    // a --schema type can be wrong, so non-numeric
+                                    // values are written as strings, like with --nested
+                                    temp_val = field.into();
+                                    temp_string2 = temp_val.to_string();
+                                    &temp_string2
+                                }
+                            },
+                            JsonlType::Boolean => {
+                                if let 't' | 'y' | '1' = boolcheck(field, &mut temp_string2) {
+                                    "true"
+                                } else {
+                                    "false"
+                                }
+                            },
+                        }
+                    } else {
+                        "null"
+                    };
+                    header_key = headers[idx].into();
+                    if field_val.is_empty() {
+                        write!(json_string, r#"{header_key}:null,"#).unwrap();
+                    } else {
+                        write!(json_string, r#"{header_key}:{field_val},"#).unwrap();
+                    }
+                }
+                json_string.pop(); // remove last comma
+                json_string.push('}');
+                json_string
+            })
+            .collect_into_vec(&mut batch_results);
+
+        // rayon collect() guarantees original order, so we can just append results each batch
+        for json in &batch_results {
+            if args.flag_array {
+                wtr.write_all(if first_record { b"[\n" } else { b",\n" })?;
+                wtr.write_all(json.as_bytes())?;
+            } else {
+                writeln!(wtr, "{json}")?;
+            }
+            first_record = false;
+        }
+
+        batch.clear();
+    } // end of batch loop
+
+    if args.flag_array {
+        wtr.write_all(if first_record { b"[]\n" } else { b"\n]\n" })?;
+    }
+    Ok(wtr.flush()?)
+}
+
+/// Infers the data types of the columns by computing their stats with the schema command.
+fn infer_field_types(
+    args: &Args,
+    conf: &Config,
+    input_filename: &str,
+) -> CliResult<Vec<JsonlType>> {
     // we're loading the entire file into memory, we need to check avail mem
     util::mem_file_check(
-        &std::path::PathBuf::from(input_filename.clone()),
+        &std::path::PathBuf::from(input_filename),
         false,
         args.flag_memcheck,
     )?;
 
-    let record_count = util::count_rows(&conf)?;
+    let record_count = util::count_rows(conf)?;
 
     // we're calling the schema command to infer data types and enums
     let schema_args = crate::cmd::schema::Args {
@@ -134,30 +315,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         flag_jobs:            Some(util::njobs(args.flag_jobs)),
         flag_no_headers:      false,
         flag_delimiter:       args.flag_delimiter,
-        arg_input:            Some(input_filename.clone()),
+        arg_input:            Some(input_filename.to_string()),
         flag_memcheck:        args.flag_memcheck,
     };
     // build schema for each field by their inferred type, min/max value/length, and unique values
     let properties_map: Map<String, Value> =
-        match infer_schema_from_stats(&schema_args, &input_filename) {
+        match infer_schema_from_stats(&schema_args, input_filename) {
             Ok(map) => map,
             Err(e) => {
                 return fail_clierror!("Failed to infer field types: {e}");
             },
         };
 
-    let mut rdr = conf.reader()?;
-
-    // TODO: instead of abusing csv writer to write jsonl file
-    // just use a normal buffered writer
-    let mut wtr = Config::new(&args.flag_output)
-        .flexible(true)
-        .no_headers(true)
-        .quote_style(csv::QuoteStyle::Never)
-        .writer()?;
-
-    let headers = rdr.headers()?.clone();
-
     // if there are less than 3 records, we can't infer boolean fields
     let no_boolean = if record_count < 3 {
         true
@@ -168,7 +337,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut lowecase_buffer = String::new();
 
     // create a vec lookup about inferred field data types
-    let mut field_type_vec: Vec<JsonlType> = Vec::with_capacity(headers.len());
+    let mut field_type_vec: Vec<JsonlType> = Vec::with_capacity(properties_map.len());
     for (_field_name, field_def) in &properties_map {
         let Some(field_map) = field_def.as_object() else {
             return fail!("Cannot create field map");
@@ -249,110 +418,170 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             .unwrap_or(JsonlType::String),
         );
     }
+    Ok(field_type_vec)
+}
 
-    // amortize memory allocation by reusing record
-    #[allow(unused_assignments)]
-    let mut batch_record = csv::StringRecord::new();
-
-    // reuse batch buffers
-    let batchsize: usize = args.flag_batch as usize;
-    let mut batch = Vec::with_capacity(batchsize);
-    let mut batch_results = Vec::with_capacity(batchsize);
+/// Takes the data types of the columns from the properties of a JSON Schema.
+/// Columns without a type in the schema are strings.
+fn field_types_from_schema(
+    schema_file: &str,
+    headers: &csv::StringRecord,
+) -> CliResult<Vec<JsonlType>> {
+    let schema: Value = match serde_json::from_str(&fs::read_to_string(schema_file)?) {
+        Ok(schema) => schema,
+        Err(e) => return fail_clierror!("Cannot parse JSON Schema {schema_file}: {e}"),
+    };
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return fail_clierror!("JSON Schema {schema_file} has no properties.");
+    };
 
-    // set RAYON_NUM_THREADS
-    util::njobs(args.flag_jobs);
+    Ok(headers
+        .iter()
+        .map(|header| {
+            // the type is either a string or an array of types, e.g. ["integer", "null"]
+            let types = match properties.get(header).and_then(|p| p.get("type")) {
+                Some(Value::String(t)) => vec![t.as_str()],
+                Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            types
+                .iter()
+                .find(|t| **t != "null")
+                .or_else(|| types.first())
+                .and_then(|t| JsonlType::from_str(t).ok())
+                .unwrap_or(JsonlType::String)
+        })
+        .collect())
+}
 
-    // main loop to read CSV and construct batches for parallel processing.
-    // each batch is processed via Rayon parallel iterator.
-    // loop exits when batch is empty.
-    'batch_loop: loop {
-        for _ in 0..batchsize {
-            match rdr.read_record(&mut batch_record) {
-                Ok(has_data) => {
-                    if has_data {
-                        batch.push(batch_record.clone());
-                    } else {
-                        // nothing else to add to batch
-                        break;
-                    }
-                },
-                Err(e) => {
-                    return fail_clierror!("Error reading file: {e}");
-                },
-            }
+/// Parses a column name into its path into a nested object, e.g. "a.b[0]" into
+/// [Key("a"), Key("b"), Index(0)]. Parts that aren't a key followed by array positions,
+/// e.g. "notes[draft]", are used as keys as is.
+fn parse_path(header: &str) -> Vec<PathSegment> {
+    let mut path = Vec::new();
+    for part in header.split('.') {
+        let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        let mut indices = Vec::new();
+        while let Some((index, after)) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            let Ok(index) = index.parse::<usize>() else {
+                break;
+            };
+            indices.push(PathSegment::Index(index));
+            rest = after;
+        }
+        if !rest.is_empty() || key.is_empty() && (path.is_empty() || indices.is_empty()) {
+            path.push(PathSegment::Key(part.to_string()));
+            continue;
         }
+        if !key.is_empty() {
+            path.push(PathSegment::Key(key.to_string()));
+        }
+        path.extend(indices);
+    }
+    path
+}
 
-        if batch.is_empty() {
-            // break out of infinite loop when at EOF
-            break 'batch_loop;
+/// Parses the column names into paths, checking that no column's value would be nested
+/// inside another column's value, e.g. with the columns "a" and "a.b".
+fn nested_paths(headers: &csv::StringRecord) -> CliResult<Vec<Vec<PathSegment>>> {
+    let paths: Vec<Vec<PathSegment>> = headers.iter().map(parse_path).collect();
+    let mut object = Value::Object(Map::new());
+    for (header, path) in headers.iter().zip(&paths) {
+        if !insert_at(&mut object, path, Value::Bool(true)) {
+            return fail_incorrectusage_clierror!(
+                "Cannot nest column \"{header}\", as its path conflicts with another column."
+            );
         }
+    }
+    Ok(paths)
+}
 
-        // process batch in parallel
-        batch
-            .par_iter()
-            .map(|record_item| {
-                let mut record = record_item.clone();
-                let mut json_string = String::new();
-                let mut temp_string2 = String::new();
+/// Inserts a value into a nested object at the given path, creating the objects and
+/// arrays along the way. Returns false if the path is already taken.
+fn insert_at(target: &mut Value, path: &[PathSegment], value: Value) -> bool {
+    let Some((segment, rest)) = path.split_first() else {
+        if !target.is_null() {
+            return false;
+        }
+        *target = value;
+        return true;
+    };
+    match segment {
+        PathSegment::Key(key) => {
+            if target.is_null() {
+                *target = Value::Object(Map::new());
+            }
+            let Value::Object(map) = target else {
+                return false;
+            };
+            insert_at(map.entry(key.as_str()).or_insert(Value::Null), rest, value)
+        },
+        PathSegment::Index(index) => {
+            if target.is_null() {
+                *target = Value::Array(Vec::new());
+            }
+            let Value::Array(values) = target else {
+                return false;
+            };
+            if values.len() <= *index {
+                values.resize(index + 1, Value::Null);
+            }
+            insert_at(&mut values[*index], rest, value)
+        },
+    }
+}
 
-                let mut header_key = Value::String(String::new());
-                let mut temp_val = Value::String(String::new());
+fn nested_json(
+    record: &csv::StringRecord,
+    paths: &[Vec<PathSegment>],
+    field_type_vec: &[JsonlType],
+) -> String {
+    let mut lowercase_buffer = String::new();
+    let mut object = Value::Object(Map::new());
+    for ((field, path), field_type) in record.iter().zip(paths).zip(field_type_vec) {
+        let value = match field_type {
+            JsonlType::String if !field.is_empty() => Value::String(field.to_string()),
+            JsonlType::Integer | JsonlType::Number if !field.is_empty() => {
+                serde_json::from_str(field).unwrap_or_else(|_| Value::String(field.to_string()))
+            },
+            JsonlType::Boolean => Value::Bool(matches!(
+                boolcheck(field, &mut lowercase_buffer),
+                't' | 'y' | '1'
+            )),
+            _ => Value::Null,
+        };
+        // the paths were checked for conflicts up front, so this always succeeds
+        insert_at(&mut object, path, value);
+    }
+    object.to_string()
+}
 
-                if args.flag_trim {
-                    record.trim();
-                }
-                write!(json_string, "{{").unwrap();
-                for (idx, field) in record.iter().enumerate() {
-                    let field_val = if let Some(field_type) = field_type_vec.get(idx) {
-                        match field_type {
-                            JsonlType::String => {
-                                if field.is_empty() {
-                                    "null"
-                                } else {
-                                    // we round-trip thru serde_json to escape the str
-                                    // per json spec (https://www.json.org/json-en.html)
-                                    temp_val = field.into();
-                                    temp_string2 = temp_val.to_string();
-                                    &temp_string2
-                                }
-                            },
-                            JsonlType::Null => "null",
-                            JsonlType::Integer | JsonlType::Number => field,
-                            JsonlType::Boolean => {
-                                if let 't' | 'y' | '1' = boolcheck(field, &mut temp_string2) {
-                                    "true"
-                                } else {
-                                    "false"
-                                }
-                            },
-                        }
-                    } else {
-                        "null"
-                    };
-                    header_key = headers[idx].into();
-                    if field_val.is_empty() {
-                        write!(json_string, r#"{header_key}:null,"#).unwrap();
-                    } else {
-                        write!(json_string, r#"{header_key}:{field_val},"#).unwrap();
-                    }
-                }
-                json_string.pop(); // remove last comma
-                json_string.push('}');
-                record.clear();
-                record.push_field(&json_string);
-                record
-            })
-            .collect_into_vec(&mut batch_results);
+/// check if a field is a valid JSON number, e.g. "-1.5e3", but not "007", "1." or "NaN"
+fn is_json_number(field: &str) -> bool {
+    let digits = |s: &str| s.bytes().take_while(u8::is_ascii_digit).count();
 
-        // rayon collect() guarantees original order, so we can just append results each batch
-        for result_record in &batch_results {
-            wtr.write_record(result_record)?;
+    let mut rest = field.strip_prefix('-').unwrap_or(field);
+    let int_len = digits(rest);
+    if int_len == 0 || (int_len > 1 && rest.starts_with('0')) {
+        return false;
+    }
+    rest = &rest[int_len..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let fraction_len = digits(fraction);
+        if fraction_len == 0 {
+            return false;
         }
-
-        batch.clear();
-    } // end of batch loop
-
-    Ok(wtr.flush()?)
+        rest = &fraction[fraction_len..];
+    }
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        let exponent_len = digits(exponent);
+        if exponent_len == 0 {
+            return false;
+        }
+        rest = &exponent[exponent_len..];
+    }
+    rest.is_empty()
 }
 
 #[inline]
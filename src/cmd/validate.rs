@@ -670,7 +670,10 @@ fn split_invalid_records(
     Ok(())
 }
 
-fn write_error_report(input_path: &str, validation_error_messages: Vec<String>) -> CliResult<()> {
+pub(crate) fn write_error_report(
+    input_path: &str,
+    validation_error_messages: Vec<String>,
+) -> CliResult<()> {
     let wtr_capacitys = env::var("QSV_WTR_BUFFER_CAPACITY")
         .unwrap_or_else(|_| DEFAULT_WTR_BUFFER_CAPACITY.to_string());
     let wtr_buffer_size: usize = wtr_capacitys.parse().unwrap_or(DEFAULT_WTR_BUFFER_CAPACITY);
@@ -0,0 +1,396 @@
+static USAGE: &str = r#"
+Convert XML, or a table in an HTML page, to CSV.
+
+Each occurrence of the repeated element given with --element becomes a record.
+Its attributes and the text of its child elements become columns, named by
+their path from the record element, joined with --separator. For example, with
+--element book, the document
+
+    <catalog>
+      <book id="b1"><title>Dune</title><price currency="USD">9.99</price></book>
+      <book id="b2"><title>Emma</title><author>Jane Austen</author></book>
+    </catalog>
+
+is converted to
+
+    id,title,price.currency,price,author
+    b1,Dune,USD,9.99,
+    b2,Emma,,,Jane Austen
+
+The text of the record element itself goes in a column named after it. When a
+child element is repeated in a record, its values are joined with --join.
+Namespace prefixes are dropped from element and attribute names.
+
+The headers are the union of the columns of all the records, in order of first
+appearance. To do this in one pass, the records are spooled to a temporary file
+before writing the CSV.
+
+With --html, a table in an HTML page is converted instead, with a record for
+each <tr> and a field for each <th> or <td>. The first row is used as the header
+row. Cells spanning several columns are followed by empty fields. Line breaks
+and repeated whitespace in cells are collapsed into single spaces. The HTML
+doesn't need to be well-formed XML, e.g. unclosed <br> elements are fine.
+
+Examples:
+    # convert each <record> element to a CSV record
+    qsv xml --element record data.xml
+
+    # only the <item> elements directly under the root <channel> element
+    qsv xml --element /channel/item feed.xml
+
+    # convert the second table of a saved web page
+    qsv xml --html --table 2 page.html -o table.csv
+
+For more examples, see https://github.com/jqnatividad/qsv/blob/master/tests/test_xml.rs.
+
+Usage:
+    qsv xml [options] [<input>]
+    qsv xml --help
+
+xml options:
+    -e, --element <path>     The path of the repeated element to convert to
+                             records, with element names separated by "/",
+                             e.g. "catalog/book". Matches elements at any depth,
+                             unless the path starts with "/", which anchors it
+                             to the root element. Required unless --html is set.
+    -s, --separator <sep>    The separator used to join element and attribute
+                             names into column names. [default: .]
+    --attr-prefix <prefix>   A prefix for the names of columns from attributes,
+                             e.g. "@", to tell them apart from child elements.
+    --join <sep>             The separator used to join the values of a child
+                             element repeated in a record. [default: |]
+    --html                   Convert a table in an HTML page instead.
+    --table <n>              With --html, the 1-based number of the table to
+                             convert, in document order. [default: 1]
+
+Common options:
+    -h, --help               Display this message
+    -o, --output <file>      Write output to <file> instead of stdout.
+    -d, --delimiter <arg>    The delimiter to use when writing CSV data.
+                             Must be a single character. [default: ,]
+"#;
+
+use std::{
+    borrow::Cow,
+    io::{self, BufRead},
+};
+
+use indexmap::IndexSet;
+use quick_xml::{events::Event, Reader};
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Delimiter, DEFAULT_RDR_BUFFER_CAPACITY},
+    util, CliError, CliResult,
+};
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input:        Option<String>,
+    flag_element:     Option<String>,
+    flag_separator:   String,
+    flag_attr_prefix: Option<String>,
+    flag_join:        String,
+    flag_html:        bool,
+    flag_table:       usize,
+    flag_output:      Option<String>,
+    flag_delimiter:   Option<Delimiter>,
+}
+
+impl From<quick_xml::Error> for CliError {
+    fn from(err: quick_xml::Error) -> CliError {
+        CliError::Other(format!("XML error: {err}"))
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rdr = io::BufReader::with_capacity(
+        DEFAULT_RDR_BUFFER_CAPACITY,
+        Config::new(&args.arg_input).io_reader()?,
+    );
+    let mut wtr = Config::new(&args.flag_output)
+        .delimiter(args.flag_delimiter)
+        .writer()?;
+
+    if args.flag_html {
+        if args.flag_table == 0 {
+            return fail_incorrectusage_clierror!("--table must be greater than 0.");
+        }
+        let mut rows = html_table_rows(rdr, args.flag_table)?;
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in &mut rows {
+            row.resize(width, String::new());
+            wtr.write_record(&*row)?;
+        }
+        return Ok(wtr.flush()?);
+    }
+
+    let Some(element) = args.flag_element.as_deref().and_then(ElementPath::parse) else {
+        return fail_incorrectusage_clierror!("--element is required unless --html is set.");
+    };
+    let attr_prefix = args.flag_attr_prefix.as_deref().unwrap_or_default();
+    let separator = args.flag_separator.as_str();
+
+    // first pass: write the records to a temp file, collecting the headers
+    let mut headers: IndexSet<String> = IndexSet::new();
+    let mut spool = util::RecordSpool::new()?;
+
+    let mut reader = Reader::from_reader(rdr);
+    reader.trim_text(true).expand_empty_elements(true);
+    let mut buf = Vec::new();
+    // the names of the elements from the root to the current element
+    let mut stack: Vec<String> = Vec::new();
+    // the depth of the record element, while in a record
+    let mut record_depth: Option<usize> = None;
+    // the values of the record, by column index
+    let mut record: Vec<(usize, String)> = Vec::new();
+
+    let mut add_value = |record: &mut Vec<(usize, String)>, column: String, value: &str| {
+        let (i, _) = headers.insert_full(column);
+        if let Some((_, values)) = record.iter_mut().find(|(column, _)| *column == i) {
+            values.push_str(&args.flag_join);
+            values.push_str(value);
+        } else {
+            record.push((i, value.to_owned()));
+        }
+    };
+
+    loop {
+        let event = match reader.read_event_into(&mut buf) {
+            Ok(event) => event,
+            Err(e) => {
+                return fail_clierror!(
+                    "Cannot parse XML at position {}: {e}",
+                    reader.buffer_position()
+                )
+            },
+        };
+        match event {
+            Event::Start(e) => {
+                stack.push(String::from_utf8_lossy(e.local_name().as_ref()).into_owned());
+                if record_depth.is_none() && element.matches(&stack) {
+                    record_depth = Some(stack.len());
+                }
+                if let Some(depth) = record_depth {
+                    let path = stack[depth..].join(separator);
+                    for attr in e.attributes() {
+                        let attr = attr.map_err(quick_xml::Error::from)?;
+                        let name = String::from_utf8_lossy(attr.key.local_name().as_ref());
+                        let column = if path.is_empty() {
+                            format!("{attr_prefix}{name}")
+                        } else {
+                            format!("{path}{separator}{attr_prefix}{name}")
+                        };
+                        add_value(&mut record, column, &attr.unescape_value()?);
+                    }
+                }
+            },
+            Event::Text(e) => {
+                if let Some(depth) = record_depth {
+                    let column = text_column(&stack, depth, separator);
+                    add_value(&mut record, column, &e.unescape()?);
+                }
+            },
+            Event::CData(e) => {
+                if let Some(depth) = record_depth {
+                    let column = text_column(&stack, depth, separator);
+                    add_value(&mut record, column, &String::from_utf8_lossy(&e));
+                }
+            },
+            Event::End(_) => {
+                if record_depth == Some(stack.len()) {
+                    spool.push(&record)?;
+                    record.clear();
+                    record_depth = None;
+                }
+                stack.pop();
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+    }
+
+    // second pass: write the spooled records, now that all the headers are known
+    spool.write_csv(&headers, &mut wtr)?;
+    Ok(wtr.flush()?)
+}
+
+/// The path of the element to convert to records.
+struct ElementPath {
+    anchored: bool,
+    names:    Vec<String>,
+}
+
+impl ElementPath {
+    fn parse(path: &str) -> Option<ElementPath> {
+        let path = path.trim();
+        let names: Vec<String> = path
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect();
+        if names.is_empty() {
+            return None;
+        }
+        Some(ElementPath {
+            anchored: path.starts_with('/'),
+            names,
+        })
+    }
+
+    fn matches(&self, stack: &[String]) -> bool {
+        if self.anchored {
+            stack == self.names.as_slice()
+        } else {
+            stack.ends_with(&self.names)
+        }
+    }
+}
+
+/// The column for text in the current element: its path from the record element, or the
+/// name of the record element for the record's own text.
+fn text_column(stack: &[String], depth: usize, separator: &str) -> String {
+    if stack.len() == depth {
+        stack[depth - 1].clone()
+    } else {
+        stack[depth..].join(separator)
+    }
+}
+
+// the maximum colspan allowed by the HTML spec
+const HTML_MAX_COLSPAN: usize = 1000;
+
+/// The rows of the cells of a table in an HTML page.
+#[derive(Default)]
+struct HtmlTable {
+    rows: Vec<Vec<String>>,
+    row:  Option<Vec<String>>,
+    // the text and the number of columns spanned of the current cell
+    cell: Option<(String, usize)>,
+}
+
+impl HtmlTable {
+    fn start_row(&mut self) {
+        self.end_row();
+        self.row = Some(Vec::new());
+    }
+
+    fn end_row(&mut self) {
+        self.end_cell();
+        if let Some(row) = self.row.take() {
+            if !row.is_empty() {
+                self.rows.push(row);
+            }
+        }
+    }
+
+    fn start_cell(&mut self, colspan: usize) {
+        self.end_cell();
+        if self.row.is_none() {
+            self.row = Some(Vec::new());
+        }
+        self.cell = Some((String::new(), colspan));
+    }
+
+    fn end_cell(&mut self) {
+        let Some((text, colspan)) = self.cell.take() else {
+            return;
+        };
+        // browsers clamp colspan to the HTML maximum, so a huge colspan can't
+        // make us push a huge number of empty fields
+        let colspan = colspan.min(HTML_MAX_COLSPAN);
+        let row = self.row.get_or_insert_with(Vec::new);
+        row.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+        for _ in 1..colspan {
+            row.push(String::new());
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if let Some((cell, _)) = self.cell.as_mut() {
+            cell.push_str(text);
+        }
+    }
+}
+
+/// Reads the rows of the `number`th table in an HTML page, ignoring nested tables.
+fn html_table_rows(rdr: impl BufRead, number: usize) -> CliResult<Vec<Vec<String>>> {
+    let mut reader = Reader::from_reader(rdr);
+    // HTML has elements that are never closed, e.g. <br>, and often unclosed <td> and <tr>
+    reader.check_end_names(false).expand_empty_elements(true);
+    let mut buf = Vec::new();
+    let mut tables_seen = 0_usize;
+    let mut in_table = false;
+    let mut nested_tables = 0_usize;
+    let mut table = HtmlTable::default();
+
+    loop {
+        let event = match reader.read_event_into(&mut buf) {
+            Ok(event) => event,
+            Err(e) => {
+                return fail_clierror!(
+                    "Cannot parse HTML at position {}: {e}",
+                    reader.buffer_position()
+                )
+            },
+        };
+        match event {
+            Event::Start(e) => {
+                let name = e.local_name().as_ref().to_ascii_lowercase();
+                if name == b"table" {
+                    if in_table {
+                        nested_tables += 1;
+                    } else {
+                        tables_seen += 1;
+                        in_table = tables_seen == number;
+                    }
+                } else if in_table && nested_tables == 0 {
+                    match name.as_slice() {
+                        b"tr" => table.start_row(),
+                        b"td" | b"th" => {
+                            // HTML attribute values can be unquoted, e.g. colspan=2
+                            let colspan = e
+                                .html_attributes()
+                                .filter_map(Result::ok)
+                                .find(|attr| attr.key.as_ref().eq_ignore_ascii_case(b"colspan"))
+                                .and_then(|attr| attr.unescape_value().ok()?.trim().parse().ok())
+                                .unwrap_or(1_usize);
+                            table.start_cell(colspan.max(1));
+                        },
+                        b"br" => table.push_text(" "),
+                        _ => {},
+                    }
+                }
+            },
+            Event::End(e) if in_table => {
+                match e.local_name().as_ref().to_ascii_lowercase().as_slice() {
+                    b"table" if nested_tables > 0 => nested_tables -= 1,
+                    b"table" => break,
+                    b"td" | b"th" if nested_tables == 0 => table.end_cell(),
+                    b"tr" if nested_tables == 0 => table.end_row(),
+                    _ => {},
+                }
+            },
+            Event::Text(e) if in_table => {
+                // HTML pages can have entities that aren't valid in XML
+                let text = e
+                    .unescape()
+                    .unwrap_or_else(|_| Cow::Owned(String::from_utf8_lossy(&e).into_owned()));
+                table.push_text(&text);
+            },
+            Event::CData(e) if in_table => table.push_text(&String::from_utf8_lossy(&e)),
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+    }
+
+    if tables_seen < number {
+        return fail_clierror!("Table {number} not found. The page has {tables_seen} table(s).");
+    }
+    table.end_row();
+    Ok(table.rows)
+}
@@ -1,15 +1,22 @@
 use std::{
+    collections::HashMap,
     env, fs,
-    io::{self, Read},
+    io::{self, BufRead, Read, Write},
     path::{Path, PathBuf},
-    sync::atomic::{AtomicBool, Ordering},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
 };
 
 use log::{debug, info, warn};
 use qsv_sniffer::{SampleSize, Sniffer};
 use serde::de::{Deserialize, Deserializer, Error};
+use strum_macros::EnumString;
+use url::Url;
 
 use crate::{
+    clitypes::NetworkIoError,
     index::Indexed,
     select::{SelectColumns, Selection},
     util, CliResult,
@@ -29,9 +36,266 @@ const NO_INDEX_WARNING_FILESIZE: u64 = 100_000_000; // 100MB
 // so we don't have to keep checking if the index has been created
 static AUTO_INDEXED: AtomicBool = AtomicBool::new(false);
 
+// default timeout in seconds when downloading remote inputs.
+// Overridden by the QSV_TIMEOUT environment variable.
+const DEFAULT_REMOTE_TIMEOUT: u16 = 30;
+
+// remote inputs already downloaded to temp files, keyed by URL, so a command that
+// creates several Configs for the same input only downloads it once
+static REMOTE_DOWNLOADS: OnceLock<Mutex<HashMap<String, Arc<tempfile::TempPath>>>> =
+    OnceLock::new();
+
+/// Deletes the temp files of downloaded remote inputs when dropped.
+/// It's instantiated at the start of main(), so the temp files are removed when qsv exits.
+pub struct RemoteDownloadsGuard;
+
+impl Drop for RemoteDownloadsGuard {
+    fn drop(&mut self) {
+        if let Some(downloads) = REMOTE_DOWNLOADS.get() {
+            if let Ok(mut downloads) = downloads.lock() {
+                downloads.clear();
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Delimiter(pub u8);
 
+/// Codec represents the compression formats qsv transparently decodes/encodes.
+/// The codec is inferred from the file extension, or from the magic bytes for stdin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum Codec {
+    #[strum(disabled)]
+    None,
+    #[strum(serialize = "snappy", serialize = "sz")]
+    Snappy,
+    #[strum(serialize = "gzip", serialize = "gz")]
+    Gzip,
+    #[strum(serialize = "zstd", serialize = "zst")]
+    Zstd,
+    #[strum(serialize = "bzip2", serialize = "bz2")]
+    Bzip2,
+}
+
+/// ColumnarFormat represents the columnar file formats qsv can read as CSV.
+/// They are detected by file extension, and streamed as CSV records with the
+/// headers taken from the file's schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnarFormat {
+    Parquet,
+    ArrowIpc,
+}
+
+impl ColumnarFormat {
+    pub fn from_path(path: &Path) -> Option<ColumnarFormat> {
+        match path
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("parquet") => Some(ColumnarFormat::Parquet),
+            Some("arrow" | "ipc") => Some(ColumnarFormat::ArrowIpc),
+            _ => None,
+        }
+    }
+}
+
+// the Snappy framing format stream identifier chunk
+const SNAPPY_MAGIC: &[u8] = b"\xff\x06\x00\x00sNaPpY";
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+const ZSTD_MAGIC: &[u8] = b"\x28\xb5\x2f\xfd";
+// bzip2 streams start with "BZh", the block size ('1'-'9') and the magic of the first block
+// (or of the end of the stream, if empty). The "BZh" prefix alone could be the start of CSV data
+const BZIP2_MAGIC: &[u8] = b"BZh";
+const BZIP2_BLOCK_MAGIC: &[u8] = b"\x31\x41\x59\x26\x53\x59";
+const BZIP2_EOS_MAGIC: &[u8] = b"\x17\x72\x45\x38\x50\x90";
+
+fn is_bzip2(bytes: &[u8]) -> bool {
+    bytes.len() >= 10
+        && bytes.starts_with(BZIP2_MAGIC)
+        && (b'1'..=b'9').contains(&bytes[3])
+        && (bytes[4..].starts_with(BZIP2_BLOCK_MAGIC) || bytes[4..].starts_with(BZIP2_EOS_MAGIC))
+}
+
+impl Codec {
+    /// infer the codec from a file extension (without the leading dot), case-insensitive
+    pub fn from_extension(ext: &str) -> Codec {
+        match ext.to_ascii_lowercase().as_str() {
+            "sz" => Codec::Snappy,
+            "gz" => Codec::Gzip,
+            "zst" => Codec::Zstd,
+            "bz2" => Codec::Bzip2,
+            _ => Codec::None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Codec {
+        path.extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .map_or(Codec::None, Codec::from_extension)
+    }
+
+    /// infer the codec from the first bytes of the data
+    pub fn from_magic(bytes: &[u8]) -> Codec {
+        if bytes.starts_with(SNAPPY_MAGIC) {
+            Codec::Snappy
+        } else if bytes.starts_with(GZIP_MAGIC) {
+            Codec::Gzip
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Codec::Zstd
+        } else if is_bzip2(bytes) {
+            Codec::Bzip2
+        } else {
+            Codec::None
+        }
+    }
+
+    pub const fn is_compressed(self) -> bool {
+        !matches!(self, Codec::None)
+    }
+
+    /// wrap a reader with a streaming decoder for the codec
+    pub fn decoder<R: Read + Send + 'static>(
+        self,
+        rdr: R,
+    ) -> io::Result<Box<dyn Read + Send + 'static>> {
+        Ok(match self {
+            Codec::None => Box::new(rdr),
+            Codec::Snappy => Box::new(snap::read::FrameDecoder::new(rdr)),
+            Codec::Gzip => Box::new(flate2::read::MultiGzDecoder::new(rdr)),
+            Codec::Zstd => Box::new(zstd::stream::read::Decoder::new(rdr)?),
+            Codec::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(rdr)),
+        })
+    }
+
+    /// wrap a writer with a streaming encoder for the codec.
+    /// If level is None, the codec's default compression level is used.
+    /// Snappy has no compression levels, so level is ignored for it.
+    /// The encoder must be finished with Encoder::finish.
+    pub fn encoder<W: io::Write>(self, wtr: W, level: Option<u32>) -> io::Result<Encoder<W>> {
+        Ok(match self {
+            Codec::None => Encoder::None(wtr),
+            Codec::Snappy => Encoder::Snappy(snap::write::FrameEncoder::new(wtr)),
+            Codec::Gzip => Encoder::Gzip(flate2::write::GzEncoder::new(
+                wtr,
+                level.map_or_else(flate2::Compression::default, flate2::Compression::new),
+            )),
+            Codec::Zstd => Encoder::Zstd(zstd::stream::write::Encoder::new(
+                wtr,
+                level.map_or(zstd::DEFAULT_COMPRESSION_LEVEL, |l| l as i32),
+            )?),
+            Codec::Bzip2 => Encoder::Bzip2(bzip2::write::BzEncoder::new(
+                wtr,
+                level.map_or_else(bzip2::Compression::default, bzip2::Compression::new),
+            )),
+        })
+    }
+}
+
+/// Encoder is a streaming encoder for a Codec.
+/// The compressed stream is only complete once finish() is called, which
+/// returns the errors of writing the end of the stream. Dropping an
+/// unfinished Encoder leaves a truncated stream for some codecs.
+pub enum Encoder<W: io::Write> {
+    None(W),
+    Snappy(snap::write::FrameEncoder<W>),
+    Gzip(flate2::write::GzEncoder<W>),
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+    Bzip2(bzip2::write::BzEncoder<W>),
+}
+
+impl<W: io::Write> Encoder<W> {
+    /// finish the compressed stream & flush the underlying writer
+    pub fn finish(self) -> io::Result<()> {
+        let mut wtr = match self {
+            Encoder::None(wtr) => wtr,
+            Encoder::Snappy(enc) => enc
+                .into_inner()
+                .map_err(snap::write::IntoInnerError::into_error)?,
+            Encoder::Gzip(enc) => enc.finish()?,
+            Encoder::Zstd(enc) => enc.finish()?,
+            Encoder::Bzip2(enc) => enc.finish()?,
+        };
+        wtr.flush()
+    }
+}
+
+impl<W: io::Write> io::Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Encoder::None(wtr) => wtr.write(buf),
+            Encoder::Snappy(enc) => enc.write(buf),
+            Encoder::Gzip(enc) => enc.write(buf),
+            Encoder::Zstd(enc) => enc.write(buf),
+            Encoder::Bzip2(enc) => enc.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::None(wtr) => wtr.flush(),
+            Encoder::Snappy(enc) => enc.flush(),
+            Encoder::Gzip(enc) => enc.flush(),
+            Encoder::Zstd(enc) => enc.flush(),
+            Encoder::Bzip2(enc) => enc.flush(),
+        }
+    }
+}
+
+/// the writer of the compressed files written by Config::io_writer.
+/// The commands flush their output when they're done, so the compressed
+/// stream is finished on flush, returning its errors instead of losing them
+/// when the encoder is dropped. Anything written after a flush starts a new
+/// compressed stream in the same file, which the decoders read as a
+/// continuation of the previous one.
+struct FinishOnFlush {
+    codec:   Codec,
+    file:    fs::File,
+    encoder: Option<Encoder<fs::File>>,
+}
+
+impl FinishOnFlush {
+    fn new(codec: Codec, file: fs::File) -> io::Result<Self> {
+        // start the stream right away, so an empty output is still a valid
+        // (empty) compressed stream
+        let encoder = codec.encoder(file.try_clone()?, None)?;
+        Ok(Self {
+            codec,
+            file,
+            encoder: Some(encoder),
+        })
+    }
+}
+
+impl io::Write for FinishOnFlush {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.encoder.is_none() {
+            self.encoder = Some(self.codec.encoder(self.file.try_clone()?, None)?);
+        }
+        // safety: the encoder was just set if it was None
+        self.encoder.as_mut().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.encoder.take() {
+            Some(encoder) => encoder.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for FinishOnFlush {
+    fn drop(&mut self) {
+        // the output wasn't flushed, so we can only try to finish the stream
+        if let Some(encoder) = self.encoder.take() {
+            let _ = encoder.finish();
+        }
+    }
+}
+
 /// Delimiter represents values that can be passed from the command line that
 /// can be used as a field delimiter in CSV data.
 ///
@@ -89,9 +353,15 @@ pub struct Config {
     pub autoindex_size: u64,
     prefer_dmy:         bool,
     pub comment:        Option<u8>,
-    snappy:             bool, // flag to enable snappy compression/decompression
+    codec:              Codec, // compression codec to transparently decode/encode with
+    columnar:           Option<ColumnarFormat>, // Parquet/Arrow input read as CSV
     pub read_buffer:    u32,
     pub write_buffer:   u32,
+    // if the input is a remote URL, the temp file it was downloaded to
+    remote_download:    Option<Arc<tempfile::TempPath>>,
+    // if the input is a remote URL that couldn't be downloaded, the error
+    // returned when the input is read
+    download_error:     Option<String>,
 }
 
 // Empty trait as an alias for Seek and Read that avoids auto trait errors
@@ -104,51 +374,48 @@ impl Config {
             Ok(delim) => Delimiter::decode_delimiter(&delim).unwrap().as_byte(),
             _ => b',',
         };
-        let (path, mut delim, snappy) = match *path {
-            None => (None, default_delim, false),
-            // WIP: support remote files; currently only http(s) is supported
-            // Some(ref s) if s.starts_with("http") && Url::parse(s).is_ok() => {
-            //     let mut snappy = false;
-            //     let delim = if s.ends_with(".csv.sz") {
-            //         snappy = true;
-            //         b','
-            //     } else if s.ends_with(".tsv.sz") || s.ends_with(".tab.sz") {
-            //         snappy = true;
-            //         b'\t'
-            //     } else {
-            //         default_delim
-            //     };
-            //     // download the file to a temporary location
-            //     util::download_file()
-            //     (Some(PathBuf::from(s)), delim, snappy)
-            // },
-            Some(ref s) if &**s == "-" => (None, default_delim, false),
+        let mut download_error = None;
+        let (path, mut delim, codec, columnar, remote_download) = match *path {
+            None => (None, default_delim, Codec::None, None, None),
+            Some(ref s) if &**s == "-" => (None, default_delim, Codec::None, None, None),
             Some(ref s) => {
-                let path = PathBuf::from(s);
-                let file_extension = path
+                // if its a remote file, download it to a temp file that keeps the
+                // URL's file extension, so we can still infer the delimiter and
+                // compression from it, and seekable readers & indexing still work
+                let (path, remote_download) = if is_remote_url(s) {
+                    match download_remote_input(s) {
+                        Ok(temp_path) => (temp_path.to_path_buf(), Some(temp_path)),
+                        Err(e) => {
+                            download_error = Some(e.to_string());
+                            (PathBuf::from(s), None)
+                        },
+                    }
+                } else {
+                    (PathBuf::from(s), None)
+                };
+                // if its a compressed file (e.g. nyc311.csv.gz), we infer the delimiter
+                // from the extension before the compression extension
+                let codec = Codec::from_path(&path);
+                let delim_path = if codec.is_compressed() {
+                    path.with_extension("")
+                } else {
+                    path.clone()
+                };
+                let file_extension = delim_path
                     .extension()
                     .unwrap_or_default()
                     .to_str()
                     .unwrap()
                     .to_ascii_lowercase();
-                let mut snappy = false;
                 let delim = if file_extension == "tsv" || file_extension == "tab" {
                     b'\t'
                 } else if file_extension == "csv" {
                     b','
                 } else {
-                    let filename = path.file_name().unwrap().to_str().unwrap();
-                    if filename.ends_with(".csv.sz") {
-                        snappy = true;
-                        b','
-                    } else if filename.ends_with(".tsv.sz") || filename.ends_with(".tab.sz") {
-                        snappy = true;
-                        b'\t'
-                    } else {
-                        default_delim
-                    }
+                    default_delim
                 };
-                (Some(path), delim, snappy || file_extension.ends_with("sz"))
+                let columnar = ColumnarFormat::from_path(&path);
+                (Some(path), delim, codec, columnar, remote_download)
             },
         };
         let sniff = util::get_envvar_flag("QSV_SNIFF_DELIMITER")
@@ -159,7 +426,7 @@ impl Config {
         };
         let no_headers = util::get_envvar_flag("QSV_NO_HEADERS");
         let mut preamble = 0_u64;
-        if sniff && path.is_some() {
+        if sniff && path.is_some() && columnar.is_none() && download_error.is_none() {
             let sniff_path = path.as_ref().unwrap().to_str().unwrap();
 
             match Sniffer::new()
@@ -203,7 +470,8 @@ impl Config {
                 .unwrap_or(0),
             prefer_dmy: util::get_envvar_flag("QSV_PREFER_DMY"),
             comment,
-            snappy,
+            codec,
+            columnar,
             read_buffer: std::env::var("QSV_RDR_BUFFER_CAPACITY")
                 .unwrap_or_else(|_| DEFAULT_RDR_BUFFER_CAPACITY.to_string())
                 .parse()
@@ -212,6 +480,8 @@ impl Config {
                 .unwrap_or_else(|_| DEFAULT_WTR_BUFFER_CAPACITY.to_string())
                 .parse()
                 .unwrap_or(DEFAULT_WTR_BUFFER_CAPACITY as u32),
+            remote_download,
+            download_error,
         }
     }
 
@@ -315,9 +585,29 @@ impl Config {
         self.path.is_none()
     }
 
-    #[cfg(feature = "polars")]
-    pub const fn is_snappy(&self) -> bool {
-        self.snappy
+    /// true if the input was a http(s) URL that was downloaded to a temp file
+    pub const fn is_remote(&self) -> bool {
+        self.remote_download.is_some()
+    }
+
+    pub const fn is_compressed(&self) -> bool {
+        self.codec.is_compressed()
+    }
+
+    /// return the error of downloading a remote input, if it failed
+    fn check_download(&self) -> io::Result<()> {
+        match self.download_error {
+            Some(ref e) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                NetworkIoError(e.clone()),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// true if the input is a Parquet or Arrow IPC file, which is read as CSV
+    pub const fn is_columnar(&self) -> bool {
+        self.columnar.is_some()
     }
 
     #[inline]
@@ -351,34 +641,49 @@ impl Config {
     }
 
     pub fn reader_file(&self) -> io::Result<csv::Reader<fs::File>> {
+        self.check_download()?;
         match self.path {
             None => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 "Cannot use <stdin> here",
             )),
+            Some(_) if self.is_columnar() => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot use Parquet/Arrow input here",
+            )),
             Some(ref p) => fs::File::open(p).map(|f| self.from_reader(f)),
         }
     }
 
     #[cfg(any(feature = "feature_capable", feature = "lite"))]
     pub fn reader_file_stdin(&self) -> io::Result<csv::Reader<Box<dyn SeekRead + 'static>>> {
+        self.check_download()?;
         Ok(match self.path {
             None => {
                 // Create a buffer in memory for stdin
                 let mut buffer: Vec<u8> = Vec::new();
-                let stdin = io::stdin();
-                stdin.lock().read_to_end(&mut buffer)?;
+                self.io_reader()?.read_to_end(&mut buffer)?;
+                self.from_reader(Box::new(io::Cursor::new(buffer)))
+            },
+            Some(_) if self.is_compressed() || self.is_columnar() => {
+                // compressed & columnar files are not seekable as CSV,
+                // so we read them as CSV into memory
+                let mut buffer: Vec<u8> = Vec::new();
+                self.io_reader()?.read_to_end(&mut buffer)?;
                 self.from_reader(Box::new(io::Cursor::new(buffer)))
             },
             Some(ref p) => self.from_reader(Box::new(fs::File::open(p).unwrap())),
         })
     }
 
-    fn autoindex_file(&self) {
+    /// create an index for the file, like QSV_AUTOINDEX_SIZE does.
+    /// It silently does nothing if the file can't be indexed.
+    pub fn autoindex_file(&self) {
         // autoindex_file should never panic. It should silently fail as its a "convenience fn"
         // that's why we have a lot of let-else returns, in lieu of unwraps
-        if self.snappy {
-            // cannot index snappy compressed files
+        if self.is_compressed() || self.is_columnar() || self.is_remote() {
+            // cannot index compressed & columnar files, and we don't index
+            // remote files, as the index would outlive the temp download
             return;
         }
 
@@ -409,6 +714,7 @@ impl Config {
     /// Unless the CSV's file size >= QSV_AUTOINDEX_SIZE, then we'll create an index automatically.
     /// This will also automatically update stale indices (i.e. the CSV is newer than the index )
     pub fn index_files(&self) -> io::Result<Option<(csv::Reader<fs::File>, fs::File)>> {
+        self.check_download()?;
         let mut data_modified = 0_u64;
         let data_fsize;
         let mut idx_path_work = PathBuf::new();
@@ -443,12 +749,13 @@ impl Config {
                     let idx_file = match fs::File::open(&idx_path_work) {
                         Err(_) => {
                             // the index file doesn't exist
-                            if self.snappy {
-                                // cannot index snappy compressed files
+                            if self.is_compressed() || self.is_columnar() || self.is_remote() {
+                                // cannot index compressed & columnar files,
+                                // and we don't autoindex remote files
                                 return Ok(None);
                             } else if self.autoindex_size > 0 && data_fsize >= self.autoindex_size {
                                 // if CSV file size >= QSV_AUTOINDEX_SIZE, and
-                                // its not a snappy file, create an index automatically
+                                // its not a compressed file, create an index automatically
                                 self.autoindex_file();
                                 fs::File::open(&idx_path_work)?
                             } else if data_fsize >= NO_INDEX_WARNING_FILESIZE {
@@ -502,16 +809,26 @@ impl Config {
     }
 
     pub fn io_reader(&self) -> io::Result<Box<dyn io::Read + Send + 'static>> {
+        self.check_download()?;
         Ok(match self.path {
-            None => Box::new(io::stdin()),
+            None => {
+                // stdin has no file extension, so we sniff its magic bytes
+                // to check if its compressed
+                let mut stdin_rdr =
+                    io::BufReader::with_capacity(DEFAULT_RDR_BUFFER_CAPACITY, io::stdin());
+                let codec = Codec::from_magic(stdin_rdr.fill_buf()?);
+                if codec.is_compressed() {
+                    info!("decoding {codec:?}-compressed stdin");
+                }
+                codec.decoder(stdin_rdr)?
+            },
+            Some(ref p) if self.is_columnar() => self.columnar_reader(p)?,
             Some(ref p) => match fs::File::open(p) {
                 Ok(x) => {
-                    if self.snappy {
-                        info!("decoding snappy-compressed file: {}", p.display());
-                        Box::new(snap::read::FrameDecoder::new(x))
-                    } else {
-                        Box::new(x)
+                    if self.is_compressed() {
+                        info!("decoding {:?}-compressed file: {}", self.codec, p.display());
                     }
+                    self.codec.decoder(x)?
                 },
                 Err(err) => {
                     let msg = format!("failed to open {}: {}", p.display(), err);
@@ -521,6 +838,36 @@ impl Config {
         })
     }
 
+    /// read a Parquet/Arrow IPC file as a stream of CSV data, using the Config's
+    /// delimiter. The header row is taken from the file's schema.
+    #[cfg(feature = "polars")]
+    fn columnar_reader(&self, p: &Path) -> io::Result<Box<dyn io::Read + Send + 'static>> {
+        let batches = ColumnarBatches::open(p, self.columnar).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("failed to open {}: {e}", p.display()),
+            )
+        })?;
+        info!("reading {:?} file as CSV: {}", self.columnar, p.display());
+        Ok(Box::new(ColumnarCsvReader::new(
+            batches,
+            self.delimiter,
+            !self.no_headers,
+        )))
+    }
+
+    #[cfg(not(feature = "polars"))]
+    #[allow(clippy::unused_self)]
+    fn columnar_reader(&self, p: &Path) -> io::Result<Box<dyn io::Read + Send + 'static>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "cannot read {}: Parquet/Arrow input requires the polars feature",
+                p.display()
+            ),
+        ))
+    }
+
     #[allow(clippy::wrong_self_convention)]
     pub fn from_reader<R: Read>(&self, rdr: R) -> csv::Reader<R> {
         csv::ReaderBuilder::new()
@@ -544,11 +891,14 @@ impl Config {
                 if p_str == "sink" {
                     // sink is /dev/null
                     Box::new(io::sink())
-                } else if self.snappy {
-                    info!("writing snappy-compressed file: {p:?}");
-                    Box::new(snap::write::FrameEncoder::new(fs::File::create(p)?))
                 } else {
-                    Box::new(fs::File::create(p)?)
+                    let file = fs::File::create(p)?;
+                    if self.is_compressed() {
+                        info!("writing {:?}-compressed file: {p:?}", self.codec);
+                        Box::new(FinishOnFlush::new(self.codec, file)?)
+                    } else {
+                        Box::new(file)
+                    }
                 }
             },
         })
@@ -572,3 +922,221 @@ impl Config {
             .from_writer(wtr)
     }
 }
+
+/// is the input a http(s) URL?
+#[inline]
+fn is_remote_url(s: &str) -> bool {
+    let lower_s = s.to_ascii_lowercase();
+    (lower_s.starts_with("http://") || lower_s.starts_with("https://")) && Url::parse(s).is_ok()
+}
+
+/// stream a remote http(s) input to a temp file, honoring the QSV_TIMEOUT and
+/// QSV_USER_AGENT environment variables.
+/// The temp file keeps the file extension/s of the URL's last path segment
+/// (e.g. ".csv.sz"), so delimiter and snappy detection work as with local files.
+/// Each URL is only downloaded once per qsv invocation.
+fn download_remote_input(url: &str) -> CliResult<Arc<tempfile::TempPath>> {
+    let downloads = REMOTE_DOWNLOADS.get_or_init(|| Mutex::new(HashMap::new()));
+    // safety: we never panic while holding the lock
+    let mut downloads = downloads.lock().unwrap();
+    if let Some(temp_path) = downloads.get(url) {
+        return Ok(Arc::clone(temp_path));
+    }
+
+    let url_suffix = Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .and_then(|mut segments| segments.next_back().map(str::to_string))
+        })
+        .and_then(|filename| filename.find('.').map(|pos| filename[pos..].to_string()))
+        .unwrap_or_default();
+
+    let temp_path = tempfile::Builder::new()
+        .prefix("qsv-remote-")
+        .suffix(&url_suffix)
+        .tempfile()?
+        .into_temp_path();
+
+    info!("downloading remote input {url} to {}", temp_path.display());
+
+    // we download in a separate thread with its own runtime, as Config::new may be
+    // called from commands that are already running inside a tokio runtime
+    let download_url = url.to_string();
+    let download_path = temp_path.to_path_buf();
+    let download_result = std::thread::spawn(move || -> CliResult<()> {
+        let future = util::download_file(
+            &download_url,
+            download_path,
+            false,
+            None,
+            Some(DEFAULT_REMOTE_TIMEOUT),
+            None,
+        );
+        tokio::runtime::Runtime::new()?.block_on(future)
+    })
+    .join();
+
+    match download_result {
+        Ok(Ok(())) => {
+            let temp_path = Arc::new(temp_path);
+            downloads.insert(url.to_string(), Arc::clone(&temp_path));
+            Ok(temp_path)
+        },
+        Ok(Err(e)) => Err(e),
+        Err(_) => fail_clierror!("download thread panicked while downloading {url}"),
+    }
+}
+
+// the maximum number of rows of a Parquet file we convert to CSV at a time
+#[cfg(feature = "polars")]
+const COLUMNAR_CHUNK_ROWS: usize = 65_536;
+
+/// The batches of rows of a Parquet/Arrow IPC file, read one at a time:
+/// a Parquet row group (split in chunks of up to COLUMNAR_CHUNK_ROWS rows),
+/// or an Arrow IPC record batch.
+#[cfg(feature = "polars")]
+enum ColumnarBatches {
+    Parquet(
+        polars::prelude::BatchedParquetReader,
+        std::collections::VecDeque<polars::prelude::DataFrame>,
+    ),
+    ArrowIpc(polars::export::arrow::io::ipc::read::FileReader<io::BufReader<fs::File>>),
+}
+
+#[cfg(feature = "polars")]
+impl ColumnarBatches {
+    fn open(
+        p: &Path,
+        format: Option<ColumnarFormat>,
+    ) -> polars::prelude::PolarsResult<ColumnarBatches> {
+        use polars::{
+            export::arrow::io::ipc::read::{read_file_metadata, FileReader},
+            prelude::{ParquetReader, SerReader},
+        };
+
+        let file = fs::File::open(p)?;
+        Ok(match format {
+            Some(ColumnarFormat::ArrowIpc) => {
+                let mut rdr = io::BufReader::with_capacity(DEFAULT_RDR_BUFFER_CAPACITY, file);
+                let metadata = read_file_metadata(&mut rdr)?;
+                ColumnarBatches::ArrowIpc(FileReader::new(rdr, metadata, None, None))
+            },
+            _ => ColumnarBatches::Parquet(
+                ParquetReader::new(file).batched(COLUMNAR_CHUNK_ROWS)?,
+                std::collections::VecDeque::new(),
+            ),
+        })
+    }
+
+    /// the next batch of rows, or None when all the batches were read
+    fn next_batch(&mut self) -> polars::prelude::PolarsResult<Option<polars::prelude::DataFrame>> {
+        use polars::prelude::DataFrame;
+
+        match self {
+            ColumnarBatches::Parquet(rdr, pending) => {
+                while pending.is_empty() {
+                    // the row groups of a local file are read without awaiting
+                    // anything, so this doesn't need an async runtime
+                    match futures::executor::block_on(rdr.next_batches(1))? {
+                        Some(batches) => pending.extend(batches),
+                        None => return Ok(None),
+                    }
+                }
+                Ok(pending.pop_front())
+            },
+            ColumnarBatches::ArrowIpc(rdr) => match rdr.next() {
+                None => Ok(None),
+                Some(batch) => {
+                    let fields = rdr.schema().fields.clone();
+                    DataFrame::try_from((batch?, fields.as_slice())).map(Some)
+                },
+            },
+        }
+    }
+
+    /// an empty DataFrame with the columns of the file, to write its header row
+    /// when it has no rows. (Parquet files always have at least one, empty, batch.)
+    fn empty(&self) -> polars::prelude::DataFrame {
+        use polars::prelude::DataFrame;
+
+        match self {
+            ColumnarBatches::Parquet(..) => DataFrame::empty(),
+            ColumnarBatches::ArrowIpc(rdr) => DataFrame::from(rdr.schema()),
+        }
+    }
+}
+
+/// Streams a Parquet/Arrow IPC file as CSV data, one batch of rows at a time,
+/// so Config::reader can parse columnar files as regular csv::ByteRecords
+/// without loading the entire file into memory.
+#[cfg(feature = "polars")]
+struct ColumnarCsvReader {
+    batches:        ColumnarBatches,
+    delimiter:      u8,
+    include_header: bool,
+    done:           bool,
+    buf:            Vec<u8>,
+    pos:            usize,
+}
+
+#[cfg(feature = "polars")]
+impl ColumnarCsvReader {
+    fn new(batches: ColumnarBatches, delimiter: u8, include_header: bool) -> Self {
+        ColumnarCsvReader {
+            batches,
+            delimiter,
+            include_header,
+            done: false,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    // convert the next batch of rows to CSV into buf
+    fn next_chunk(&mut self) -> io::Result<()> {
+        use polars::prelude::{CsvWriter, SerWriter};
+
+        let polars_err = |e: polars::error::PolarsError| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("Polars error: {e}"))
+        };
+
+        self.buf.clear();
+        self.pos = 0;
+        let mut chunk = match self.batches.next_batch().map_err(polars_err)? {
+            Some(chunk) => chunk,
+            None => {
+                self.done = true;
+                if !self.include_header {
+                    return Ok(());
+                }
+                self.batches.empty()
+            },
+        };
+        if chunk.height() > 0 || self.include_header {
+            CsvWriter::new(&mut self.buf)
+                .include_header(self.include_header)
+                .with_separator(self.delimiter)
+                .finish(&mut chunk)
+                .map_err(polars_err)?;
+            self.include_header = false;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "polars")]
+impl io::Read for ColumnarCsvReader {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.buf.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let len = out.len().min(self.buf.len() - self.pos);
+        out[..len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
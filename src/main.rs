@@ -58,6 +58,7 @@ mod config;
 mod index;
 mod odhtcache;
 mod select;
+mod statscache;
 mod util;
 
 static USAGE: &str = r#"
@@ -95,6 +96,7 @@ fn main() -> QsvExitCode {
     enabled_commands.push_str(
         "    behead      Drop header from CSV file
     cat         Concatenate by row or column
+    compress    Compress/decompress data using gzip, zstd, bzip2 or Snappy
     count       Count records
     datefmt     Format date/datetime strings
     dedup       Remove redundant rows
@@ -125,14 +127,18 @@ fn main() -> QsvExitCode {
     enabled_commands
         .push_str("    foreach     Loop over a CSV file to execute bash commands (*nix only)\n");
 
-    enabled_commands.push_str("    frequency   Show frequency tables\n");
+    enabled_commands.push_str(
+        "    frequency   Show frequency tables
+    fwf         Convert fixed-width files to CSV and back\n",
+    );
 
     #[cfg(all(feature = "geocode", not(feature = "lite")))]
     enabled_commands
         .push_str("    geocode     Geocodes a location against the Geonames cities database.\n");
 
     enabled_commands.push_str(
-        "    headers     Show header names
+        "    groupby     Compute summary statistics for each group of rows
+    headers     Show header names
     help        Show this usage message
     index       Create CSV index for faster access
     input       Read CSVs w/ special quoting, skipping, trimming & transcoding rules
@@ -142,15 +148,23 @@ fn main() -> QsvExitCode {
     #[cfg(all(feature = "polars", feature = "feature_capable"))]
     enabled_commands.push_str("    joinp       Join CSV files using the Pola.rs engine\n");
 
-    enabled_commands.push_str("    jsonl       Convert newline-delimited JSON files to CSV\n");
+    enabled_commands.push_str(
+        "    json        Convert JSON arrays of records to CSV
+    jsonl       Convert newline-delimited JSON files to CSV\n",
+    );
+
+    #[cfg(all(feature = "lens", feature = "feature_capable"))]
+    enabled_commands.push_str("    lens        Browse a CSV interactively in the terminal\n");
 
     #[cfg(all(feature = "luau", feature = "feature_capable"))]
     enabled_commands.push_str("    luau        Execute Luau script on CSV data\n");
 
-    enabled_commands.push_str(
-        "    partition   Partition CSV data based on a column value
-    pseudo      Pseudonymise the values of a column\n",
-    );
+    enabled_commands.push_str("    partition   Partition CSV data based on a column value\n");
+
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    enabled_commands.push_str("    pivot       Pivot/unpivot CSV data using the Pola.rs engine\n");
+
+    enabled_commands.push_str("    pseudo      Pseudonymise the values of a column\n");
 
     #[cfg(all(feature = "python", feature = "feature_capable"))]
     enabled_commands.push_str("    py          Evaluate a Python expression on CSV data\n");
@@ -181,20 +195,25 @@ fn main() -> QsvExitCode {
     enabled_commands.push_str(
         "    stats       Infer data types and compute summary statistics
     table       Align CSV data into columns
+    template    Render CSV records through MiniJinja templates
     tojsonl     Convert CSV to newline-delimited JSON\n",
     );
 
     #[cfg(all(feature = "to", feature = "feature_capable"))]
-    enabled_commands
-        .push_str("    to          Convert CSVs to PostgreSQL/XLSX/Parquet/SQLite/Data Package\n");
+    enabled_commands.push_str(
+        "    to          Convert CSVs to PostgreSQL/XLSX/ODS/Parquet/SQLite/DataPackage/HTML/XML\n",
+    );
 
     enabled_commands.push_str(
         "    transpose   Transpose rows/columns of CSV data
-    validate    Validate CSV data for RFC4180-compliance or with JSON Schema",
+    validate    Validate CSV data for RFC4180-compliance or with JSON Schema
+    xml         Convert XML or HTML tables to CSV",
     );
     let num_commands = enabled_commands.split('\n').count();
 
     let now = Instant::now();
+    // removes the temp files of remote inputs downloaded by Config when qsv exits
+    let _remote_downloads_guard = config::RemoteDownloadsGuard;
     let (qsv_args, _) = match util::init_logger() {
         Ok((qsv_args, logger_handle)) => (qsv_args, logger_handle),
         Err(e) => {
@@ -323,6 +342,7 @@ enum Command {
     Apply,
     Behead,
     Cat,
+    Compress,
     Count,
     Datefmt,
     Dedup,
@@ -345,8 +365,10 @@ enum Command {
     #[cfg(all(feature = "foreach", target_family = "unix", not(feature = "lite")))]
     ForEach,
     Frequency,
+    Fwf,
     #[cfg(all(feature = "geocode", feature = "feature_capable"))]
     Geocode,
+    GroupBy,
     Headers,
     Help,
     Index,
@@ -354,10 +376,15 @@ enum Command {
     Join,
     #[cfg(all(feature = "polars", feature = "feature_capable"))]
     JoinP,
+    Json,
     Jsonl,
+    #[cfg(all(feature = "lens", feature = "feature_capable"))]
+    Lens,
     #[cfg(all(feature = "luau", feature = "feature_capable"))]
     Luau,
     Partition,
+    #[cfg(all(feature = "polars", feature = "feature_capable"))]
+    Pivot,
     Pseudo,
     #[cfg(all(feature = "python", feature = "feature_capable"))]
     Py,
@@ -380,11 +407,13 @@ enum Command {
     SqlP,
     Stats,
     Table,
+    Template,
     Transpose,
     #[cfg(all(feature = "to", feature = "feature_capable"))]
     To,
     Tojsonl,
     Validate,
+    Xml,
 }
 
 impl Command {
@@ -407,6 +436,7 @@ impl Command {
             #[cfg(all(feature = "apply", feature = "feature_capable"))]
             Command::Apply => cmd::apply::run(argv),
             Command::Cat => cmd::cat::run(argv),
+            Command::Compress => cmd::compress::run(argv),
             Command::Count => cmd::count::run(argv),
             Command::Datefmt => cmd::datefmt::run(argv),
             Command::Dedup => cmd::dedup::run(argv),
@@ -429,8 +459,10 @@ impl Command {
             Command::Flatten => cmd::flatten::run(argv),
             Command::Fmt => cmd::fmt::run(argv),
             Command::Frequency => cmd::frequency::run(argv),
+            Command::Fwf => cmd::fwf::run(argv),
             #[cfg(all(feature = "geocode", feature = "feature_capable"))]
             Command::Geocode => cmd::geocode::run(argv),
+            Command::GroupBy => cmd::groupby::run(argv),
             Command::Headers => cmd::headers::run(argv),
             Command::Help => {
                 wout!("{USAGE}");
@@ -442,10 +474,15 @@ impl Command {
             Command::Join => cmd::join::run(argv),
             #[cfg(all(feature = "polars", feature = "feature_capable"))]
             Command::JoinP => cmd::joinp::run(argv),
+            Command::Json => cmd::json::run(argv),
             Command::Jsonl => cmd::jsonl::run(argv),
+            #[cfg(all(feature = "lens", feature = "feature_capable"))]
+            Command::Lens => cmd::lens::run(argv),
             #[cfg(all(feature = "luau", feature = "feature_capable"))]
             Command::Luau => cmd::luau::run(argv),
             Command::Partition => cmd::partition::run(argv),
+            #[cfg(all(feature = "polars", feature = "feature_capable"))]
+            Command::Pivot => cmd::pivot::run(argv),
             Command::Pseudo => cmd::pseudo::run(argv),
             #[cfg(all(feature = "python", feature = "feature_capable"))]
             Command::Py => cmd::python::run(argv),
@@ -468,11 +505,13 @@ impl Command {
             Command::SqlP => cmd::sqlp::run(argv),
             Command::Stats => cmd::stats::run(argv),
             Command::Table => cmd::table::run(argv),
+            Command::Template => cmd::template::run(argv),
             Command::Transpose => cmd::transpose::run(argv),
             #[cfg(all(feature = "to", feature = "feature_capable"))]
             Command::To => cmd::to::run(argv),
             Command::Tojsonl => cmd::tojsonl::run(argv),
             Command::Validate => cmd::validate::run(argv),
+            Command::Xml => cmd::xml::run(argv),
         }
     }
 }
@@ -54,6 +54,7 @@ macro_rules! command_list {
     () => {
         "
     applydp     Apply series of transformations to a column
+    compress    Compress/decompress data using gzip, zstd, bzip2 or Snappy
     count       Count records
     datefmt     Format date/datetime strings
     describegpt Infer extended metadata using a LLM
@@ -63,12 +64,14 @@ macro_rules! command_list {
     exclude     Excludes the records in one CSV from another
     extdedup    Remove duplicates rows from an arbitrarily large text file
     frequency   Show frequency tables
+    groupby     Compute summary statistics for each group of rows
     headers     Show header names
     help        Show this usage message
     index       Create CSV index for faster access
     input       Read CSVs w/ special quoting, skipping, trimming & transcoding rules
     joinp       Join CSV files using the Pola.rs engine
     luau        Execute Luau script on CSV data
+    pivot       Pivot/unpivot CSV data using the Pola.rs engine
     pseudo      Pseudonymise the values of a column
     rename      Rename the columns of CSV data efficiently
     replace     Replace patterns in CSV data
@@ -99,6 +102,7 @@ mod config;
 mod index;
 mod odhtcache;
 mod select;
+mod statscache;
 mod util;
 
 static USAGE: &str = r#"
@@ -129,6 +133,8 @@ struct Args {
 
 fn main() -> QsvExitCode {
     let now = Instant::now();
+    // removes the temp files of remote inputs downloaded by Config when qsv exits
+    let _remote_downloads_guard = config::RemoteDownloadsGuard;
     let (qsv_args, _) = match util::init_logger() {
         Ok((qsv_args, logger_handle)) => (qsv_args, logger_handle),
         Err(e) => {
@@ -240,6 +246,7 @@ Please choose one of the following commands:",
 #[serde(rename_all = "lowercase")]
 enum Command {
     ApplyDP,
+    Compress,
     Count,
     Datefmt,
     Dedup,
@@ -249,6 +256,7 @@ enum Command {
     Exclude,
     ExtDedup,
     Frequency,
+    GroupBy,
     Headers,
     Help,
     Index,
@@ -257,6 +265,8 @@ enum Command {
     JoinP,
     #[cfg(feature = "luau")]
     Luau,
+    #[cfg(feature = "polars")]
+    Pivot,
     Pseudo,
     Rename,
     Replace,
@@ -293,6 +303,7 @@ impl Command {
         CURRENT_COMMAND.get_or_init(|| argv[1].to_lowercase());
         match self {
             Command::ApplyDP => cmd::applydp::run(argv),
+            Command::Compress => cmd::compress::run(argv),
             Command::Count => cmd::count::run(argv),
             Command::Datefmt => cmd::datefmt::run(argv),
             Command::Dedup => cmd::dedup::run(argv),
@@ -302,6 +313,7 @@ impl Command {
             Command::Exclude => cmd::exclude::run(argv),
             Command::ExtDedup => cmd::extdedup::run(argv),
             Command::Frequency => cmd::frequency::run(argv),
+            Command::GroupBy => cmd::groupby::run(argv),
             Command::Headers => cmd::headers::run(argv),
             Command::Help => {
                 wout!("{USAGE}");
@@ -314,6 +326,8 @@ impl Command {
             Command::JoinP => cmd::joinp::run(argv),
             #[cfg(feature = "luau")]
             Command::Luau => cmd::luau::run(argv),
+            #[cfg(feature = "polars")]
+            Command::Pivot => cmd::pivot::run(argv),
             Command::Pseudo => cmd::pseudo::run(argv),
             Command::Rename => cmd::rename::run(argv),
             Command::Replace => cmd::replace::run(argv),
@@ -21,6 +21,7 @@ macro_rules! command_list {
         "
     behead      Drop header from CSV file
     cat         Concatenate by row or column
+    compress    Compress/decompress data using gzip, zstd, bzip2 or Snappy
     count       Count records
     datefmt     Format date/datetime columns
     dedup       Remove redundant rows
@@ -37,11 +38,14 @@ macro_rules! command_list {
     flatten     Show one field per line
     fmt         Format CSV output (change field delimiter)
     frequency   Show frequency tables
+    fwf         Convert fixed-width files to CSV and back
+    groupby     Compute summary statistics for each group of rows
     headers     Show header names
     help        Show this usage message
     index       Create CSV index for faster access
     input       Read CSVs w/ special quoting, skipping, trimming & transcoding rules
     join        Join CSV files
+    json        Convert JSON arrays of records to CSV
     jsonl       Convert newline-delimited JSON files to CSV
     partition   Partition CSV data based on a column value
     pseudo      Pseudonymise the values of a column
@@ -62,9 +66,11 @@ macro_rules! command_list {
     split       Split CSV data into many files
     stats       Infer data types and compute summary statistics
     table       Align CSV data into columns
+    template    Render CSV records through MiniJinja templates
     tojsonl     Convert CSV to newline-delimited JSON
     transpose   Transpose rows/columns of CSV data
     validate    Validate CSV data for RFC4180-compliance or with JSON Schema
+    xml         Convert XML or HTML tables to CSV
 
 sponsored by datHere - Data Infrastructure Engineering (https://qsv.datHere.com)
 "
@@ -76,6 +82,7 @@ mod config;
 mod index;
 mod odhtcache;
 mod select;
+mod statscache;
 mod util;
 
 static USAGE: &str = r#"
@@ -107,6 +114,8 @@ struct Args {
 
 fn main() -> QsvExitCode {
     let now = Instant::now();
+    // removes the temp files of remote inputs downloaded by Config when qsv exits
+    let _remote_downloads_guard = config::RemoteDownloadsGuard;
     let (qsv_args, _) = match util::init_logger() {
         Ok((qsv_args, logger_handle)) => (qsv_args, logger_handle),
         Err(e) => {
@@ -224,6 +233,7 @@ Please choose one of the following commands:",
 enum Command {
     Behead,
     Cat,
+    Compress,
     Count,
     Datefmt,
     Dedup,
@@ -240,11 +250,14 @@ enum Command {
     Flatten,
     Fmt,
     Frequency,
+    Fwf,
+    GroupBy,
     Headers,
     Help,
     Index,
     Input,
     Join,
+    Json,
     Jsonl,
     Partition,
     Pseudo,
@@ -265,9 +278,11 @@ enum Command {
     Split,
     Stats,
     Table,
+    Template,
     Tojsonl,
     Transpose,
     Validate,
+    Xml,
 }
 
 impl Command {
@@ -287,6 +302,7 @@ impl Command {
         match self {
             Command::Behead => cmd::behead::run(argv),
             Command::Cat => cmd::cat::run(argv),
+            Command::Compress => cmd::compress::run(argv),
             Command::Count => cmd::count::run(argv),
             Command::Datefmt => cmd::datefmt::run(argv),
             Command::Dedup => cmd::dedup::run(argv),
@@ -303,6 +319,8 @@ impl Command {
             Command::Flatten => cmd::flatten::run(argv),
             Command::Fmt => cmd::fmt::run(argv),
             Command::Frequency => cmd::frequency::run(argv),
+            Command::Fwf => cmd::fwf::run(argv),
+            Command::GroupBy => cmd::groupby::run(argv),
             Command::Headers => cmd::headers::run(argv),
             Command::Help => {
                 wout!("{USAGE}");
@@ -312,6 +330,7 @@ impl Command {
             Command::Index => cmd::index::run(argv),
             Command::Input => cmd::input::run(argv),
             Command::Join => cmd::join::run(argv),
+            Command::Json => cmd::json::run(argv),
             Command::Jsonl => cmd::jsonl::run(argv),
             Command::Partition => cmd::partition::run(argv),
             Command::Pseudo => cmd::pseudo::run(argv),
@@ -332,9 +351,11 @@ impl Command {
             Command::Split => cmd::split::run(argv),
             Command::Stats => cmd::stats::run(argv),
             Command::Table => cmd::table::run(argv),
+            Command::Template => cmd::template::run(argv),
             Command::Tojsonl => cmd::tojsonl::run(argv),
             Command::Transpose => cmd::transpose::run(argv),
             Command::Validate => cmd::validate::run(argv),
+            Command::Xml => cmd::xml::run(argv),
         }
     }
 }
@@ -0,0 +1,155 @@
+// Shared reader for the stats cache written by the `stats` command
+// (<FILESTEM>.stats.csv & <FILESTEM>.stats.csv.json), so other commands can reuse
+// the inferred column types & cardinalities instead of recomputing them.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+
+/// the cached stats of a column
+pub struct ColumnStats {
+    pub field:             String,
+    /// the inferred type - NULL, Integer, Float, String, Date, DateTime or Boolean
+    pub field_type:        String,
+    /// only available if the stats were computed with --cardinality or --everything
+    pub cardinality:       Option<u64>,
+    /// false if the cardinality is a HyperLogLog estimate, i.e. the stats were
+    /// computed with --approx
+    pub cardinality_exact: bool,
+}
+
+pub struct StatsCache {
+    columns:            Vec<ColumnStats>,
+    /// the record count saved in the stats args. Zero if it was not saved.
+    record_count:       u64,
+    /// true if the stats were computed with --infer-dates --dates-whitelist all,
+    /// i.e. the types of all the date columns were inferred
+    all_dates_inferred: bool,
+}
+
+/// returns true if the cache file is newer than the input file,
+/// i.e. the input file was not modified after the cache was created
+pub fn is_current(cache_path: &Path, input_path: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(cache_path), modified(input_path)) {
+        (Some(cache_modified), Some(input_modified)) => cache_modified > input_modified,
+        _ => false,
+    }
+}
+
+/// returns the path of the <FILESTEM>.stats.csv file of the input file
+pub fn stats_csv_path(input_path: &Path) -> PathBuf {
+    input_path.with_extension("stats.csv")
+}
+
+impl StatsCache {
+    /// loads the cached stats of the input file if they exist and are current.
+    /// Returns None otherwise, or if the stats were computed without headers.
+    pub fn load(input_path: &Path) -> Option<StatsCache> {
+        let stats_csv = stats_csv_path(input_path);
+        let stats_args_json = stats_csv.with_extension("csv.json");
+        if !is_current(&stats_csv, input_path) || !is_current(&stats_args_json, input_path) {
+            debug!("no current stats cache for {}", input_path.display());
+            return None;
+        }
+
+        let stats_args: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&stats_args_json).ok()?).ok()?;
+        // the field names are column indices without headers, and the stats file is
+        // snappy-compressed if the stats output was
+        if stats_args["flag_no_headers"].as_bool() != Some(false)
+            || stats_args["flag_output_snappy"].as_bool() == Some(true)
+        {
+            return None;
+        }
+        let record_count = stats_args["record_count"].as_u64().unwrap_or_default();
+        // the stats columns with approximate results, e.g. the cardinality with --approx
+        let approx_columns: Vec<&str> = stats_args["approx_columns"]
+            .as_array()
+            .map(|columns| {
+                columns
+                    .iter()
+                    .filter_map(serde_json::Value::as_str)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let cardinality_exact = !approx_columns.contains(&"cardinality");
+        let all_dates_inferred = stats_args["flag_infer_dates"].as_bool() == Some(true)
+            && stats_args["flag_dates_whitelist"]
+                .as_str()
+                .is_some_and(|whitelist| whitelist.eq_ignore_ascii_case("all"));
+
+        let mut rdr = csv::Reader::from_path(&stats_csv).ok()?;
+        let headers = rdr.headers().ok()?.clone();
+        let col_idx = |name: &str| headers.iter().position(|h| h == name);
+        let (field_idx, type_idx) = (col_idx("field")?, col_idx("type")?);
+        let cardinality_idx = col_idx("cardinality");
+
+        let mut columns = Vec::new();
+        for record in rdr.records() {
+            let record = record.ok()?;
+            columns.push(ColumnStats {
+                field: record.get(field_idx)?.to_string(),
+                field_type: record.get(type_idx)?.to_string(),
+                cardinality: cardinality_idx
+                    .and_then(|i| record.get(i))
+                    .and_then(|c| c.parse().ok()),
+                cardinality_exact,
+            });
+        }
+        debug!("loaded stats cache for {}", input_path.display());
+
+        Some(StatsCache {
+            columns,
+            record_count,
+            all_dates_inferred,
+        })
+    }
+
+    /// the cached stats of the given column
+    pub fn get(&self, field: &str) -> Option<&ColumnStats> {
+        self.columns.iter().find(|c| c.field == field)
+    }
+
+    /// the record count of the input file, if it was saved in the stats args
+    pub const fn record_count(&self) -> Option<u64> {
+        if self.record_count == 0 {
+            None
+        } else {
+            Some(self.record_count)
+        }
+    }
+
+    /// true if the types of all the date columns were inferred, i.e. Date & DateTime
+    /// columns are not typed as String
+    pub const fn all_dates_inferred(&self) -> bool {
+        self.all_dates_inferred
+    }
+
+    /// the Polars data types of the Integer, Float & String columns,
+    /// to be used instead of inferring them from a sample of the rows.
+    /// When Polars tries to parse dates, the String columns are left to Polars
+    /// unless all the date columns were inferred, as they may be dates.
+    #[cfg(feature = "polars")]
+    pub fn polars_dtypes(&self, try_parsedates: bool) -> polars::prelude::Schema {
+        use polars::prelude::{DataType, Field};
+
+        let string_dtypes = !try_parsedates || self.all_dates_inferred;
+        self.columns
+            .iter()
+            .filter_map(|c| {
+                let dtype = match c.field_type.as_str() {
+                    "Integer" => DataType::Int64,
+                    "Float" => DataType::Float64,
+                    "String" if string_dtypes => DataType::String,
+                    // let Polars infer NULL, Boolean & date columns, as their
+                    // values are not necessarily in a format Polars can parse
+                    _ => return None,
+                };
+                Some(Field::new(&c.field, dtype))
+            })
+            .collect()
+    }
+}
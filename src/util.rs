@@ -1365,6 +1365,21 @@ pub fn decompress_snappy_file(
     Ok(format!("{}", decompressed_filepath.display()))
 }
 
+/// decompress a snappy, gzip, zstd or bzip2 compressed file to tmpdir,
+/// inferring the compression codec from its file extension
+pub fn decompress_file(path: &Path, tmpdir: &tempfile::TempDir) -> Result<String, CliError> {
+    let codec = config::Codec::from_path(path);
+    let mut decoder = codec.decoder(std::fs::File::open(path)?)?;
+    let file_stem = path.file_stem().unwrap().to_str().unwrap();
+    let decompressed_filepath = tmpdir
+        .path()
+        .join(format!("qsv__{file_stem}__qsv_temp_decompressed"));
+    let mut decompressed_file = std::fs::File::create(decompressed_filepath.clone())?;
+    std::io::copy(&mut decoder, &mut decompressed_file)?;
+    decompressed_file.flush()?;
+    Ok(format!("{}", decompressed_filepath.display()))
+}
+
 /// downloads a file from a url and saves it to a path
 /// if show_progress is true, a progress bar will be shown
 /// if custom_user_agent is Some, it will be used as the user agent
@@ -1406,6 +1421,10 @@ pub async fn download_file(
     };
 
     let res = client.get(url).send().await?;
+    // don't save an HTTP error page as the downloaded file
+    if res.status().is_client_error() || res.status().is_server_error() {
+        return fail_clierror!("Cannot download {url}: HTTP status {}.", res.status());
+    }
 
     let total_size = match res.content_length() {
         Some(l) => l,
@@ -1513,7 +1532,8 @@ pub fn isutf8_file(path: &Path) -> Result<bool, CliError> {
 /// If the input is a file with the extension ".infile-list", read the file & add each line as a
 /// file to the input.
 /// If the input is a file, add the file to the input.
-/// If the input are snappy compressed files, uncompress them before adding them to the input.
+/// If the input are compressed files (snappy, gzip, zstd or bzip2), uncompress them before
+/// adding them to the input.
 pub fn process_input(
     mut arg_input: Vec<PathBuf>,
     tmpdir: &tempfile::TempDir,
@@ -1604,14 +1624,14 @@ pub fn process_input(
             return fail_clierror!("Input file '{}' does not exist", path.display());
         }
 
-        // is the input file snappy compressed?
-        if path.extension().and_then(std::ffi::OsStr::to_str) == Some("sz") {
+        // is the input file compressed?
+        if config::Codec::from_path(&path).is_compressed() {
             // if so, decompress the file
-            let decompressed_filepath = decompress_snappy_file(&path, tmpdir)?;
+            let decompressed_filepath = decompress_file(&path, tmpdir)?;
 
             // rename the decompressed file to the original filename, but still
             // inside the temp directory. this is so that the decompressed file can be
-            // processed as if it was the original file without the "sz" extension
+            // processed as if it was the original file without the compression extension
             let original_filepath = path.with_extension("");
             // safety: we know the path has a filename
             let original_filename = original_filepath.file_name().unwrap();
@@ -1845,3 +1865,59 @@ pub fn write_json_record<W: std::io::Write>(
     }
     Ok(write!(json_wtr, "}}")?)
 }
+
+/// Spools sparse records, i.e. their (column index, value) pairs, to a temp file,
+/// so commands that discover their columns while reading their input (e.g. json
+/// & xml) can write CSV whose headers are the union of the columns of all the
+/// records, in order of first appearance, without keeping the records in memory.
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+pub struct RecordSpool {
+    spool: BufWriter<File>,
+}
+
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+impl RecordSpool {
+    pub fn new() -> CliResult<RecordSpool> {
+        Ok(RecordSpool {
+            spool: BufWriter::with_capacity(DEFAULT_WTR_BUFFER_CAPACITY, tempfile::tempfile()?),
+        })
+    }
+
+    /// spool a record, one JSON array of (column index, value) pairs per line
+    pub fn push(&mut self, record: &[(usize, String)]) -> CliResult<()> {
+        serde_json::to_writer(&mut self.spool, record)?;
+        self.spool.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// rewind the spool & write the headers and the spooled records as CSV,
+    /// with empty fields for the columns a record doesn't have
+    pub fn write_csv<W: Write>(
+        self,
+        headers: &indexmap::IndexSet<String>,
+        wtr: &mut csv::Writer<W>,
+    ) -> CliResult<()> {
+        use std::io::{BufRead, Seek};
+
+        let mut spool = self
+            .spool
+            .into_inner()
+            .map_err(std::io::IntoInnerError::into_error)?;
+        spool.rewind()?;
+        let spool = BufReader::with_capacity(config::DEFAULT_RDR_BUFFER_CAPACITY, spool);
+
+        if !headers.is_empty() {
+            wtr.write_record(headers)?;
+        }
+        let mut row = vec![String::new(); headers.len()];
+        for line in spool.lines() {
+            let record: Vec<(usize, String)> = serde_json::from_str(&line?)?;
+            row.iter_mut().for_each(String::clear);
+            for (i, value) in record {
+                row[i] = value;
+            }
+            wtr.write_record(&row)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use newline_converter::dos2unix;
+
+use crate::workdir::Workdir;
+
+fn compress_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["Col1", "Description"],
+        svec![
+            "1",
+            "The quick brown fox jumped over the lazy dog by the zigzag quarry site."
+        ],
+        svec!["2", "メアリーは小さな羊を持っていた"],
+        svec!["3", "I think, therefore I am."],
+        svec!["4", "終極問題的答案是42。"],
+        svec!["5", "I'm Batman."],
+    ]
+}
+
+fn compress_roundtrip(codec: &str, extension: &str) {
+    let wrk = Workdir::new(&format!("compress_roundtrip_{codec}"));
+    let thedata = compress_data();
+    wrk.create("in.csv", thedata.clone());
+
+    let out_file = wrk
+        .path(&format!("out.csv.{extension}"))
+        .to_string_lossy()
+        .to_string();
+
+    let mut cmd = wrk.command("compress");
+    cmd.args(["--codec", codec])
+        .arg("in.csv")
+        .args(["--output", &out_file]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("--decompress").arg(&out_file);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, thedata);
+    wrk.assert_success(&mut cmd);
+
+    // other commands transparently decompress the file based on its extension
+    let mut cmd = wrk.command("select");
+    cmd.arg("Col1").arg(&out_file);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["Col1"],
+        svec!["1"],
+        svec!["2"],
+        svec!["3"],
+        svec!["4"],
+        svec!["5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn compress_roundtrip_gzip() {
+    compress_roundtrip("gzip", "gz");
+}
+
+#[test]
+fn compress_roundtrip_zstd() {
+    compress_roundtrip("zstd", "zst");
+}
+
+#[test]
+fn compress_roundtrip_bzip2() {
+    compress_roundtrip("bzip2", "bz2");
+}
+
+#[test]
+fn compress_roundtrip_snappy() {
+    compress_roundtrip("snappy", "sz");
+}
+
+#[test]
+fn compress_codec_from_output_extension() {
+    let wrk = Workdir::new("compress_codec_from_output_extension");
+    wrk.create("in.csv", compress_data());
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("in.csv").args(["--output", "out.csv.zst"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("--check")
+        .args(["--codec", "zstd"])
+        .arg("out.csv.zst");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("--check")
+        .args(["--codec", "gzip"])
+        .arg("out.csv.zst");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn compress_check_not_compressed() {
+    let wrk = Workdir::new("compress_check_not_compressed");
+    wrk.create("in.csv", compress_data());
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("--check").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn compress_validate() {
+    let wrk = Workdir::new("compress_validate");
+    wrk.create("in.csv", compress_data());
+
+    let mut cmd = wrk.command("compress");
+    cmd.args(["--codec", "bzip2"])
+        .arg("in.csv")
+        .args(["--output", "out.csv.bz2"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("--validate").arg("out.csv.bz2");
+    wrk.assert_success(&mut cmd);
+
+    // truncate the compressed file, so its no longer valid
+    let compressed = std::fs::read(wrk.path("out.csv.bz2")).unwrap();
+    std::fs::write(
+        wrk.path("truncated.csv.bz2"),
+        &compressed[..compressed.len() / 2],
+    )
+    .unwrap();
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("--validate").arg("truncated.csv.bz2");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn compress_gzip_output_transparent() {
+    let wrk = Workdir::new("compress_gzip_output_transparent");
+    let test_file = wrk.load_test_file("boston311-100.csv");
+
+    // commands transparently compress their output based on the --output extension
+    let mut cmd = wrk.command("select");
+    cmd.arg("1-")
+        .arg(test_file)
+        .args(["--output", "out.csv.gz"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("--decompress").arg("out.csv.gz");
+    let got: String = wrk.stdout(&mut cmd);
+
+    let expected = wrk.load_test_resource("boston311-100.csv");
+    assert_eq!(dos2unix(&got), dos2unix(&expected).trim_end());
+}
+
+#[test]
+fn compress_zstd_output_finished() {
+    let wrk = Workdir::new("compress_zstd_output_finished");
+    wrk.create("in.csv", compress_data());
+
+    // the compressed stream is finished when the command flushes its output
+    let mut cmd = wrk.command("select");
+    cmd.arg("Col1")
+        .arg("in.csv")
+        .args(["--output", "out.csv.zst"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("compress");
+    cmd.arg("--validate").arg("out.csv.zst");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("count");
+    cmd.arg("out.csv.zst");
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "5");
+}
+
+#[test]
+fn compress_stdin_magic_bytes() {
+    let wrk = Workdir::new("compress_stdin_magic_bytes");
+    wrk.create("in.csv", compress_data());
+
+    let mut cmd = wrk.command("compress");
+    cmd.args(["--codec", "gzip"])
+        .arg("in.csv")
+        .args(["--output", "in.csv.gz"]);
+    wrk.assert_success(&mut cmd);
+
+    // compressed stdin is detected by its magic bytes
+    let mut cmd = wrk.command("count");
+    cmd.stdin(std::fs::File::open(wrk.path("in.csv.gz")).unwrap());
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "5");
+}
+
+#[test]
+fn compress_stdin_bzh_csv() {
+    let wrk = Workdir::new("compress_stdin_bzh_csv");
+    wrk.create(
+        "in.csv",
+        vec![svec!["BZh_code", "name"], svec!["1", "a"], svec!["2", "b"]],
+    );
+
+    // plain CSV starting with "BZh" is not mistaken for bzip2 data
+    let mut cmd = wrk.command("count");
+    cmd.stdin(std::fs::File::open(wrk.path("in.csv")).unwrap());
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "2");
+}
+
+#[test]
+fn compress_invalid_level() {
+    let wrk = Workdir::new("compress_invalid_level");
+    wrk.create("in.csv", compress_data());
+
+    let mut cmd = wrk.command("compress");
+    cmd.args(["--codec", "bzip2", "--level", "0"])
+        .arg("in.csv")
+        .args(["--output", "out.csv.bz2"]);
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid Bzip2 compression level 0. It must be from 1 to 9.\n"
+    );
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("compress");
+    cmd.args(["--codec", "gzip", "--level", "10"])
+        .arg("in.csv")
+        .args(["--output", "out.csv.gz"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn compress_stdin_summary() {
+    let wrk = Workdir::new("compress_stdin_summary");
+    wrk.create("in.csv", compress_data());
+    let input_bytes = std::fs::metadata(wrk.path("in.csv")).unwrap().len();
+
+    // the summary uses the number of bytes read from stdin
+    let mut cmd = wrk.command("compress");
+    cmd.args(["--output", "out.csv.gz"])
+        .stdin(std::fs::File::open(wrk.path("in.csv")).unwrap());
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.contains(&format!("Decompressed bytes: {input_bytes} B")));
+    assert!(!got.contains("NaN") && !got.contains("inf"));
+}
@@ -287,3 +287,94 @@ fn dedup_not_sorted2() {
     let got: String = wrk.output_stderr(&mut cmd);
     assert!(got.contains("Aborting! Input not sorted!"));
 }
+
+fn setup_external(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["20", "b"],
+            svec!["10", "a"],
+            svec!["20", "B"],
+            svec!["10.0", "c"],
+            svec!["1", "d"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn dedup_external() {
+    let wrk = setup_external("dedup_external");
+    let mut cmd = wrk.command("dedup");
+    cmd.arg("--external")
+        .args(["--select", "id"])
+        .args(["--dupes-output", "dupes.csv"])
+        .arg("in.csv");
+
+    // the first occurrences are kept, in the input order
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["20", "b"],
+        svec!["10", "a"],
+        svec!["10.0", "c"],
+        svec!["1", "d"],
+    ];
+    assert_eq!(got, expected);
+
+    let got_dupes = wrk.read_to_string("dupes.csv");
+    assert_eq!(got_dupes, "id,name\n20,B\n");
+
+    let got_count = wrk.output_stderr(&mut cmd);
+    assert_eq!(got_count, "1\n");
+}
+
+#[test]
+fn dedup_external_numeric() {
+    let wrk = setup_external("dedup_external_numeric");
+    let mut cmd = wrk.command("dedup");
+    cmd.arg("--external")
+        .arg("--numeric")
+        .args(["--select", "id"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["20", "b"],
+        svec!["10", "a"],
+        svec!["1", "d"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn dedup_external_ignore_case() {
+    let wrk = setup_external("dedup_external_ignore_case");
+    let mut cmd = wrk.command("dedup");
+    cmd.arg("--external")
+        .arg("--ignore-case")
+        .args(["--select", "id,name"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["20", "b"],
+        svec!["10", "a"],
+        svec!["10.0", "c"],
+        svec!["1", "d"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn dedup_external_sorted() {
+    let wrk = setup_external("dedup_external_sorted");
+    let mut cmd = wrk.command("dedup");
+    cmd.arg("--external").arg("--sorted").arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -467,6 +467,104 @@ diffresult;h1;h2;h3
     assert_eq!(got.as_str(), expected);
 }
 
+fn setup_format(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "left.csv",
+        vec![
+            svec!["id", "name", "age"],
+            svec!["1", "alice", "30"],
+            svec!["2", "bob", "40"],
+            svec!["3", "carol", "50"],
+        ],
+    );
+    wrk.create(
+        "right.csv",
+        vec![
+            svec!["id", "name", "age"],
+            svec!["1", "alice", "31"],
+            svec!["3", "carol", "50"],
+            svec!["4", "dave", "20"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+fn diff_format_changes() {
+    let wrk = setup_format("diff_format_changes");
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--format", "changes"])
+        .args(["--sort-columns", "0"])
+        .arg("left.csv")
+        .arg("right.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "change", "field", "old_value", "new_value"],
+        svec!["1", "modified", "age", "30", "31"],
+        svec!["2", "removed", "", "", ""],
+        svec!["4", "added", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_format_jsonl() {
+    let wrk = setup_format("diff_format_jsonl");
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--format", "jsonl"])
+        .args(["--sort-columns", "0"])
+        .arg("left.csv")
+        .arg("right.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"op":"modify","key":{"id":"1"},"changes":{"age":{"old":"30","new":"31"}}}
+{"op":"remove","key":{"id":"2"},"record":{"id":"2","name":"bob","age":"40"}}
+{"op":"add","key":{"id":"4"},"record":{"id":"4","name":"dave","age":"20"}}"#;
+    assert_eq!(dos2unix(&got), expected);
+}
+
+#[test]
+fn diff_format_json() {
+    let wrk = setup_format("diff_format_json");
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--format", "json"])
+        .args(["--sort-columns", "0"])
+        .arg("left.csv")
+        .arg("right.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let got: serde_json::Value = serde_json::from_str(&got).unwrap();
+    assert_eq!(got.as_array().unwrap().len(), 3);
+    assert_eq!(got[0]["op"], "modify");
+    assert_eq!(got[0]["changes"]["age"]["new"], "31");
+}
+
+#[test]
+fn diff_format_summary() {
+    let wrk = setup_format("diff_format_summary");
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--format", "summary"])
+        .arg("left.csv")
+        .arg("right.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["added", "removed", "modified"], svec!["1", "1", "1"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_format_invalid() {
+    let wrk = setup_format("diff_format_invalid");
+    let mut cmd = wrk.command("diff");
+    cmd.args(["--format", "patch"])
+        .arg("left.csv")
+        .arg("right.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
 fn create_file_with_delim(wrk: &Workdir, file_path_new: &str, file_path: &str, delimiter: u8) {
     let mut select_cmd = wrk.command("select");
     select_cmd.args(["1-", file_path]);
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use crate::workdir::Workdir;
 
 #[test]
@@ -1617,3 +1619,166 @@ fn excel_formula_empty_string_value() {
 
     wrk.assert_success(&mut cmd);
 }
+
+#[test]
+fn excel_header_row_auto() {
+    let wrk = Workdir::new("excel_header_row_auto");
+
+    let xlsx_file = wrk.load_test_file("excel-tables.xlsx");
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["--header-row", "auto"]).arg(xlsx_file);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "product", "units"],
+        svec!["North", "Widget", "10"],
+        svec!["South", "Gadget", "25"],
+        svec!["East", "Widget", "7"],
+    ];
+    assert_eq!(got, expected);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "3 3-column rows exported from \"Report\" sheet\n");
+}
+
+#[test]
+fn excel_header_row_number() {
+    let wrk = Workdir::new("excel_header_row_number");
+
+    let xlsx_file = wrk.load_test_file("excel-tables.xlsx");
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["--header-row", "4"]).arg(xlsx_file);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "product", "units"],
+        svec!["North", "Widget", "10"],
+        svec!["South", "Gadget", "25"],
+        svec!["East", "Widget", "7"],
+    ];
+    assert_eq!(got, expected);
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn excel_header_row_outside_sheet() {
+    let wrk = Workdir::new("excel_header_row_outside_sheet");
+
+    let xlsx_file = wrk.load_test_file("excel-tables.xlsx");
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["--header-row", "9"]).arg(xlsx_file);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Header row 9 is not one of the exported rows (1-7).\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn excel_table() {
+    let wrk = Workdir::new("excel_table");
+
+    let xlsx_file = wrk.load_test_file("excel-tables.xlsx");
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["--table", "salestable"]).arg(xlsx_file);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "product", "units"],
+        svec!["North", "Widget", "10"],
+        svec!["South", "Gadget", "25"],
+        svec!["East", "Widget", "7"],
+    ];
+    assert_eq!(got, expected);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "3 3-column rows exported from \"SalesTable\" table\n");
+}
+
+#[test]
+fn excel_table_not_found() {
+    let wrk = Workdir::new("excel_table_not_found");
+
+    let xlsx_file = wrk.load_test_file("excel-tables.xlsx");
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["--table", "Sales"]).arg(xlsx_file);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "Table \"Sales\" not found. Available tables: SalesTable, RegionCodes\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn excel_table_ods() {
+    let wrk = Workdir::new("excel_table_ods");
+
+    let ods_file = wrk.load_test_file("excel-ods.ods");
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["--table", "Table1"]).arg(ods_file);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --table is only supported for xlsx and xlsm files.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn excel_all_sheets() {
+    let wrk = Workdir::new("excel_all_sheets");
+
+    let xlsx_file = wrk.load_test_file("excel-tables.xlsx");
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["--all-sheets", "out", "--header-row", "auto"])
+        .arg(xlsx_file);
+
+    let got = wrk.output_stderr(&mut cmd);
+    let expected = format!(
+        "3 3-column rows exported from \"Report\" sheet to {}\n2 2-column rows exported from \
+         \"Regions\" sheet to {}\n\"Empty\" sheet skipped: it's empty\n",
+        Path::new("out").join("Report.csv").display(),
+        Path::new("out").join("Regions.csv").display()
+    );
+    assert_eq!(got, expected);
+
+    let got = wrk.read_to_string("out/Report.csv");
+    assert_eq!(
+        got,
+        "region,product,units\nNorth,Widget,10\nSouth,Gadget,25\nEast,Widget,7\n"
+    );
+    let got = wrk.read_to_string("out/Regions.csv");
+    assert_eq!(got, "code,name\nN,North\nS,South\n");
+    assert!(!wrk.path("out/Empty.csv").exists());
+}
+
+#[test]
+fn excel_all_sheets_filename() {
+    let wrk = Workdir::new("excel_all_sheets_filename");
+
+    let xlsx_file = wrk.load_test_file("excel-tables.xlsx");
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["--all-sheets", "out", "--filename", "sales_{}.tsv"])
+        .args(["--delimiter", "\t", "--quiet"])
+        .arg(xlsx_file);
+    wrk.assert_success(&mut cmd);
+
+    let got = wrk.read_to_string("out/sales_Regions.tsv");
+    assert_eq!(got, "code\tname\nN\tNorth\nS\tSouth\n");
+    // without --header-row, the title is the header row
+    let got = wrk.read_to_string("out/sales_Report.tsv");
+    assert!(got.starts_with("Quarterly Sales Report\t\t\nPrepared by Finance\t\t\n"));
+}
@@ -24,3 +24,86 @@ fn extsort() {
 
     assert_eq!(dos2unix(&sorted_output), dos2unix(&expected_csv));
 }
+
+#[test]
+fn extsort_select_quoted_newlines() {
+    let wrk = Workdir::new("extsort_select_quoted_newlines");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "note"],
+            svec!["3", "third"],
+            svec!["1", "first\nline"],
+            svec!["2", "second"],
+        ],
+    );
+
+    let mut cmd = wrk.command("extsort");
+    cmd.args(["--select", "id"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "note"],
+        svec!["1", "first\nline"],
+        svec!["2", "second"],
+        svec!["3", "third"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn extsort_numeric_reverse() {
+    let wrk = Workdir::new("extsort_numeric_reverse");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name", "amount"],
+            svec!["a", "9"],
+            svec!["b", "100"],
+            svec!["c", "20.5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("extsort");
+    cmd.args(["--select", "amount"])
+        .arg("--numeric")
+        .arg("--reverse")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "amount"],
+        svec!["b", "100"],
+        svec!["c", "20.5"],
+        svec!["a", "9"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn extsort_by_spec() {
+    let wrk = Workdir::new("extsort_by_spec");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["country", "revenue"],
+            svec!["US", "20"],
+            svec!["CA", "9"],
+            svec!["US", "100"],
+            svec!["CA", "50"],
+        ],
+    );
+
+    let mut cmd = wrk.command("extsort");
+    cmd.args(["--by", "country,revenue:desc:n"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "revenue"],
+        svec!["CA", "50"],
+        svec!["CA", "9"],
+        svec!["US", "100"],
+        svec!["US", "20"],
+    ];
+    assert_eq!(got, expected);
+}
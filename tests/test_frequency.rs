@@ -358,6 +358,121 @@ fn frequency_select() {
     assert_eq!(got, expected);
 }
 
+fn setup_stats_cache(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "color"],
+            svec!["1", "red"],
+            svec!["2", "blue"],
+            svec!["3", "red"],
+            svec!["4", "red"],
+        ],
+    );
+
+    // create the stats cache with the cardinalities
+    let mut cmd = wrk.command("stats");
+    cmd.arg("in.csv")
+        .arg("--cardinality")
+        .args(["--cache-threshold", "1"]);
+    wrk.assert_success(&mut cmd);
+    wrk
+}
+
+#[test]
+fn frequency_stats_cache_all_unique() {
+    let wrk = setup_stats_cache("frequency_stats_cache_all_unique");
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["id", "<ALL_UNIQUE>", "4", "100"],
+        svec!["color", "red", "3", "75"],
+        svec!["color", "blue", "1", "25"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_stats_cache_only_unique_selected() {
+    let wrk = setup_stats_cache("frequency_stats_cache_only_unique_selected");
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv").args(["--select", "id"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["id", "<ALL_UNIQUE>", "4", "100"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_stats_cache_approx() {
+    let wrk = Workdir::new("frequency_stats_cache_approx");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "color"],
+            svec!["1", "red"],
+            svec!["2", "blue"],
+            svec!["3", "red"],
+        ],
+    );
+
+    // the approximate cardinalities of the stats cache are not used, even if
+    // they're equal to the record count
+    let mut cmd = wrk.command("stats");
+    cmd.arg("in.csv")
+        .args(["--cardinality", "--approx"])
+        .args(["--cache-threshold", "1"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv").args(["--select", "id"]);
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort();
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["id", "1", "1", "33.33333"],
+        svec!["id", "2", "1", "33.33333"],
+        svec!["id", "3", "1", "33.33333"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_stats_mode_none() {
+    let wrk = setup_stats_cache("frequency_stats_mode_none");
+    let mut cmd = wrk.command("frequency");
+    cmd.arg("in.csv")
+        .args(["--select", "id"])
+        .args(["--stats-mode", "none"]);
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort();
+    let expected = vec![
+        svec!["field", "value", "count", "percentage"],
+        svec!["id", "1", "1", "25"],
+        svec!["id", "2", "1", "25"],
+        svec!["id", "3", "1", "25"],
+        svec!["id", "4", "1", "25"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn frequency_stats_mode_invalid() {
+    let (wrk, mut cmd) = setup("frequency_stats_mode_invalid");
+    cmd.args(["--stats-mode", "always"]);
+
+    wrk.assert_err(&mut cmd);
+}
+
 // This tests that a frequency table computed by `qsv` is always the same
 // as the frequency table computed in memory.
 #[test]
@@ -0,0 +1,199 @@
+use crate::workdir::Workdir;
+
+fn extract() -> &'static str {
+    "0001Ann       00012.50\n0002Bob Smith 00003.00\r\n\n0003Émilie\n"
+}
+
+#[test]
+fn fwf_widths() {
+    let wrk = Workdir::new("fwf_widths");
+    wrk.create_from_string("extract.txt", extract());
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "4,10,8", "--names", "id,name,amount"])
+        .arg("extract.txt");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "amount"],
+        svec!["0001", "Ann", "00012.50"],
+        svec!["0002", "Bob Smith", "00003.00"],
+        svec!["0003", "Émilie", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fwf_positions() {
+    let wrk = Workdir::new("fwf_positions");
+    wrk.create_from_string(
+        "report.txt",
+        &format!("DAILY EXTRACT\nID  NAME      AMOUNT\n{}", extract()),
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--positions", "15:8,1:4", "--skip-lines", "2", "report.txt"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field_1", "field_2"],
+        svec!["00012.50", "0001"],
+        svec!["00003.00", "0002"],
+        svec!["", "0003"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--positions", "5:10", "--skip-lines", "2", "--no-trim"])
+        .arg("report.txt");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field_1"],
+        svec!["Ann       "],
+        svec!["Bob Smith "],
+        svec!["Émilie"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fwf_layout() {
+    let wrk = Workdir::new("fwf_layout");
+    wrk.create_from_string("extract.txt", extract());
+    wrk.create(
+        "layout.csv",
+        vec![
+            svec!["Name", "Start", "End", "Type"],
+            svec!["amount", "15", "22", "decimal"],
+            svec!["id", "1", "4", "integer"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--layout", "layout.csv", "extract.txt"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["amount", "id"],
+        svec!["00012.50", "0001"],
+        svec!["00003.00", "0002"],
+        svec!["", "0003"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fwf_export() {
+    let wrk = Workdir::new("fwf_export");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "name", "amount"],
+            svec!["0001", "Ann", "12.50"],
+            svec!["0003", "Émilie", "3"],
+        ],
+    );
+    wrk.create(
+        "layout.csv",
+        vec![
+            svec!["name", "width"],
+            svec!["id", "4"],
+            svec!["name", "10"],
+            svec!["amount", "8"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--export", "--layout", "layout.csv"])
+        .args(["in.csv", "-o", "out.txt"]);
+    wrk.assert_success(&mut cmd);
+
+    let got = wrk.read_to_string("out.txt");
+    assert_eq!(got, "0001Ann       12.50   \n0003Émilie    3       \n");
+
+    // and back to CSV
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--layout", "layout.csv", "out.txt"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "amount"],
+        svec!["0001", "Ann", "12.50"],
+        svec!["0003", "Émilie", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fwf_export_overflow() {
+    let wrk = Workdir::new("fwf_export_overflow");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "Ann"],
+            svec!["2", "Bob Smithson"],
+            svec!["30", "Cy"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--export", "--widths", "1,10", "in.csv", "-o", "out.txt"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "2 out of 3 records have fields longer than their column. See \
+         in.csv.validation-errors.tsv.\n"
+    );
+    wrk.assert_err(&mut cmd);
+
+    let report = wrk.read_to_string("in.csv.validation-errors.tsv");
+    assert_eq!(
+        report,
+        "row_number\tfield\terror
+2\tname\tvalue is 12 characters long, but the column is 10 characters wide
+3\tid\tvalue is 2 characters long, but the column is 1 characters wide
+"
+    );
+    let got = wrk.read_to_string("out.txt");
+    assert_eq!(got, "1Ann       \n2Bob Smiths\n3Cy        \n");
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--export", "--truncate", "--widths", "1,10", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "1Ann       \n2Bob Smiths\n3Cy        ");
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn fwf_invalid_spec() {
+    let wrk = Workdir::new("fwf_invalid_spec");
+    wrk.create_from_string("extract.txt", extract());
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "4,10", "--positions", "1:4", "extract.txt"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Specify the columns with one of --widths, --positions or --layout.\n"
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "4,0", "extract.txt"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Invalid width \"0\". Must be a positive integer.\n"
+    );
+
+    let mut cmd = wrk.command("fwf");
+    cmd.args(["--widths", "4,10", "--names", "id", "extract.txt"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "usage error: 1 names given for 2 columns.\n");
+}
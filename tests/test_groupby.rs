@@ -0,0 +1,175 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let sales = vec![
+        svec!["region", "product", "amount"],
+        svec!["east", "a", "10"],
+        svec!["west", "b", "5"],
+        svec!["east", "b", "20"],
+        svec!["west", "a", ""],
+        svec!["east", "a", "30"],
+    ];
+
+    let wrk = Workdir::new(name);
+    wrk.create("sales.csv", sales);
+    wrk
+}
+
+// only keep the given columns of the groupby output, so the tests
+// don't depend on all the stats columns
+fn pick_columns(rows: &[Vec<String>], columns: &[&str]) -> Vec<Vec<String>> {
+    let idxs: Vec<usize> = columns
+        .iter()
+        .map(|col| rows[0].iter().position(|h| h == col).unwrap())
+        .collect();
+    rows.iter()
+        .map(|row| idxs.iter().map(|&i| row[i].clone()).collect())
+        .collect()
+}
+
+#[test]
+fn groupby_select() {
+    let wrk = setup("groupby_select");
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("region")
+        .arg("sales.csv")
+        .args(["--select", "amount"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_columns(
+        &got,
+        &[
+            "region",
+            "count",
+            "field",
+            "type",
+            "sum",
+            "min",
+            "max",
+            "mean",
+            "nullcount",
+            "sparsity",
+        ],
+    );
+    let expected = vec![
+        svec![
+            "region",
+            "count",
+            "field",
+            "type",
+            "sum",
+            "min",
+            "max",
+            "mean",
+            "nullcount",
+            "sparsity"
+        ],
+        svec!["east", "3", "amount", "Integer", "60", "10", "30", "20", "0", "0"],
+        svec!["west", "2", "amount", "Integer", "5", "5", "5", "5", "1", "0.5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_default_selection() {
+    let wrk = setup("groupby_default_selection");
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("region").arg("sales.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_columns(&got, &["region", "count", "field", "type", "min", "max"]);
+    let expected = vec![
+        svec!["region", "count", "field", "type", "min", "max"],
+        svec!["east", "3", "product", "String", "a", "b"],
+        svec!["east", "3", "amount", "Integer", "10", "30"],
+        svec!["west", "2", "product", "String", "a", "b"],
+        svec!["west", "2", "amount", "Integer", "5", "5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_multiple_columns() {
+    let wrk = setup("groupby_multiple_columns");
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("region,product").arg("sales.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_columns(&got, &["region", "product", "count", "field", "sum"]);
+    let expected = vec![
+        svec!["region", "product", "count", "field", "sum"],
+        svec!["east", "a", "2", "amount", "40"],
+        svec!["east", "b", "1", "amount", "20"],
+        svec!["west", "a", "1", "amount", ""],
+        svec!["west", "b", "1", "amount", "5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_cardinality_median() {
+    let wrk = setup("groupby_cardinality_median");
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("region")
+        .arg("sales.csv")
+        .args(["--select", "amount"])
+        .arg("--cardinality")
+        .arg("--median");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_columns(&got, &["region", "median", "cardinality"]);
+    let expected = vec![
+        svec!["region", "median", "cardinality"],
+        svec!["east", "20", "3"],
+        svec!["west", "5", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_external() {
+    let wrk = setup("groupby_external");
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("region").arg("sales.csv").arg("--everything");
+    let expected: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    // a memory limit of zero spills the groups to disk after every row,
+    // so the partial stats of each group are merged back from several runs
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("region")
+        .arg("sales.csv")
+        .arg("--everything")
+        .arg("--external")
+        .args(["--memory-limit", "0"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_indexed_parallel() {
+    let wrk = setup("groupby_indexed_parallel");
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("region").arg("sales.csv").arg("--cardinality");
+    let expected: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    let mut cmd = wrk.command("index");
+    cmd.arg("sales.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("region")
+        .arg("sales.csv")
+        .arg("--cardinality")
+        .args(["--jobs", "2"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_no_value_columns() {
+    let wrk = setup("groupby_no_value_columns");
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("1-").arg("sales.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -301,3 +301,386 @@ fn join_cross_no_headers() {
     ];
     assert_eq!(got, expected);
 }
+
+#[cfg(feature = "apply")]
+fn setup_fuzzy(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "left.csv",
+        vec![
+            svec!["company", "city"],
+            svec!["Acme Corp", "Boston"],
+            svec!["Globex", "Springfield"],
+            svec!["Kwik Mart", "Buffalo"],
+        ],
+    );
+    wrk.create(
+        "right.csv",
+        vec![
+            svec!["name", "id"],
+            svec!["ACME Corp.", "1"],
+            svec!["Acme Co", "2"],
+            svec!["Globex Inc", "3"],
+            svec!["Quik Mart", "4"],
+        ],
+    );
+    wrk
+}
+
+#[test]
+#[cfg(feature = "apply")]
+fn join_fuzzy() {
+    let wrk = setup_fuzzy("join_fuzzy");
+    let mut cmd = wrk.command("join");
+    cmd.args(["--fuzzy", "damerau-levenshtein:0.7"])
+        .arg("--ignore-case")
+        .args(["company", "left.csv", "name", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["company", "city", "name", "id", "fuzzy_score"],
+        svec!["Acme Corp", "Boston", "ACME Corp.", "1", "0.9"],
+        svec!["Kwik Mart", "Buffalo", "Quik Mart", "4", "0.7778"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[cfg(feature = "apply")]
+fn join_fuzzy_left_top_k() {
+    let wrk = setup_fuzzy("join_fuzzy_left_top_k");
+    let mut cmd = wrk.command("join");
+    cmd.args(["--fuzzy", "damerau-levenshtein:0.7"])
+        .args(["--top-k", "2"])
+        .arg("--left")
+        .arg("--ignore-case")
+        .args(["company", "left.csv", "name", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["company", "city", "name", "id", "fuzzy_score"],
+        svec!["Acme Corp", "Boston", "ACME Corp.", "1", "0.9"],
+        svec!["Acme Corp", "Boston", "Acme Co", "2", "0.7778"],
+        svec!["Globex", "Springfield", "", "", ""],
+        svec!["Kwik Mart", "Buffalo", "Quik Mart", "4", "0.7778"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[cfg(feature = "apply")]
+fn join_fuzzy_block_prefix() {
+    let wrk = setup_fuzzy("join_fuzzy_block_prefix");
+    let mut cmd = wrk.command("join");
+    cmd.args(["--fuzzy", "damerau-levenshtein:0.7"])
+        .args(["--block", "prefix:1"])
+        .arg("--ignore-case")
+        .args(["company", "left.csv", "name", "right.csv"]);
+
+    // "Kwik Mart" and "Quik Mart" are in different blocks, so they're never compared
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["company", "city", "name", "id", "fuzzy_score"],
+        svec!["Acme Corp", "Boston", "ACME Corp.", "1", "0.9"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[cfg(feature = "apply")]
+fn join_fuzzy_invalid() {
+    let wrk = setup_fuzzy("join_fuzzy_invalid");
+    let mut cmd = wrk.command("join");
+    cmd.args(["--fuzzy", "soundex:0.7"])
+        .args(["company", "left.csv", "name", "right.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--fuzzy", "jaro-winkler:0.7"])
+        .arg("--full")
+        .args(["company", "left.csv", "name", "right.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn join_interval_point() {
+    let wrk = Workdir::new("join_interval_point");
+    wrk.create(
+        "events.csv",
+        vec![
+            svec!["id", "value"],
+            svec!["a", "5"],
+            svec!["b", "15"],
+            svec!["c", "25"],
+            svec!["d", "abc"],
+            svec!["e", "10"],
+        ],
+    );
+    wrk.create(
+        "ranges.csv",
+        vec![
+            svec!["name", "lo", "hi"],
+            svec!["low", "0", "10"],
+            svec!["mid", "10", "20"],
+            svec!["wide", "0", "100"],
+            svec!["reversed", "30", "20"],
+        ],
+    );
+    let mut cmd = wrk.command("join");
+    cmd.args(["--interval", "number"])
+        .args(["value", "events.csv", "lo,hi", "ranges.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "value", "name", "lo", "hi"],
+        svec!["a", "5", "low", "0", "10"],
+        svec!["a", "5", "wide", "0", "100"],
+        svec!["b", "15", "mid", "10", "20"],
+        svec!["b", "15", "wide", "0", "100"],
+        svec!["c", "25", "wide", "0", "100"],
+        svec!["e", "10", "low", "0", "10"],
+        svec!["e", "10", "mid", "10", "20"],
+        svec!["e", "10", "wide", "0", "100"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_interval_date_left() {
+    let wrk = Workdir::new("join_interval_date_left");
+    wrk.create(
+        "logins.csv",
+        vec![
+            svec!["user", "ts"],
+            svec!["ann", "2024-01-15"],
+            svec!["bob", "2024-03-01"],
+            svec!["cat", "2024-02-29 10:30:00"],
+        ],
+    );
+    wrk.create(
+        "windows.csv",
+        vec![
+            svec!["window", "start", "end"],
+            svec!["january", "2024-01-01", "2024-01-31"],
+            svec!["february", "2024-02-01", "2024-02-29 23:59:59"],
+        ],
+    );
+    let mut cmd = wrk.command("join");
+    cmd.args(["--interval", "date", "--left"]);
+    cmd.args(["ts", "logins.csv", "start,end", "windows.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["user", "ts", "window", "start", "end"],
+        svec!["ann", "2024-01-15", "january", "2024-01-01", "2024-01-31"],
+        svec!["bob", "2024-03-01", "", "", ""],
+        svec![
+            "cat",
+            "2024-02-29 10:30:00",
+            "february",
+            "2024-02-01",
+            "2024-02-29 23:59:59"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_interval_overlap() {
+    let wrk = Workdir::new("join_interval_overlap");
+    wrk.create(
+        "bookings.csv",
+        vec![
+            svec!["room", "from", "to"],
+            svec!["r1", "1", "3"],
+            svec!["r2", "4", "6"],
+            svec!["r3", "10", "12"],
+        ],
+    );
+    wrk.create(
+        "tasks.csv",
+        vec![
+            svec!["task", "start", "end"],
+            svec!["t1", "3", "4"],
+            svec!["t2", "7", "9"],
+            svec!["t3", "0", "20"],
+        ],
+    );
+    let mut cmd = wrk.command("join");
+    cmd.args(["--interval", "number"])
+        .args(["from,to", "bookings.csv", "start,end", "tasks.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["room", "from", "to", "task", "start", "end"],
+        svec!["r1", "1", "3", "t1", "3", "4"],
+        svec!["r1", "1", "3", "t3", "0", "20"],
+        svec!["r2", "4", "6", "t1", "3", "4"],
+        svec!["r2", "4", "6", "t3", "0", "20"],
+        svec!["r3", "10", "12", "t3", "0", "20"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_interval_invalid() {
+    let wrk = Workdir::new("join_interval_invalid");
+    wrk.create("a.csv", vec![svec!["x", "y"], svec!["1", "2"]]);
+    wrk.create("b.csv", vec![svec!["x", "y"], svec!["1", "2"]]);
+
+    // <columns2> must select a start and an end column
+    let mut cmd = wrk.command("join");
+    cmd.args(["--interval", "number"])
+        .args(["x", "a.csv", "x", "b.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--interval", "time"])
+        .args(["x", "a.csv", "x,y", "b.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--interval", "number"])
+        .arg("--full")
+        .args(["x", "a.csv", "x,y", "b.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+fn setup_multi(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "people.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "Alice"],
+            svec!["2", "Bob"],
+            svec!["3", "Carol"],
+        ],
+    );
+    wrk.create(
+        "ages.csv",
+        vec![
+            svec!["id", "age"],
+            svec!["1", "30"],
+            svec!["2", "40"],
+            svec!["4", "50"],
+        ],
+    );
+    wrk.create(
+        "cities.csv",
+        vec![
+            svec!["uid", "city"],
+            svec!["2", "Paris"],
+            svec!["1", "Rome"],
+            svec!["1", "Oslo"],
+        ],
+    );
+    wrk.create(
+        "ages2.csv",
+        vec![svec!["id", "age"], svec!["1", "31"], svec!["5", "60"]],
+    );
+    wrk
+}
+
+#[test]
+fn join_three_inputs() {
+    let wrk = setup_multi("join_three_inputs");
+    let mut cmd = wrk.command("join");
+    cmd.args(["id", "people.csv", "id", "ages.csv", "uid", "cities.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "id", "age", "uid", "city"],
+        svec!["1", "Alice", "1", "30", "1", "Rome"],
+        svec!["1", "Alice", "1", "30", "1", "Oslo"],
+        svec!["2", "Bob", "2", "40", "2", "Paris"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_three_inputs_coalesce_keys() {
+    let wrk = setup_multi("join_three_inputs_coalesce_keys");
+    let mut cmd = wrk.command("join");
+    cmd.arg("--coalesce-keys")
+        .args(["id", "people.csv", "id", "ages.csv", "uid", "cities.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "age", "city"],
+        svec!["1", "Alice", "30", "Rome"],
+        svec!["1", "Alice", "30", "Oslo"],
+        svec!["2", "Bob", "40", "Paris"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_full_coalesce_keys() {
+    let wrk = setup_multi("join_full_coalesce_keys");
+    let mut cmd = wrk.command("join");
+    cmd.args(["--full", "--coalesce-keys"])
+        .args(["id", "people.csv", "id", "ages.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "age"],
+        svec!["1", "Alice", "30"],
+        svec!["2", "Bob", "40"],
+        svec!["3", "Carol", ""],
+        svec!["4", "", "50"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_prefixes() {
+    let wrk = setup_multi("join_prefixes");
+    let mut cmd = wrk.command("join");
+    cmd.args(["--left-prefix", "p.", "--right-prefix", "a."])
+        .args(["id", "people.csv", "id", "ages.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["p.id", "p.name", "a.id", "a.age"],
+        svec!["1", "Alice", "1", "30"],
+        svec!["2", "Bob", "2", "40"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_right_suffix_numbered() {
+    let wrk = setup_multi("join_right_suffix_numbered");
+    let mut cmd = wrk.command("join");
+    cmd.args(["--right-suffix", "_{n}"]).args([
+        "id",
+        "people.csv",
+        "id",
+        "ages.csv",
+        "id",
+        "ages2.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "id_2", "age", "id_3", "age_3"],
+        svec!["1", "Alice", "1", "30", "1", "31"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_more_inputs_interval_error() {
+    let wrk = setup_multi("join_more_inputs_interval_error");
+    let mut cmd = wrk.command("join");
+    cmd.args(["--interval", "number"]).args([
+        "id",
+        "people.csv",
+        "id,age",
+        "ages.csv",
+        "id,age",
+        "ages2.csv",
+    ]);
+    wrk.assert_err(&mut cmd);
+}
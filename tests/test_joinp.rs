@@ -1090,3 +1090,125 @@ fn joinp_asof_date_diffcolnames_sqlfilter() {
     ];
     assert_eq!(got, expected);
 }
+
+fn create_interval_files(wrk: &Workdir) {
+    wrk.create(
+        "events.csv",
+        vec![
+            svec!["id", "value"],
+            svec!["a", "5"],
+            svec!["b", "15"],
+            svec!["c", "25"],
+            svec!["d", "abc"],
+            svec!["e", "10"],
+        ],
+    );
+    wrk.create(
+        "ranges.csv",
+        vec![
+            svec!["name", "lo", "hi"],
+            svec!["low", "0", "10"],
+            svec!["mid", "10", "20"],
+            svec!["wide", "0", "100"],
+            svec!["reversed", "30", "20"],
+        ],
+    );
+}
+
+#[test]
+fn joinp_interval_point() {
+    let wrk = Workdir::new("joinp_interval_point");
+    create_interval_files(&wrk);
+
+    let mut cmd = wrk.command("joinp");
+    cmd.args(["--interval", "number"])
+        .args(["value", "events.csv", "lo,hi", "ranges.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "value", "name", "lo", "hi"],
+        svec!["a", "5", "low", "0", "10"],
+        svec!["a", "5", "wide", "0", "100"],
+        svec!["b", "15", "mid", "10", "20"],
+        svec!["b", "15", "wide", "0", "100"],
+        svec!["c", "25", "wide", "0", "100"],
+        svec!["e", "10", "low", "0", "10"],
+        svec!["e", "10", "mid", "10", "20"],
+        svec!["e", "10", "wide", "0", "100"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn joinp_interval_overlap_left() {
+    let wrk = Workdir::new("joinp_interval_overlap_left");
+    wrk.create(
+        "bookings.csv",
+        vec![
+            svec!["guest", "checkin", "checkout"],
+            svec!["ann", "2024-01-01", "2024-01-05"],
+            svec!["bob", "2024-02-10", "2024-02-12"],
+            svec!["cy", "2024-03-01", "2024-03-03"],
+        ],
+    );
+    wrk.create(
+        "promos.csv",
+        vec![
+            svec!["promo", "start", "end"],
+            svec!["winter", "2024-01-04", "2024-02-10"],
+            svec!["spring", "2024-03-04", "2024-05-31"],
+        ],
+    );
+
+    let mut cmd = wrk.command("joinp");
+    cmd.args(["--interval", "date", "--left"]).args([
+        "checkin,checkout",
+        "bookings.csv",
+        "start,end",
+        "promos.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["guest", "checkin", "checkout", "promo", "start", "end"],
+        svec![
+            "ann",
+            "2024-01-01",
+            "2024-01-05",
+            "winter",
+            "2024-01-04",
+            "2024-02-10"
+        ],
+        svec![
+            "bob",
+            "2024-02-10",
+            "2024-02-12",
+            "winter",
+            "2024-01-04",
+            "2024-02-10"
+        ],
+        svec!["cy", "2024-03-01", "2024-03-03", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn joinp_interval_unsupported_join() {
+    let wrk = Workdir::new("joinp_interval_unsupported_join");
+    create_interval_files(&wrk);
+
+    let mut cmd = wrk.command("joinp");
+    cmd.args(["--interval", "number", "--full"]).args([
+        "value",
+        "events.csv",
+        "lo,hi",
+        "ranges.csv",
+    ]);
+
+    wrk.assert_err(&mut cmd);
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --interval only supports inner and --left joins.\n"
+    );
+}
@@ -0,0 +1,193 @@
+use crate::workdir::Workdir;
+
+fn orders() -> &'static str {
+    r#"[
+    {"id": 1, "customer": {"name": "Ann", "address": {"city": "Oslo"}}, "items": ["pen", "ink"]},
+    {"id": 2, "customer": {"name": "Bob"}, "items": [], "paid": true},
+    {"id": 3, "customer": null, "items": ["pad"], "note": "rush"}
+]"#
+}
+
+#[test]
+fn json_array() {
+    let wrk = Workdir::new("json_array");
+    wrk.create_from_string("orders.json", orders());
+
+    let mut cmd = wrk.command("json");
+    cmd.arg("orders.json");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "id",
+            "customer.name",
+            "customer.address.city",
+            "items",
+            "paid",
+            "customer",
+            "note"
+        ],
+        svec!["1", "Ann", "Oslo", r#"["pen","ink"]"#, "", "", ""],
+        svec!["2", "Bob", "", "[]", "true", "", ""],
+        svec!["3", "", "", r#"["pad"]"#, "", "", "rush"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn json_separator_explode() {
+    let wrk = Workdir::new("json_separator_explode");
+    wrk.create_from_string("orders.json", orders());
+
+    let mut cmd = wrk.command("json");
+    cmd.args(["--separator", "_", "--arrays", "explode"])
+        .arg("orders.json");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "id",
+            "customer_name",
+            "customer_address_city",
+            "items",
+            "paid",
+            "customer",
+            "note"
+        ],
+        svec!["1", "Ann", "Oslo", "pen", "", "", ""],
+        svec!["1", "Ann", "Oslo", "ink", "", "", ""],
+        svec!["2", "Bob", "", "", "true", "", ""],
+        svec!["3", "", "", "pad", "", "", "rush"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn json_explode_objects() {
+    let wrk = Workdir::new("json_explode_objects");
+    wrk.create_from_string(
+        "data.json",
+        r#"[{"order": 1, "lines": [{"sku": "A", "qty": 2}, {"sku": "B", "qty": 1}]}]"#,
+    );
+
+    let mut cmd = wrk.command("json");
+    cmd.args(["--arrays", "explode", "data.json"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["order", "lines.sku", "lines.qty"],
+        svec!["1", "A", "2"],
+        svec!["1", "B", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn json_arrays_index() {
+    let wrk = Workdir::new("json_arrays_index");
+    wrk.create_from_string("orders.json", orders());
+
+    let mut cmd = wrk.command("json");
+    cmd.args(["--arrays", "index", "orders.json"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "id",
+            "customer.name",
+            "customer.address.city",
+            "items.0",
+            "items.1",
+            "paid",
+            "customer",
+            "note"
+        ],
+        svec!["1", "Ann", "Oslo", "pen", "ink", "", "", ""],
+        svec!["2", "Bob", "", "", "", "true", "", ""],
+        svec!["3", "", "", "pad", "", "", "", "rush"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn json_pointer() {
+    let wrk = Workdir::new("json_pointer");
+    wrk.create_from_string(
+        "response.json",
+        r#"{"meta": {"count": 2, "tags": ["x"]}, "data": {"a/b": [{"v": 1}, {"v": 2}]}}"#,
+    );
+
+    let mut cmd = wrk.command("json");
+    cmd.args(["--pointer", "/data/a~1b", "response.json"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["v"], svec!["1"], svec!["2"]];
+    assert_eq!(got, expected);
+
+    // a single object is a single record
+    let mut cmd = wrk.command("json");
+    cmd.args(["--pointer", "/meta", "response.json"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["count", "tags"], svec!["2", r#"["x"]"#]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn json_pointer_array_index() {
+    let wrk = Workdir::new("json_pointer_array_index");
+    wrk.create_from_string(
+        "data.json",
+        r#"{"results": [{"rows": [1, 2]}, {"rows": [3, 4, 5]}]}"#,
+    );
+
+    let mut cmd = wrk.command("json");
+    cmd.args(["--pointer", "/results/1/rows", "data.json"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["value"], svec!["3"], svec!["4"], svec!["5"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn json_pointer_not_found() {
+    let wrk = Workdir::new("json_pointer_not_found");
+    wrk.create_from_string("data.json", r#"{"data": []}"#);
+
+    let mut cmd = wrk.command("json");
+    cmd.args(["--pointer", "/rows", "data.json"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "Nothing found at /rows.\n");
+}
+
+#[test]
+fn json_invalid() {
+    let wrk = Workdir::new("json_invalid");
+    wrk.create_from_string("data.json", r#"[{"a": 1}, {"a": "#);
+
+    let mut cmd = wrk.command("json");
+    cmd.arg("data.json");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("json");
+    cmd.args(["--arrays", "flatten", "data.json"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+#[cfg(feature = "fetch")]
+fn json_jql() {
+    let wrk = Workdir::new("json_jql");
+    wrk.create_from_string(
+        "response.json",
+        r#"{"data": {"items": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]}}"#,
+    );
+
+    let mut cmd = wrk.command("json");
+    cmd.args(["--jql", r#""data""items""#, "response.json"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name"], svec!["1", "a"], svec!["2", "b"]];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,31 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn lens_stdin() {
+    let wrk = Workdir::new("lens_stdin");
+
+    let mut cmd = wrk.command("lens");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        &*got,
+        "usage error: lens needs a file to browse, it cannot read stdin.\n"
+    );
+}
+
+#[test]
+fn lens_not_a_terminal() {
+    let wrk = Workdir::new("lens_not_a_terminal");
+    wrk.create(
+        "data.csv",
+        vec![svec!["letter", "number"], svec!["a", "1"], svec!["b", "2"]],
+    );
+
+    let mut cmd = wrk.command("lens");
+    cmd.arg("data.csv");
+
+    // the tests capture stdout, so lens bails out before indexing the file
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(&*got, "usage error: lens needs an interactive terminal.\n");
+    assert!(!wrk.path("data.csv.idx").exists());
+}
@@ -0,0 +1,201 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let sales = vec![
+        svec!["region", "quarter", "amount", "units"],
+        svec!["east", "Q2", "10", "1"],
+        svec!["east", "Q1", "5", "2"],
+        svec!["west", "Q1", "7", "3"],
+        svec!["east", "Q1", "3", "4"],
+        svec!["west", "Q2", "8", "5"],
+    ];
+
+    let wrk = Workdir::new(name);
+    wrk.create("sales.csv", sales);
+    wrk
+}
+
+#[test]
+fn pivot_sum() {
+    let wrk = setup("pivot_sum");
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("quarter")
+        .arg("sales.csv")
+        .args(["--index", "region"])
+        .args(["--values", "amount"])
+        .args(["--agg", "sum"])
+        .arg("--maintain-order");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "Q2", "Q1"],
+        svec!["east", "10", "8"],
+        svec!["west", "8", "7"],
+    ];
+    assert_eq!(got, expected);
+
+    let got_shape = wrk.output_stderr(&mut cmd);
+    assert_eq!(got_shape, "(2, 3)\n");
+}
+
+#[test]
+fn pivot_sort_columns() {
+    let wrk = setup("pivot_sort_columns");
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("quarter")
+        .arg("sales.csv")
+        .args(["--index", "region"])
+        .args(["--values", "amount"])
+        .args(["--agg", "max"])
+        .arg("--sort-columns")
+        .arg("--maintain-order");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "Q1", "Q2"],
+        svec!["east", "5", "10"],
+        svec!["west", "7", "8"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_len() {
+    let wrk = setup("pivot_len");
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("quarter")
+        .arg("sales.csv")
+        .args(["--index", "region"])
+        .args(["--values", "amount"])
+        .args(["--agg", "len"])
+        .arg("--sort-columns")
+        .arg("--maintain-order");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "Q1", "Q2"],
+        svec!["east", "2", "1"],
+        svec!["west", "1", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_default_index() {
+    let wrk = setup("pivot_default_index");
+    let mut cmd = wrk.command("pivot");
+    // region and units are not used, so they're the index columns
+    cmd.arg("quarter")
+        .arg("sales.csv")
+        .args(["--values", "amount"])
+        .arg("--sort-columns")
+        .arg("--maintain-order");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "units", "Q1", "Q2"],
+        svec!["east", "1", "", "10"],
+        svec!["east", "2", "5", ""],
+        svec!["west", "3", "7", ""],
+        svec!["east", "4", "3", ""],
+        svec!["west", "5", "", "8"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_multiple_values() {
+    let wrk = setup("pivot_multiple_values");
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("quarter")
+        .arg("sales.csv")
+        .args(["--index", "region"])
+        .args(["--agg", "sum"])
+        .arg("--maintain-order");
+
+    // amount and units are the value columns, each pivoted on the two quarters
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 3);
+    assert_eq!(got[0].len(), 5);
+    assert_eq!(got[0][0], "region");
+}
+
+#[test]
+fn pivot_no_index_or_values() {
+    let wrk = setup("pivot_no_index_or_values");
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("quarter").arg("sales.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn pivot_invalid_agg() {
+    let wrk = setup("pivot_invalid_agg");
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("quarter")
+        .arg("sales.csv")
+        .args(["--index", "region"])
+        .args(["--agg", "mode"]);
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn pivot_unpivot() {
+    let wrk = Workdir::new("pivot_unpivot");
+    wrk.create(
+        "wide.csv",
+        vec![
+            svec!["region", "Q1", "Q2"],
+            svec!["east", "8", "10"],
+            svec!["west", "7", "8"],
+        ],
+    );
+
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("--unpivot")
+        .arg("Q1,Q2")
+        .arg("wide.csv")
+        .args(["--index", "region"])
+        .args(["--variable-name", "quarter"])
+        .args(["--value-name", "amount"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "quarter", "amount"],
+        svec!["east", "Q1", "8"],
+        svec!["west", "Q1", "7"],
+        svec!["east", "Q2", "10"],
+        svec!["west", "Q2", "8"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn pivot_parquet_format() {
+    let wrk = setup("pivot_parquet_format");
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("quarter")
+        .arg("sales.csv")
+        .args(["--index", "region"])
+        .args(["--values", "amount"])
+        .args(["--agg", "sum"])
+        .arg("--sort-columns")
+        .arg("--maintain-order")
+        .args(["--format", "parquet"])
+        .args(["--output", "pivot.parquet"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("SKIP_INPUT")
+        .arg("select * from read_parquet('pivot.parquet')");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["region", "Q1", "Q2"],
+        svec!["east", "8", "10"],
+        svec!["west", "7", "8"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -483,3 +483,190 @@ fn sample_percentage_negative_sample_size_error() {
 
     wrk.assert_err(&mut cmd);
 }
+
+fn survey_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "region", "weight"],
+        svec!["1", "north", "0"],
+        svec!["2", "south", "1.5"],
+        svec!["3", "north", "0"],
+        svec!["4", "south", "0"],
+        svec!["5", "north", "2"],
+        svec!["6", "south", "x"],
+    ]
+}
+
+#[test]
+fn sample_systematic_first() {
+    let wrk = Workdir::new("sample_systematic_first");
+    wrk.create("in.csv", survey_data());
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--systematic", "first"]).arg("2").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "region", "weight"],
+        svec!["1", "north", "0"],
+        svec!["3", "north", "0"],
+        svec!["5", "north", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sample_systematic_indexed_percentage() {
+    let wrk = Workdir::new("sample_systematic_indexed_percentage");
+    wrk.create_indexed("in.csv", survey_data());
+
+    // 0.5 is every 2nd record
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--systematic", "first"]).arg("0.5").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "region", "weight"],
+        svec!["1", "north", "0"],
+        svec!["3", "north", "0"],
+        svec!["5", "north", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sample_systematic_random() {
+    let wrk = Workdir::new("sample_systematic_random");
+    wrk.create("in.csv", survey_data());
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--systematic", "random"])
+        .args(["--seed", "42"])
+        .arg("3")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 3);
+    let first: usize = got[1][0].parse().unwrap();
+    let second: usize = got[2][0].parse().unwrap();
+    assert!(first <= 3);
+    assert_eq!(second, first + 3);
+}
+
+#[test]
+fn sample_bernoulli() {
+    let wrk = Workdir::new("sample_bernoulli");
+    wrk.create("in.csv", survey_data());
+
+    let mut cmd = wrk.command("sample");
+    cmd.arg("--bernoulli")
+        .args(["--seed", "42"])
+        .arg("0.5")
+        .arg("in.csv");
+
+    // the sampled records are in their input order
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let ids: Vec<usize> = got[1..].iter().map(|row| row[0].parse().unwrap()).collect();
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let mut cmd = wrk.command("sample");
+    cmd.arg("--bernoulli").arg("5").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn sample_stratified() {
+    let wrk = Workdir::new("sample_stratified");
+    wrk.create("in.csv", survey_data());
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--stratified", "region"])
+        .args(["--seed", "42"])
+        .arg("2")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let regions: Vec<&str> = got[1..].iter().map(|row| row[1].as_str()).collect();
+    assert_eq!(regions, vec!["north", "north", "south", "south"]);
+}
+
+#[test]
+fn sample_weighted() {
+    let wrk = Workdir::new("sample_weighted");
+    wrk.create("in.csv", survey_data());
+
+    // only two records have a positive weight
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--weighted", "weight"])
+        .args(["--seed", "42"])
+        .arg("3")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "region", "weight"],
+        svec!["2", "south", "1.5"],
+        svec!["5", "north", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sample_stratified_weighted_indexed() {
+    let wrk = Workdir::new("sample_stratified_weighted_indexed");
+    wrk.create("in.csv", survey_data());
+    wrk.create_indexed("indexed.csv", survey_data());
+
+    // seeking to the sampled records with the index samples the same records
+    for method in ["--stratified", "--weighted"] {
+        let column = if method == "--stratified" {
+            "region"
+        } else {
+            "weight"
+        };
+        let mut cmd = wrk.command("sample");
+        cmd.args([method, column])
+            .args(["--seed", "42"])
+            .arg("2")
+            .arg("in.csv");
+        let expected: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+        let mut cmd = wrk.command("sample");
+        cmd.args([method, column])
+            .args(["--seed", "42"])
+            .arg("2")
+            .arg("indexed.csv");
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        assert_eq!(got, expected);
+    }
+}
+
+#[test]
+fn sample_cluster() {
+    let wrk = Workdir::new("sample_cluster");
+    wrk.create("in.csv", survey_data());
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--cluster", "region"])
+        .args(["--seed", "42"])
+        .arg("1")
+        .arg("in.csv");
+
+    // all the records of one of the two regions
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 4);
+    assert!(got[1..].iter().all(|row| row[1] == got[1][1]));
+}
+
+#[test]
+fn sample_multiple_methods_error() {
+    let wrk = Workdir::new("sample_multiple_methods_error");
+    wrk.create("in.csv", survey_data());
+
+    let mut cmd = wrk.command("sample");
+    cmd.arg("--bernoulli")
+        .args(["--cluster", "region"])
+        .arg("0.5")
+        .arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
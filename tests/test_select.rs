@@ -193,3 +193,67 @@ select_test_err!(select_err_regex_nomatch, "/nomatch/");
 select_test_err!(select_err_regex_invalid, "/?/");
 select_test_err!(select_err_regex_empty, "//");
 select_test_err!(select_err_regex_triple_slash, "///");
+
+#[cfg(feature = "polars")]
+fn create_columnar_test_file(wrk: &Workdir, format: &str, output: &str) {
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["letter", "number", "flag"],
+            svec!["alpha", "13", "true"],
+            svec!["beta", "24", "false"],
+            svec!["gamma", "37", "true"],
+        ],
+    );
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("data.csv")
+        .arg("select * from data")
+        .args(["--format", format])
+        .args(["--output", output]);
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+#[cfg(feature = "polars")]
+fn select_parquet_input() {
+    let wrk = Workdir::new("select_parquet_input");
+    create_columnar_test_file(&wrk, "parquet", "data.parquet");
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("number,letter").arg("data.parquet");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["number", "letter"],
+        svec!["13", "alpha"],
+        svec!["24", "beta"],
+        svec!["37", "gamma"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[cfg(feature = "polars")]
+fn select_arrow_input() {
+    let wrk = Workdir::new("select_arrow_input");
+    create_columnar_test_file(&wrk, "arrow", "data.arrow");
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("flag").arg("data.arrow");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["flag"], svec!["true"], svec!["false"], svec!["true"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[cfg(feature = "polars")]
+fn select_parquet_input_no_headers() {
+    let wrk = Workdir::new("select_parquet_input_no_headers");
+    create_columnar_test_file(&wrk, "parquet", "data.parquet");
+
+    // with --no-headers, the schema's column names are not emitted
+    let mut cmd = wrk.command("select");
+    cmd.arg("1").arg("--no-headers").arg("data.parquet");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["alpha"], svec!["beta"], svec!["gamma"]];
+    assert_eq!(got, expected);
+}
@@ -308,3 +308,104 @@ fn slice_neg_index_withindex() {
 fn slice_neg_index_no_headers_withindex() {
     test_index("slice_neg_index_no_headers_withindex", -2, "d", false, true);
 }
+
+use std::{sync::mpsc, thread};
+
+use actix_web::{dev::ServerHandle, rt, web, App, HttpResponse, HttpServer};
+
+// convenience macros for changing remote input test ip/port to use
+macro_rules! remote_input_server {
+    () => {
+        "127.0.0.1:8082"
+    };
+}
+
+macro_rules! remote_input_url {
+    ($filename:expr) => {
+        concat!("http://", remote_input_server!(), "/", $filename)
+    };
+}
+
+/// serves files from resources/test, standing in for a remote http server
+async fn serve_test_resource(filename: web::Path<String>) -> HttpResponse {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("resources/test/");
+    path.push(filename.into_inner());
+    match std::fs::read(path) {
+        Ok(contents) => HttpResponse::Ok().body(contents),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+async fn run_remote_input_webserver(tx: mpsc::Sender<ServerHandle>) -> std::io::Result<()> {
+    let server = HttpServer::new(|| {
+        App::new().service(web::resource("/{filename}").route(web::get().to(serve_test_resource)))
+    })
+    .bind(remote_input_server!())?
+    .run();
+
+    let _ = tx.send(server.handle());
+
+    server.await
+}
+
+#[test]
+fn slice_remote_input() {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || rt::System::new().block_on(run_remote_input_webserver(tx)));
+    let server_handle = rx.recv().expect("test webserver error");
+
+    let wrk = Workdir::new("slice_remote_input");
+    let test_file = wrk.load_test_file("boston311-100.csv");
+
+    let mut cmd = wrk.command("slice");
+    cmd.args(["--start", "10", "--len", "5"])
+        .arg(remote_input_url!("boston311-100.csv"));
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    let mut cmd = wrk.command("slice");
+    cmd.args(["--start", "10", "--len", "5"]).arg(test_file);
+    let expected: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    assert_eq!(got, expected);
+
+    // snappy compressed remote inputs are detected by their URL suffix
+    let mut cmd = wrk.command("slice");
+    cmd.args(["--start", "10", "--len", "5"])
+        .arg(remote_input_url!("boston311-100.csv.sz"));
+    let got_snappy: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    assert_eq!(got_snappy, expected);
+
+    // HTTP error responses are not parsed as CSV
+    let mut cmd = wrk.command("slice");
+    cmd.args(["--len", "5"])
+        .arg(remote_input_url!("doesnotexist.csv"));
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        concat!(
+            "network error: Cannot download ",
+            remote_input_url!("doesnotexist.csv"),
+            ": HTTP status 404 Not Found.\n"
+        )
+    );
+    wrk.assert_err(&mut cmd);
+
+    rt::System::new().block_on(server_handle.stop(true));
+}
+
+#[test]
+fn slice_remote_input_notfound() {
+    let wrk = Workdir::new("slice_remote_input_notfound");
+
+    // nothing is listening on this port
+    let mut cmd = wrk.command("slice");
+    cmd.args(["--len", "5"])
+        .arg("http://127.0.0.1:8083/boston311-100.csv");
+
+    // the download error is returned when the input is read
+    let got = wrk.output_stderr(&mut cmd);
+    assert!(got.starts_with("network error: "), "{got}");
+    wrk.assert_err(&mut cmd);
+}
@@ -516,6 +516,95 @@ fn sort_random_secure() {
     assert_eq!(got, expected);
 }
 
+fn sort_spec_data() -> Vec<Vec<String>> {
+    vec![
+        svec!["country", "revenue", "created", "version"],
+        svec!["US", "100", "2023-02-01", "1.10.0"],
+        svec!["CA", "9", "2023-01-15", "1.9.2"],
+        svec!["US", "20", "01/03/2023", "1.2.0"],
+        svec!["CA", "50", "2022-12-31", "1.10.1"],
+        svec!["US", "20", "2023-01-02", "1.9.10"],
+    ]
+}
+
+#[test]
+fn sort_by_spec() {
+    let wrk = Workdir::new("sort_by_spec");
+    wrk.create("in.csv", sort_spec_data());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--by", "country:asc,revenue:desc:n,created:asc:date"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "revenue", "created", "version"],
+        svec!["CA", "50", "2022-12-31", "1.10.1"],
+        svec!["CA", "9", "2023-01-15", "1.9.2"],
+        svec!["US", "100", "2023-02-01", "1.10.0"],
+        svec!["US", "20", "2023-01-02", "1.9.10"],
+        svec!["US", "20", "01/03/2023", "1.2.0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_by_spec_natural() {
+    let wrk = Workdir::new("sort_by_spec_natural");
+    wrk.create("in.csv", sort_spec_data());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--by", "4:desc:natural"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got: Vec<String> = got.into_iter().map(|row| row[3].clone()).collect();
+    assert_eq!(
+        got,
+        svec!["version", "1.10.1", "1.10.0", "1.9.10", "1.9.2", "1.2.0"]
+    );
+}
+
+#[test]
+fn sort_by_spec_unique() {
+    let wrk = Workdir::new("sort_by_spec_unique");
+    wrk.create("in.csv", sort_spec_data());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--by", "country:i"])
+        .arg("--unique")
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "revenue", "created", "version"],
+        svec!["CA", "9", "2023-01-15", "1.9.2"],
+        svec!["US", "100", "2023-02-01", "1.10.0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_by_spec_invalid_column() {
+    let wrk = Workdir::new("sort_by_spec_invalid_column");
+    wrk.create("in.csv", sort_spec_data());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--by", "region:desc"]).arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn sort_by_spec_with_numeric() {
+    let wrk = Workdir::new("sort_by_spec_with_numeric");
+    wrk.create("in.csv", sort_spec_data());
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--by", "revenue"]).arg("--numeric").arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
 /// Order `a` and `b` lexicographically using `Ord`
 pub fn iter_cmp<A, L, R>(mut a: L, mut b: R) -> cmp::Ordering
 where
@@ -226,3 +226,27 @@ fn sortcheck_simple_all_json_progressbar() {
     );
     wrk.assert_err(&mut cmd);
 }
+
+#[test]
+fn sortcheck_by_spec() {
+    let wrk = Workdir::new("sortcheck_by_spec");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["country", "revenue"],
+            svec!["CA", "50"],
+            svec!["CA", "9"],
+            svec!["US", "100"],
+            svec!["US", "20"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sortcheck");
+    cmd.args(["--by", "country,revenue:desc:n"]).arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    // as strings, "9" > "50"
+    let mut cmd = wrk.command("sortcheck");
+    cmd.args(["--by", "country,revenue:desc"]).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -116,6 +116,73 @@ sqlp_test!(
     }
 );
 
+#[test]
+fn sqlp_stats_cache_dtypes() {
+    let wrk = Workdir::new("sqlp_stats_cache_dtypes");
+    wrk.create(
+        "codes.csv",
+        vec![
+            svec!["code", "amount"],
+            svec!["01", "1"],
+            svec!["02", "2.5"],
+            svec!["abc", "3"],
+        ],
+    );
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("codes.csv").args(["--cache-threshold", "1"]);
+    wrk.assert_success(&mut cmd);
+
+    // only inferring from the first row would make code an integer column
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("codes.csv")
+        .arg("select code, amount from codes where code <> 'abc'")
+        .args(["--infer-len", "1"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["code", "amount"],
+        svec!["01", "1.0"],
+        svec!["02", "2.5"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("codes.csv")
+        .arg("select code, amount from codes where code <> 'abc'")
+        .args(["--infer-len", "1"])
+        .args(["--stats-mode", "none"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn sqlp_stats_cache_try_parsedates() {
+    let wrk = Workdir::new("sqlp_stats_cache_try_parsedates");
+    wrk.create(
+        "events.csv",
+        vec![
+            svec!["event", "dt"],
+            svec!["a", "2023-01-15"],
+            svec!["b", "2023-02-20"],
+        ],
+    );
+
+    // without --infer-dates, the stats type the dt column as a String
+    let mut cmd = wrk.command("stats");
+    cmd.arg("events.csv").args(["--cache-threshold", "1"]);
+    wrk.assert_success(&mut cmd);
+
+    // which must not stop --try-parsedates from parsing it as a Date
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("events.csv")
+        .arg("select event, DATE_PART(dt, 'month') as month from events")
+        .arg("--try-parsedates");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["event", "month"], svec!["a", "1"], svec!["b", "2"]];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn sqlp_join_cross() {
     let wrk = Workdir::new("join_cross");
@@ -1123,6 +1123,354 @@ fn stats_leading_zero_handling() {
     assert_eq!(got, expected);
 }
 
+#[test]
+#[cfg(feature = "polars")]
+fn stats_parquet_input() {
+    let wrk = Workdir::new("stats_parquet_input");
+
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["col1", "col2", "col3"],
+            svec!["1", "4321", "a"],
+            svec!["2", "3210", "b"],
+            svec!["3", "2101", "c"],
+        ],
+    );
+    let mut cmd = wrk.command("sqlp");
+    cmd.arg("data.csv")
+        .arg("select * from data")
+        .args(["--format", "parquet"])
+        .args(["--output", "data.parquet"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--typesonly").arg("data.parquet");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "type"],
+        svec!["col1", "Integer"],
+        svec!["col2", "Integer"],
+        svec!["col3", "String"],
+    ];
+    assert_eq!(got, expected);
+}
+
+fn percentile_data(wrk: &Workdir) {
+    let mut rows = vec![svec!["n", "s"]];
+    for i in 1..=10 {
+        rows.push(vec![i.to_string(), format!("s{i}")]);
+    }
+    wrk.create("data.csv", rows);
+}
+
+// only keep the field & the given stats columns
+fn pick_stats_columns(rows: &[Vec<String>], columns: &[&str]) -> Vec<Vec<String>> {
+    let idxs: Vec<usize> = std::iter::once("field")
+        .chain(columns.iter().copied())
+        .map(|col| rows[0].iter().position(|h| h == col).unwrap())
+        .collect();
+    rows.iter()
+        .map(|row| idxs.iter().map(|&i| row[i].clone()).collect())
+        .collect()
+}
+
+#[test]
+fn stats_percentiles_exact() {
+    let wrk = Workdir::new("stats_percentiles_exact");
+    percentile_data(&wrk);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .arg("--median")
+        .args(["--percentiles", "10,50,90"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    // the percentile columns are the last ones
+    assert_eq!(got[0][got[0].len() - 3..], ["p10", "p50", "p90"]);
+    let got = pick_stats_columns(&got, &["median", "p10", "p50", "p90"]);
+    let expected = vec![
+        svec!["field", "median", "p10", "p50", "p90"],
+        svec!["n", "5.5", "1.9", "5.5", "9.1"],
+        svec!["s", "", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_percentiles_exact_quartiles_mad() {
+    let wrk = Workdir::new("stats_percentiles_exact_quartiles_mad");
+    percentile_data(&wrk);
+    let columns = ["mad", "q1", "q2_median", "q3", "iqr"];
+
+    // the MAD & quartiles are the same whether or not the values
+    // are only kept in the --percentiles distribution
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--mad", "--quartiles"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = pick_stats_columns(&got, &columns);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .args(["--mad", "--quartiles"])
+        .args(["--percentiles", "50"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(pick_stats_columns(&got, &columns), expected);
+    assert_eq!(pick_stats_columns(&got, &["p50"])[1], ["n", "5.5"]);
+}
+
+#[test]
+fn stats_percentiles_approx() {
+    let wrk = Workdir::new("stats_percentiles_approx");
+    percentile_data(&wrk);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--percentiles", "0,50,100"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_stats_columns(&got, &["p0", "p50", "p100"]);
+    let expected = vec![
+        svec!["field", "p0", "p50", "p100"],
+        svec!["n", "1", "5.5", "10"],
+        svec!["s", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_percentiles_invalid() {
+    let wrk = Workdir::new("stats_percentiles_invalid");
+    percentile_data(&wrk);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--percentiles", "50,101"]);
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_percentiles_duplicate() {
+    let wrk = Workdir::new("stats_percentiles_duplicate");
+    percentile_data(&wrk);
+
+    // 50 & 50.0 would both compute the same percentile
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").args(["--percentiles", "25,50,50.0"]);
+
+    let got = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: Duplicate percentile: 50.0. Percentiles can only be specified once.\n"
+    );
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_histogram() {
+    let wrk = Workdir::new("stats_histogram");
+    percentile_data(&wrk);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .arg("--median")
+        .args(["--histogram", "3"]);
+    wrk.assert_success(&mut cmd);
+
+    let histograms: serde_json::Value =
+        serde_json::from_str(&wrk.read_to_string("data.stats.histogram.json")).unwrap();
+    // the String column has no histogram
+    let expected = serde_json::json!([{
+        "field": "n",
+        "type": "Integer",
+        "exact": true,
+        "min": 1,
+        "max": 10,
+        "bins": [
+            {"lower": 1, "upper": 4, "count": 3},
+            {"lower": 4, "upper": 7, "count": 3},
+            {"lower": 7, "upper": 10, "count": 4},
+        ],
+    }]);
+    assert_eq!(histograms, expected);
+}
+
+#[test]
+fn stats_approx() {
+    let wrk = Workdir::new("stats_approx");
+    percentile_data(&wrk);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .arg("--approx")
+        .arg("--median")
+        .arg("--mad")
+        .arg("--cardinality")
+        .args(["--cache-threshold", "1"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_stats_columns(&got, &["median", "mad", "cardinality"]);
+    let expected = vec![
+        svec!["field", "median", "mad", "cardinality"],
+        svec!["n", "5.5", "2.5", "10"],
+        svec!["s", "", "", "10"],
+    ];
+    assert_eq!(got, expected);
+
+    // the approximated columns are flagged in the stats args
+    let stats_args: serde_json::Value =
+        serde_json::from_str(&wrk.read_to_string("data.stats.csv.json")).unwrap();
+    assert_eq!(
+        stats_args["approx_columns"],
+        serde_json::json!(["median", "mad", "cardinality"])
+    );
+}
+
+#[test]
+fn stats_approx_quartiles_parallel() {
+    let wrk = Workdir::new("stats_approx_quartiles_parallel");
+    percentile_data(&wrk);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .arg("--approx")
+        .arg("--quartiles")
+        .arg("--cardinality");
+    let expected: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_stats_columns(&expected, &["q1", "q2_median", "q3"]);
+    assert_eq!(got[1], svec!["n", "3", "5.5", "8"]);
+
+    // the sketches of the parallel chunks are merged
+    let mut cmd = wrk.command("index");
+    cmd.arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv")
+        .arg("--approx")
+        .arg("--quartiles")
+        .arg("--cardinality")
+        .args(["--jobs", "2"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_approx_cardinality_large() {
+    let wrk = Workdir::new("stats_approx_cardinality_large");
+    let mut rows = vec![svec!["id"]];
+    for i in 0..20_000 {
+        rows.push(vec![format!("id{i}")]);
+    }
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").arg("--approx").arg("--cardinality");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_stats_columns(&got, &["cardinality"]);
+    let cardinality: f64 = got[1][1].parse().unwrap();
+    // HyperLogLog has a standard error of ~0.8%
+    assert!((cardinality - 20_000.0).abs() < 20_000.0 * 0.03);
+}
+
+fn quality_data(wrk: &Workdir) {
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["code", "name", "n"],
+            svec!["AB-12", "Alice", "1"],
+            svec!["CD-34", " bob", "2"],
+            svec!["EF-56", "Carol ", "3"],
+            svec!["GHI-7", "dAve", "100"],
+        ],
+    );
+}
+
+#[test]
+fn stats_quality() {
+    let wrk = Workdir::new("stats_quality");
+    quality_data(&wrk);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").arg("--quality");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_stats_columns(
+        &got,
+        &[
+            "whitespace_padded",
+            "mixed_case",
+            "sort_order",
+            "top_patterns",
+        ],
+    );
+    let expected = vec![
+        svec![
+            "field",
+            "whitespace_padded",
+            "mixed_case",
+            "sort_order",
+            "top_patterns"
+        ],
+        svec!["code", "0", "0", "Ascending", "AA-99:3, AAA-9:1"],
+        svec!["name", "2", "3", "Unsorted", " aaa:1, Aaaaa:1, Aaaaa :1"],
+        svec!["n", "0", "0", "Ascending", "9:3, 999:1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_quality_outside_fences() {
+    let wrk = Workdir::new("stats_quality_outside_fences");
+    let mut rows = vec![svec!["n"]];
+    for i in 1..=9 {
+        rows.push(vec![i.to_string()]);
+    }
+    rows.push(svec!["100"]);
+    wrk.create("data.csv", rows);
+
+    // with --quartiles, the fences are computed exactly
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").arg("--quality").arg("--quartiles");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_stats_columns(&got, &["outside_inner_fences", "outside_outer_fences"]);
+    let expected = vec![
+        svec!["field", "outside_inner_fences", "outside_outer_fences"],
+        svec!["n", "1", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    // otherwise, they're approximated
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").arg("--quality");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = pick_stats_columns(&got, &["outside_inner_fences", "outside_outer_fences"]);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_quality_parallel() {
+    let wrk = Workdir::new("stats_quality_parallel");
+    quality_data(&wrk);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").arg("--quality");
+    let expected: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    // the chunks are merged in order, so the sort order is the same
+    let mut cmd = wrk.command("index");
+    cmd.arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("data.csv").arg("--quality").args(["--jobs", "2"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
 mod stats_infer_nothing {
     // Only test CSV data with headers.
     // Empty CSV data with no headers won't produce any statistical analysis.
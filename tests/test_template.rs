@@ -0,0 +1,111 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "amount", "due date"],
+        svec!["Alice", "1234.5", "2024-01-15"],
+        svec!["Bob", "99", "2024-03-01"],
+        svec!["O'Brien", "", ""],
+    ]
+}
+
+#[test]
+fn template_inline() {
+    let wrk = Workdir::new("template_inline");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("template");
+    cmd.arg("--template")
+        .arg("{{ row_number }}. {{ name }} owes {{ amount|format_number(2, \",\") }}")
+        .arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "1. Alice owes 1,234.50\n2. Bob owes 99.00\n3. O'Brien owes ";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn template_filters() {
+    let wrk = Workdir::new("template_filters");
+    wrk.create("data.csv", data());
+    wrk.create_from_string(
+        "insert.sql.j2",
+        "INSERT INTO t VALUES ({{ name|sql_quote }}, {{ row[\"due date\"]|sql_quote }}, '{{ \
+         row[\"due date\"]|format_date(\"%B %d, %Y\") }}');",
+    );
+
+    let mut cmd = wrk.command("template");
+    cmd.args(["--template-file", "insert.sql.j2", "data.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+INSERT INTO t VALUES ('Alice', '2024-01-15', 'January 15, 2024');
+INSERT INTO t VALUES ('Bob', '2024-03-01', 'March 01, 2024');
+INSERT INTO t VALUES ('O''Brien', NULL, '');";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn template_conditionals() {
+    let wrk = Workdir::new("template_conditionals");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("template");
+    cmd.arg("--template")
+        .arg(
+            "{% if amount and amount|float > 100 %}{{ name }}: large{% else %}{{ name }}: small{% \
+             endif %}",
+        )
+        .arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "Alice: large\nBob: small\nO'Brien: small");
+}
+
+#[test]
+fn template_whole_file() {
+    let wrk = Workdir::new("template_whole_file");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("template");
+    cmd.arg("--whole-file")
+        .arg("--template")
+        .arg("{{ headers|join(\",\") }}:{% for r in rows %} {{ r.name }}{% endfor %}")
+        .arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "name,amount,due date: Alice Bob O'Brien");
+}
+
+#[test]
+fn template_outdir() {
+    let wrk = Workdir::new("template_outdir");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("template");
+    cmd.args(["--template", "Dear {{ name }},"])
+        .args(["--outdir", "letters", "--filename", "letter_{}.txt"])
+        .arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    assert_eq!(wrk.read_to_string("letters/letter_1.txt"), "Dear Alice,\n");
+    assert_eq!(wrk.read_to_string("letters/letter_2.txt"), "Dear Bob,\n");
+    assert_eq!(
+        wrk.read_to_string("letters/letter_3.txt"),
+        "Dear O'Brien,\n"
+    );
+}
+
+#[test]
+fn template_invalid() {
+    let wrk = Workdir::new("template_invalid");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("template");
+    cmd.args(["--template", "{% if name %}unclosed", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("template");
+    cmd.args(["--template", "{{ name|format_number }}", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
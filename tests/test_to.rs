@@ -460,6 +460,122 @@ state       string      string"#
     // TODO: check that the parquet files are valid and contain the correct data
 }
 
+#[test]
+fn to_xlsx_typed() {
+    let wrk = Workdir::new("to_xlsx_typed");
+    wrk.create(
+        "orders.csv",
+        vec![
+            svec!["id", "amount", "ordered", "note"],
+            svec!["007", "12.5", "2024-01-15", "rush"],
+            svec!["12", "3", "2024-03-01", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["xlsx", "orders.xlsx", "orders.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.starts_with("Table 'orders' (2 rows)"));
+    // stats isn't cached next to the input
+    assert!(!wrk.path("orders.stats.csv").exists());
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["orders.xlsx", "--date-format", "%d/%m/%Y"]);
+
+    // dates are real dates, so they're formatted with --date-format,
+    // and leading zeros are kept
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "amount", "ordered", "note"],
+        svec!["007", "12.5", "15/01/2024", "rush"],
+        svec!["12", "3", "01/03/2024", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn to_xlsx_stats_cache_without_dates() {
+    let wrk = Workdir::new("to_xlsx_stats_cache_without_dates");
+    wrk.create(
+        "orders.csv",
+        vec![
+            svec!["amount", "ordered"],
+            svec!["12.5", "2024-01-15"],
+            svec!["3", "2024-03-01"],
+        ],
+    );
+
+    // a stats cache without date inference is not used
+    let mut cmd = wrk.command("stats");
+    cmd.arg("orders.csv").args(["--cache-threshold", "1"]);
+    wrk.assert_success(&mut cmd);
+    assert!(wrk.path("orders.stats.csv").exists());
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["xlsx", "orders.xlsx", "orders.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["orders.xlsx", "--date-format", "%d/%m/%Y"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["amount", "ordered"],
+        svec!["12.5", "15/01/2024"],
+        svec!["3", "01/03/2024"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn to_xlsx_sheet_names() {
+    let wrk = Workdir::new("to_xlsx_sheet_names");
+    let data = vec![svec!["region", "sales"], svec!["northeast", "100"]];
+    wrk.create("quarterly sales report for the northeast.csv", data.clone());
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["xlsx", "report.xlsx"])
+        .arg("quarterly sales report for the northeast.csv");
+    wrk.assert_success(&mut cmd);
+
+    // truncated to 31 characters at the last separator
+    let mut cmd = wrk.command("excel");
+    cmd.args(["report.xlsx", "--sheet", "quarterly sales report for the"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data);
+}
+
+#[test]
+fn to_ods() {
+    let wrk = Workdir::new("to_ods");
+    let cities = vec![
+        svec!["city", "population"],
+        svec!["Boston", "650706"],
+        svec!["Buffalo", "276807"],
+    ];
+    let places = vec![svec!["place", "rating"], svec!["Logan Airport", "4.5"]];
+    wrk.create("cities.csv", cities.clone());
+    wrk.create("places.csv", places.clone());
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["ods", "--quiet", "data.ods", "cities.csv", "places.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["data.ods", "--sheet", "cities"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, cities);
+
+    let mut cmd = wrk.command("excel");
+    cmd.args(["data.ods", "--sheet", "places"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, places);
+}
+
 #[test]
 #[ignore = "Testing postgres support requires a running, properly configured postgres server, \
             which is not available on CI"]
@@ -549,3 +665,130 @@ state       string      string"#
         ]
     );
 }
+
+#[test]
+fn to_html() {
+    let wrk = Workdir::new("to_html");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name", "note"],
+            svec!["Ann", "a < b & c"],
+            svec!["Bob", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["html", "-", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+</head>
+<body>
+<table>
+<thead>
+<tr><th>name</th><th>note</th></tr>
+</thead>
+<tbody>
+<tr><td>Ann</td><td>a &lt; b &amp; c</td></tr>
+<tr><td>Bob</td><td></td></tr>
+</tbody>
+</table>
+</body>
+</html>"#;
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn to_html_fragment() {
+    let wrk = Workdir::new("to_html_fragment");
+    wrk.create("cities.csv", vec![svec!["city"], svec!["Boston"]]);
+    wrk.create("places.csv", vec![svec!["place"], svec!["Logan Airport"]]);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["html", "--fragment", "tables.html"])
+        .args(["cities.csv", "places.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let got = wrk.read_to_string("tables.html");
+    let expected = "<table>
+<caption>cities</caption>
+<thead>
+<tr><th>city</th></tr>
+</thead>
+<tbody>
+<tr><td>Boston</td></tr>
+</tbody>
+</table>
+<table>
+<caption>places</caption>
+<thead>
+<tr><th>place</th></tr>
+</thead>
+<tbody>
+<tr><td>Logan Airport</td></tr>
+</tbody>
+</table>
+";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn to_xml() {
+    let wrk = Workdir::new("to_xml");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["title", "due date", "1st"],
+            svec!["Dune & Emma", "2024-01-15", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["xml", "--root", "books", "--row", "book", "-", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<books>
+  <book>
+    <title>Dune &amp; Emma</title>
+    <due_date>2024-01-15</due_date>
+    <_1st/>
+  </book>
+</books>"#;
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn to_xml_roundtrip() {
+    let wrk = Workdir::new("to_xml_roundtrip");
+    let cities = vec![
+        svec!["city", "state"],
+        svec!["Boston", "MA"],
+        svec!["New York", ""],
+        svec!["Buffalo", "NY"],
+    ];
+    let places = vec![svec!["city", "place"], svec!["Boston", "Logan Airport"]];
+    wrk.create("cities.csv", cities);
+    wrk.create("places.csv", places);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["xml", "data.xml", "cities.csv", "places.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--element", "/data/table/row", "data.xml"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state", "place"],
+        svec!["Boston", "MA", ""],
+        svec!["New York", "", ""],
+        svec!["Buffalo", "NY", ""],
+        svec!["Boston", "", "Logan Airport"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -448,3 +448,141 @@ fn tojsonl_issue_1649_false_positive_tf_3recs() {
 
     assert_eq!(got, expected);
 }
+
+#[test]
+#[serial]
+fn tojsonl_nested_paths() {
+    let wrk = Workdir::new("tojsonl_nested_paths");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec![
+                "id",
+                "address.city",
+                "address.zip",
+                "tags[0]",
+                "tags[1]",
+                "active"
+            ],
+            svec!["1", "Oslo", "N-0150", "a", "b", "true"],
+            svec!["2", "Bergen", "", "c", "", "false"],
+            svec!["3", "Tromso", "N-9008", "", "", "true"],
+        ],
+    );
+
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("--nested").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"id":1,"address":{"city":"Oslo","zip":"N-0150"},"tags":["a","b"],"active":true}
+{"id":2,"address":{"city":"Bergen","zip":null},"tags":["c",null],"active":false}
+{"id":3,"address":{"city":"Tromso","zip":"N-9008"},"tags":[null,null],"active":true}"#;
+
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[serial]
+fn tojsonl_nested_conflict() {
+    let wrk = Workdir::new("tojsonl_nested_conflict");
+    wrk.create(
+        "in.csv",
+        vec![svec!["a", "a.b"], svec!["1", "2"], svec!["3", "4"]],
+    );
+
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("--nested").arg("in.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+#[serial]
+fn tojsonl_schema() {
+    let wrk = Workdir::new("tojsonl_schema");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "amount", "flag", "note"],
+            svec!["007", "1.5", "yes", "x"],
+            svec!["008", "", "no", ""],
+        ],
+    );
+    wrk.create_from_string(
+        "in.schema.json",
+        r#"{"properties": {
+            "id": {"type": ["string"]},
+            "amount": {"type": ["number", "null"]},
+            "flag": {"type": "boolean"}
+        }}"#,
+    );
+
+    let mut cmd = wrk.command("tojsonl");
+    cmd.args(["--schema", "in.schema.json", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"id":"007","amount":1.5,"flag":true,"note":"x"}
+{"id":"008","amount":null,"flag":false,"note":null}"#;
+
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[serial]
+fn tojsonl_schema_invalid_number() {
+    let wrk = Workdir::new("tojsonl_schema_invalid_number");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "amount"],
+            svec!["1", "-2.5e3"],
+            svec!["2", "abc"],
+            svec!["3", "007"],
+        ],
+    );
+    wrk.create_from_string(
+        "in.schema.json",
+        r#"{"properties": {
+            "id": {"type": "integer"},
+            "amount": {"type": "number"}
+        }}"#,
+    );
+
+    // values that aren't numbers are written as strings, so the JSON stays valid
+    let mut cmd = wrk.command("tojsonl");
+    cmd.args(["--schema", "in.schema.json", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"{"id":1,"amount":-2.5e3}
+{"id":2,"amount":"abc"}
+{"id":3,"amount":"007"}"#;
+
+    assert_eq!(got, expected);
+}
+
+#[test]
+#[serial]
+fn tojsonl_array() {
+    let wrk = Workdir::new("tojsonl_array");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "Fanuel"],
+            svec!["2", "Travis"],
+            svec!["3", "Joel"],
+        ],
+    );
+
+    let mut cmd = wrk.command("tojsonl");
+    cmd.arg("--array").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"[
+{"id":1,"name":"Fanuel"},
+{"id":2,"name":"Travis"},
+{"id":3,"name":"Joel"}
+]"#;
+
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,163 @@
+use crate::workdir::Workdir;
+
+fn page() -> &'static str {
+    r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>Contacts</title></head>
+<body>
+<p>Our contacts:<br></p>
+<table>
+  <tr><th>Name</th><th colspan=2>Contact</th></tr>
+  <tr><td>Ann&nbsp;Lee<td>ann@example.com<td>555-1234</tr>
+  <tr><td>Bob <b>Smith</b></td><td>bob@<br>example.com</td></tr>
+</table>
+<table><tr><td>AT&T</td></tr></table>
+</body></html>"#
+}
+
+#[test]
+fn xml_element() {
+    let wrk = Workdir::new("xml_element");
+    wrk.create_from_string(
+        "catalog.xml",
+        r#"<?xml version="1.0"?>
+<catalog>
+  <book id="b1"><title>Dune</title><price currency="USD">9.99</price></book>
+  <book id="b2"><title>Emma</title><author>Jane Austen</author></book>
+</catalog>"#,
+    );
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--element", "book", "catalog.xml"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "title", "price.currency", "price", "author"],
+        svec!["b1", "Dune", "USD", "9.99", ""],
+        svec!["b2", "Emma", "", "", "Jane Austen"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn xml_anchored_element() {
+    let wrk = Workdir::new("xml_anchored_element");
+    wrk.create_from_string(
+        "feed.xml",
+        "<feed><item><name>a</name></item><group><item><name>b</name></item></group></feed>",
+    );
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--element", "item", "feed.xml"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["name"], svec!["a"], svec!["b"]]);
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--element", "/feed/item", "feed.xml"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["name"], svec!["a"]]);
+}
+
+#[test]
+fn xml_attr_prefix_join() {
+    let wrk = Workdir::new("xml_attr_prefix_join");
+    wrk.create_from_string(
+        "data.xml",
+        r#"<x:rows xmlns:x="urn:x">
+  <x:row id="1"><tag>a</tag><tag>b</tag>text<note><![CDATA[1 < 2]]></note></x:row>
+  <x:row id="2"/>
+</x:rows>"#,
+    );
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["-e", "row", "--attr-prefix", "@", "--join", ";", "-s", "_"])
+        .arg("data.xml");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["@id", "tag", "row", "note"],
+        svec!["1", "a;b", "text", "1 < 2"],
+        svec!["2", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn xml_element_required() {
+    let wrk = Workdir::new("xml_element_required");
+    wrk.create_from_string("data.xml", "<rows><row>1</row></rows>");
+
+    let mut cmd = wrk.command("xml");
+    cmd.arg("data.xml");
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(
+        got,
+        "usage error: --element is required unless --html is set.\n"
+    );
+}
+
+#[test]
+fn xml_invalid() {
+    let wrk = Workdir::new("xml_invalid");
+    wrk.create_from_string("data.xml", "<rows><row>1</rows>");
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--element", "row", "data.xml"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn xml_html() {
+    let wrk = Workdir::new("xml_html");
+    wrk.create_from_string("page.html", page());
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--html", "page.html"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["Name", "Contact", ""],
+        svec!["Ann Lee", "ann@example.com", "555-1234"],
+        svec!["Bob Smith", "bob@ example.com", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn xml_html_table() {
+    let wrk = Workdir::new("xml_html_table");
+    wrk.create_from_string("page.html", page());
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--html", "--table", "2", "page.html"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["AT&T"]]);
+
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--html", "--table", "3", "page.html"]);
+
+    let got: String = wrk.output_stderr(&mut cmd);
+    assert_eq!(got, "Table 3 not found. The page has 2 table(s).\n");
+}
+
+#[test]
+fn xml_html_colspan_clamped() {
+    let wrk = Workdir::new("xml_html_colspan_clamped");
+    wrk.create_from_string(
+        "page.html",
+        "<table><tr><td colspan=1000000>wide</td></tr><tr><td>a</td><td>b</td></tr></table>",
+    );
+
+    // colspan is clamped to the HTML maximum of 1000
+    let mut cmd = wrk.command("xml");
+    cmd.args(["--html", "page.html"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 2);
+    assert_eq!(got[0].len(), 1000);
+    assert_eq!(got[0][0], "wide");
+    assert_eq!(got[1][..2], svec!["a", "b"]);
+}
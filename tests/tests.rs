@@ -40,6 +40,7 @@ mod test_behead;
 mod test_cat;
 mod test_combos;
 mod test_comments;
+mod test_compress;
 mod test_count;
 mod test_datefmt;
 mod test_dedup;
@@ -67,8 +68,11 @@ mod test_fmt;
 #[cfg(all(feature = "foreach", target_family = "unix"))]
 mod test_foreach;
 mod test_frequency;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_fwf;
 #[cfg(all(feature = "feature_capable", feature = "geocode"))]
 mod test_geocode;
+mod test_groupby;
 mod test_headers;
 mod test_index;
 mod test_input;
@@ -77,11 +81,17 @@ mod test_join;
 #[cfg(feature = "polars")]
 mod test_joinp;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_json;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_jsonl;
+#[cfg(all(feature = "lens", feature = "feature_capable"))]
+mod test_lens;
 #[cfg(feature = "luau")]
 mod test_luau;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_partition;
+#[cfg(feature = "polars")]
+mod test_pivot;
 mod test_pseudo;
 #[cfg(feature = "python")]
 mod test_py;
@@ -107,6 +117,8 @@ mod test_sqlp;
 mod test_stats;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_table;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_template;
 #[cfg(all(feature = "to", feature = "feature_capable"))]
 mod test_to;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
@@ -114,6 +126,8 @@ mod test_tojsonl;
 #[cfg(any(feature = "feature_capable", feature = "lite"))]
 mod test_transpose;
 mod test_validate;
+#[cfg(any(feature = "feature_capable", feature = "lite"))]
+mod test_xml;
 
 fn qcheck<T: Testable>(p: T) {
     env::set_var("QSV_SKIPUTF8_CHECK", "1");